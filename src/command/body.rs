@@ -0,0 +1,56 @@
+use anyhow::{Context, anyhow};
+use clap::{ArgAction, Args};
+
+use crate::config::Config;
+
+#[derive(Args, Debug)]
+#[command(
+	about = "Print a JSON skeleton of the request body of an endpoint",
+	long_about = r#"
+Print a JSON request body for an endpoint, with its required fields filled in
+with values of their types. Values are taken from the examples of the spec,
+then from its defaults and allowed values, and are placeholders otherwise.
+
+Examples:
+  httpie-oapi body petstore POST /pet > body.json
+  http POST https://petstore3.swagger.io/api/v3/pet < body.json
+  httpie-oapi body petstore POST /pet --all
+"#
+)]
+pub(super) struct BodyCommand {
+	/// Name of the API service
+	#[arg(value_name = "NAME")]
+	name: String,
+
+	/// HTTP method of the endpoint (e.g. `POST`)
+	#[arg(value_name = "METHOD")]
+	method: String,
+
+	/// The API path of the endpoint (e.g. `/users/{id}`, `/users/42` or a URL)
+	#[arg(value_name = "PATH")]
+	path: String,
+
+	/// Include the optional fields as well
+	#[arg(long, action = ArgAction::SetTrue)]
+	all: bool,
+}
+
+impl BodyCommand {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		let api =
+			config.get_api(&self.name).with_context(|| format!("API '{}' not found", self.name))?;
+		let endpoints = api.endpoints(config.is_offline());
+		let method = self.method.to_uppercase();
+		let ep = endpoints
+			.find_method(&api.base_url, &self.method, &self.path)
+			.with_context(|| format!("No {} endpoint matched path '{}'", method, self.path))?;
+		if !ep.has_json_body() {
+			return Err(match ep.form_flag() {
+				Some(flag) => anyhow!("{} {} takes a form body, send it with {}", method, ep.path, flag),
+				None => anyhow!("{} {} has no JSON body", method, ep.path),
+			});
+		}
+		println!("{}", serde_json::to_string_pretty(&ep.example_body(self.all))?);
+		Ok(())
+	}
+}
@@ -0,0 +1,131 @@
+use std::fs;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, anyhow};
+use clap::{Args, Subcommand};
+
+use super::history::format_size;
+use crate::config::Config;
+use crate::openapi::{EndPoints, cache};
+use crate::time::format_age;
+
+#[derive(Subcommand, Debug)]
+pub(super) enum CacheCommands {
+	/// Remove the cached specs and endpoints of one or all APIs, they are
+	/// downloaded again when needed
+	Clear(ClearArgs),
+	/// Remove cache files of APIs that are no longer registered
+	Prune,
+	/// Show the cache sizes, ages, endpoint counts and load times of all APIs,
+	/// slowest to load first
+	Stats,
+}
+
+/// Cache state of one API as reported by `cache stats`
+struct Stats<'a> {
+	name: &'a str,
+	spec_size: Option<u64>,
+	endpoints_size: Option<u64>,
+	/// Seconds since the endpoints cache was written
+	age: Option<u64>,
+	/// Number of endpoints and the time taken to load them from the cache,
+	/// which every completion outside the daemon pays
+	load: Option<(usize, Duration)>,
+}
+
+#[derive(Args, Debug)]
+pub(super) struct ClearArgs {
+	/// Name of the API service, defaults to all APIs
+	#[arg(value_name = "NAME")]
+	name: Option<String>,
+}
+
+impl CacheCommands {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		match self {
+			CacheCommands::Clear(args) => Self::clear(args, config),
+			CacheCommands::Prune => Self::prune(config),
+			CacheCommands::Stats => Self::stats(config),
+		}
+	}
+
+	fn clear(args: &ClearArgs, config: &Config) -> anyhow::Result<()> {
+		match &args.name {
+			Some(name) => {
+				config.get_api(name).ok_or_else(|| anyhow!("API '{}' not found", name))?;
+				cache::remove_caches(name);
+				println!("Cleared the cache of API '{}'", name);
+			}
+			None => {
+				let apis = config.list_apis();
+				for api in &apis {
+					cache::remove_caches(&api.name);
+				}
+				println!("Cleared the caches of {} APIs", apis.len());
+			}
+		}
+		Ok(())
+	}
+
+	fn prune(config: &Config) -> anyhow::Result<()> {
+		let orphans = cache::orphans(|name| config.get_api(name).is_some());
+		for path in &orphans {
+			let result = match path.is_dir() {
+				true => fs::remove_dir_all(path),
+				false => fs::remove_file(path),
+			};
+			result.with_context(|| format!("Failed to remove '{}'", path.display()))?;
+			println!("Removed {}", path.display());
+		}
+		println!("Removed {} orphaned cache files", orphans.len());
+		Ok(())
+	}
+
+	fn stats(config: &Config) -> anyhow::Result<()> {
+		let size = |path| fs::metadata(path).ok().map(|metadata| metadata.len());
+		let mut stats: Vec<_> = config
+			.list_apis()
+			.into_iter()
+			.map(|api| {
+				cache::recover(&api.name);
+				let endpoints_path = Config::get_endpoints_cache_path(&api.name);
+				let started = Instant::now();
+				let load = EndPoints::try_from_json(&endpoints_path)
+					.ok()
+					.map(|endpoints| (endpoints.all().len(), started.elapsed()));
+				Stats {
+					name: &api.name,
+					spec_size: size(Config::get_cache_path(&api.name)),
+					endpoints_size: size(endpoints_path),
+					age: cache::age(&api.name),
+					load,
+				}
+			})
+			.collect();
+		if stats.is_empty() {
+			println!("No APIs registered");
+			return Ok(());
+		}
+		stats.sort_by_key(|stats| std::cmp::Reverse(stats.load.map(|(_, time)| time)));
+
+		let size = |size: Option<u64>| size.map(format_size).unwrap_or_else(|| "-".to_string());
+		println!("NAME\tSPEC\tENDPOINTS CACHE\tREFRESHED\tENDPOINTS\tLOAD TIME");
+		for stats in stats {
+			let refreshed = stats.age.map(|age| format_age(crate::time::now().saturating_sub(age)));
+			let (endpoints, load_time) = match stats.load {
+				Some((count, time)) => (count.to_string(), format!("{:.1}ms", time.as_secs_f64() * 1000.0)),
+				None => ("-".to_string(), "not cached".to_string()),
+			};
+			println!(
+				"{}\t{}\t{}\t{}\t{}\t{}",
+				stats.name,
+				size(stats.spec_size),
+				size(stats.endpoints_size),
+				refreshed.as_deref().unwrap_or("-"),
+				endpoints,
+				load_time
+			);
+		}
+		Ok(())
+	}
+}
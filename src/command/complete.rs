@@ -1,6 +1,25 @@
-use clap::Args;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::process::{Command as Process, Stdio};
 
-use crate::{config::Config, tokens::Tokens};
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	config::Config,
+	history::History,
+	openapi::{
+		ApiSpec, BodyParams, EndPoints, Method, Param, cache, endpoint::EndPoint, param::ParamSource,
+		path_filter::PathFilter,
+	},
+	time::{format_age, parse_duration},
+	tokens::Tokens,
+	values::ValueCache,
+	vars::VarStore,
+};
+
+/// Separators of HTTPie request items, longer ones first where they overlap
+const ITEM_SEPARATORS: [&str; 5] = [":=", "==", "=", ":", "@"];
 
 #[derive(Args, Debug)]
 pub(super) struct CompleteCommand {
@@ -16,6 +35,35 @@ pub(super) struct CompleteCommand {
 	/// the cursor is at "users"
 	#[arg(long, short = 'c', value_name = "CURSOR_POS")]
 	cursor_pos: usize,
+
+	/// Output format of the candidates
+	#[arg(long, value_enum, default_value_t = CompleteFormat::Fish)]
+	format: CompleteFormat,
+
+	/// Characters of the path to type before all paths of an API are listed,
+	/// overrides `completion.min_path_length` of the config file
+	#[arg(long, value_name = "N")]
+	min_path_length: Option<usize>,
+}
+
+/// Settings of a completion request, sent to the daemon along with the line
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(super) struct CompleteOptions {
+	#[serde(default)]
+	format: CompleteFormat,
+	#[serde(default)]
+	min_path_length: Option<usize>,
+	/// Group the suggested APIs are limited to, resolved by the client since
+	/// the daemon does not share its environment
+	#[serde(default)]
+	group: Option<String>,
+	/// Whether only cached endpoints are completed
+	#[serde(default)]
+	pub(super) offline: bool,
+	/// Whether endpoints are only read from the cache, stale caches are still
+	/// refreshed in the background unless `offline` is set
+	#[serde(skip)]
+	pub(super) cache_only: bool,
 }
 
 impl CompleteCommand {
@@ -26,79 +74,577 @@ impl CompleteCommand {
 	/// analyzes the current command line context and suggests appropriate
 	/// completions based on the following rules:
 	///
-	/// 1. If no token contains any base_url, show all available API specs
+	/// 1. If no token contains any base_url, show all available API specs, of the
+	///    active group if one is set (see `Config::active_group`)
 	///    Example: "http " -> shows all registered API base URLs
+	///    On the first positional the HTTP methods are shown as well
+	///    Example: "http G" -> shows base URLs and GET, POST, ...
 	///
 	/// 2. If a token contains a base_url, use that API spec
 	///    Example: "http https://api.example.com" -> uses api.example.com's spec
 	///
 	/// 3. If cursor is on the base_url token, show all paths for that API
 	///    Example: "http https://api.example.com" -> shows all available endpoints
+	///    A `#<tag>` suffix or a `--tag <tag>` option limits the paths to that
+	///    tag, a partial tag shows the tags instead
+	///    Example: "http https://api.example.com#users" -> shows paths tagged users
+	///    The value of a `--tag` option is completed with the tags of the API,
+	///    of all cached APIs when no base_url is typed
+	///    Example: "http --tag " -> shows the tags of all APIs
+	///    A `--regex <regex>` or `--glob <glob>` option limits the paths to
+	///    those it matches
+	///    Example: "http --glob '/v2/**/orders' https://api.example.com"
 	///
 	/// 4. If cursor is not on base_url token, show all parameters for the matched
 	///    path Example: "http https://api.example.com/users " -> shows all
 	///    parameters for /users
 	///
+	/// 5. If cursor is on a parameter assignment (e.g. `page==`), show the
+	///    default and example values declared in the spec, values fetched for
+	///    linked params, the stored variable for path params, and values used
+	///    before with the same URL
+	///
+	/// Body parameters are filtered by the API's body param mode; when body
+	/// flattening is disabled a `--raw` body skeleton is suggested instead.
+	///
 	/// The completion suggestions are formatted for fish shell, with descriptions
 	/// and proper parameter formatting (e.g., query parameters with ==, headers
-	/// with :).
+	/// with :). `--format json` prints them as structured JSON instead.
 	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
-		tracing::info!(
-			"Processing completion request: line={}, cursor_pos={}",
-			self.line,
-			self.cursor_pos
-		);
-		let tokens = Tokens::new(&self.line, self.cursor_pos);
+		let options = CompleteOptions {
+			format: self.format,
+			min_path_length: self.min_path_length,
+			group: config.active_group(),
+			offline: config.is_offline(),
+			cache_only: false,
+		};
+		#[cfg(unix)]
+		if super::daemon::complete(&self.line, self.cursor_pos, options.clone())? {
+			return Ok(());
+		}
+		Self::complete(&self.line, self.cursor_pos, options, config, &mut io::stdout().lock())
+	}
+
+	/// Write the completion candidates for a command line to `out`
+	pub(super) fn complete(
+		line: &str,
+		cursor_pos: usize,
+		options: CompleteOptions,
+		config: &Config,
+		out: &mut impl Write,
+	) -> anyhow::Result<()> {
+		let min_path_length = options.min_path_length.unwrap_or(config.completion.min_path_length);
+		let candidates = Self::candidates(line, cursor_pos, min_path_length, &options, config)?;
+		match options.format {
+			CompleteFormat::Fish => {
+				for candidate in candidates {
+					writeln!(out, "{}\t{}", candidate.value, candidate.description)?;
+				}
+			}
+			CompleteFormat::Json => writeln!(out, "{}", serde_json::to_string(&candidates)?)?,
+		}
+		Ok(())
+	}
+
+	/// Collect the completion candidates for a command line
+	fn candidates(
+		line: &str,
+		cursor_pos: usize,
+		min_path_length: usize,
+		options: &CompleteOptions,
+		config: &Config,
+	) -> anyhow::Result<Vec<Candidate>> {
+		tracing::info!("Processing completion request: line={}, cursor_pos={}", line, cursor_pos);
+		let tokens = Tokens::new(line, cursor_pos);
+		let mut candidates = Vec::new();
 		let apis = config.list_apis();
 		tracing::debug!("Parsed tokens: {:?}", tokens);
 
 		// Step 1: Check if any token contains a base_url
-		let mut matched_api = None;
-		let mut matched_token = None;
-
+		let mut matched = None;
 		for &api in apis.iter() {
 			if let Some(token) = tokens.find_token_starting_with(&api.base_url) {
-				matched_api = Some(api);
-				matched_token = Some(token);
+				matched = Some((api, token, api.base_url.clone()));
 				break;
 			}
 		}
+		// Endpoints served from servers of their own are matched by those,
+		// looked up in the caches once a URL of no API is typed
+		if matched.is_none() && tokens.has_token_starting_with("http") {
+			matched = apis.iter().find_map(|&api| {
+				let endpoints = api.cached_endpoints()?;
+				endpoints
+					.own_base_urls(&api.base_url)
+					.into_iter()
+					.find_map(|base_url| Some((api, tokens.find_token_starting_with(&base_url)?, base_url)))
+			});
+		}
+
+		// The value of a `--tag` option is completed with the tags of the
+		// typed API, of the cached APIs of the active group otherwise
+		if let Some(typed) = tokens.current_option_value("--tag") {
+			let current = tokens.current_token().map_or("", |token| token.text.as_str());
+			let prefix = &current[..current.len() - typed.len()];
+			let endpoints: Vec<_> = match &matched {
+				Some((api, ..)) => vec![api.endpoints(options.offline || options.cache_only)],
+				None => {
+					let apis = config.list_apis_in(options.group.as_deref()).into_iter();
+					apis.filter_map(|api| api.cached_endpoints()).collect()
+				}
+			};
+			let mut tags: Vec<(&str, usize)> = Vec::new();
+			for (tag, count) in endpoints.iter().flat_map(|endpoints| endpoints.tags()) {
+				match tags.iter_mut().find(|(name, _)| *name == tag) {
+					Some((_, total)) => *total += count,
+					None => tags.push((tag, count)),
+				}
+			}
+			tracing::debug!("Cursor is on a --tag value, showing tags");
+			for (tag, count) in tags {
+				candidates.push(Candidate::new(
+					CandidateKind::Tag,
+					format!("{}{}", prefix, tag),
+					&format!("{} endpoints", count),
+				));
+			}
+			return Ok(candidates);
+		}
 
-		// If no base_url is found in any token, show all API specs
-		let (Some(matched_api), Some(matched_token)) = (matched_api, matched_token) else {
+		// If no base_url is found in any token, show all API specs of the
+		// active group, and the methods when completing the first positional
+		// since HTTPie accepts the METHOD before the URL
+		let Some((matched_api, matched_token, base_url)) = matched else {
 			tracing::debug!("No base_url found in tokens, showing all API specs");
-			for api in apis {
-				println!("{}/\t{}", api.base_url, api.name);
+			for api in config.list_apis_in(options.group.as_deref()) {
+				candidates.push(Candidate::new(
+					CandidateKind::Api,
+					format!("{}/", api.base_url),
+					&api.name,
+				));
 			}
-			return Ok(());
+			let positionals = tokens.positionals();
+			let first_positional = match tokens.current_token() {
+				Some(current) => positionals.first().is_none_or(|first| first.start == current.start),
+				None => positionals.is_empty(),
+			};
+			if first_positional {
+				for method in Method::ALL {
+					candidates.push(Candidate::new(
+						CandidateKind::Method,
+						method.to_string(),
+						method.description(),
+					));
+				}
+			}
+			return Ok(candidates);
 		};
 
+		if !options.offline {
+			Self::revalidate(matched_api, config);
+		}
+
 		// Step 2 & 3: Check if cursor is on the base_url token
 		if let Some(current_token) = tokens.current_token() {
 			tracing::debug!("Current token: {}", current_token.text);
-			if current_token.text.starts_with(&matched_api.base_url) {
-				tracing::debug!("Cursor is on base_url token, showing all paths");
-				for ep in matched_api.get_endpoints().all() {
-					println!("{}", ep.fish_complete_format(&matched_api.base_url));
+			if current_token.text.starts_with(&base_url) {
+				let endpoints = matched_api.endpoints(options.offline || options.cache_only);
+				let rest = &current_token.text[base_url.len()..];
+				let tag = rest.strip_prefix('#').or_else(|| tokens.option_value("--tag"));
+				let Some(tag) = tag else {
+					if let Some(filter) = Self::path_filter(&tokens) {
+						tracing::debug!("Showing paths matching {:?}", filter);
+						for ep in endpoints.filter(&filter) {
+							candidates.push(Candidate::endpoint(ep, &matched_api.base_url));
+						}
+						return Ok(candidates);
+					}
+					if rest.trim_start_matches('/').chars().count() < min_path_length {
+						tracing::debug!(
+							"Path is shorter than {} chars, showing most used paths",
+							min_path_length
+						);
+						let top_paths = config.completion.top_paths;
+						for ep in Self::most_used(&endpoints, &matched_api.base_url, top_paths) {
+							candidates.push(Candidate::endpoint(ep, &matched_api.base_url));
+						}
+						return Ok(candidates);
+					}
+					tracing::debug!("Cursor is on base_url token, showing all paths");
+					for ep in endpoints.all() {
+						candidates.push(Candidate::endpoint(ep, &matched_api.base_url));
+					}
+					return Ok(candidates);
+				};
+
+				let tagged = endpoints.with_tag(tag);
+				if tagged.is_empty() && rest.starts_with('#') {
+					tracing::debug!("Cursor is on a partial tag, showing tags");
+					for (name, count) in endpoints.tags() {
+						candidates.push(Candidate::new(
+							CandidateKind::Tag,
+							format!("{}#{}", base_url, name),
+							&format!("{} endpoints", count),
+						));
+					}
+				}
+				tracing::debug!("Showing paths tagged '{}'", tag);
+				for ep in tagged {
+					candidates.push(Candidate::endpoint(ep, &matched_api.base_url));
 				}
-				return Ok(());
+				return Ok(candidates);
 			}
 		}
 
 		// Step 4: Show parameters for the matched path
-		let path =
-			matched_token.text.strip_prefix(&matched_api.base_url).unwrap_or(&matched_token.text);
+		let path = matched_token.text.strip_prefix(&base_url).unwrap_or(&matched_token.text);
 		tracing::info!("Looking for parameters for path: {}", path);
+		let endpoints = matched_api.endpoints(options.offline || options.cache_only);
+
+		// Step 5: Complete values when the cursor is on a param assignment
+		if let Some(current_token) = tokens.current_token() {
+			// Only the operations of the typed path, which share params such as
+			// its path variables
+			let operations = endpoints.find_operations(&matched_api.base_url, &matched_token.text);
+			let mut assigned: Vec<&Param> = Vec::new();
+			for param in operations.into_iter().flat_map(|ep| &ep.params) {
+				let seen =
+					assigned.iter().any(|other| other.name == param.name && other.source == param.source);
+				if !seen && Self::assigns(&current_token.text, param) {
+					assigned.push(param);
+				}
+			}
+			if !assigned.is_empty() {
+				tracing::debug!("Cursor is on a param assignment, showing values");
+				for param in assigned {
+					candidates.extend(Self::value_candidates(matched_api, param, &matched_token.text));
+				}
+				return Ok(candidates);
+			}
+		}
 
-		for ep in matched_api.get_endpoints().filter(path) {
+		for ep in endpoints.with_prefix(path) {
 			tracing::debug!("Found matching endpoint: {}", ep.path);
-			for param in ep.get_params_sort() {
-				if !tokens.has_token_starting_with(&param.httpie_param_format()) {
-					println!("{}", param.fish_complete_format());
+			let body_params = matched_api.body_params_for(&ep.path);
+			for param in ep.get_completion_params(body_params, config.completion.body_depth) {
+				if param.is_repeatable() || !tokens.has_token_starting_with(&param.httpie_param_format()) {
+					candidates.push(Candidate::param(&param));
 				}
 			}
+			if body_params == BodyParams::None
+				&& ep.has_json_body()
+				&& !tokens.has_token_starting_with("--raw")
+			{
+				candidates.push(Candidate::from_fish(CandidateKind::Body, &ep.fish_body_skeleton_format()));
+			}
+			// HTTPie sends fields as JSON unless told to send a form
+			if let Some(flag) = ep.form_flag()
+				&& !["--form", "-f", "--multipart"].iter().any(|flag| tokens.has_token_starting_with(flag))
+			{
+				let description = match flag {
+					"--multipart" => "send a multipart form with file parts",
+					_ => "send the body as a form",
+				};
+				candidates.push(Candidate::new(CandidateKind::Body, flag.to_string(), description));
+			}
 		}
 
-		Ok(())
+		Ok(candidates)
+	}
+
+	/// Refresh the cache of an API in a detached process once it is older than
+	/// `completion.stale_after`, while the stale endpoints are completed
+	fn revalidate(api: &ApiSpec, config: &Config) {
+		let Some(stale_after) = &config.completion.stale_after else {
+			return;
+		};
+		let stale_after = match parse_duration(stale_after) {
+			Ok(stale_after) => stale_after,
+			Err(e) => {
+				tracing::warn!("Invalid completion.stale_after: {:#}", e);
+				return;
+			}
+		};
+		if cache::age(&api.name).is_none_or(|age| age <= stale_after) {
+			return;
+		}
+
+		// Mark the cache fresh first so the next key presses do not start
+		// more refreshes, a failed refresh is retried once it is stale again
+		tracing::info!("Cache of '{}' is stale, refreshing it in the background", api.name);
+		if let Err(e) = cache::touch(&api.name) {
+			tracing::warn!("{:#}", e);
+			return;
+		}
+		let spawned = std::env::current_exe().and_then(|exe| {
+			let mut command = Process::new(exe);
+			command.args(["spec", "refresh", &api.name]);
+			command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+			#[cfg(unix)]
+			std::os::unix::process::CommandExt::process_group(&mut command, 0);
+			command.spawn()
+		});
+		if let Err(e) = spawned {
+			tracing::warn!("Failed to start background refresh of '{}': {}", api.name, e);
+		}
+	}
+
+	/// The path filter of a `--regex` or `--glob` option on the line, invalid
+	/// patterns are ignored
+	fn path_filter(tokens: &Tokens) -> Option<PathFilter> {
+		let filter = match (tokens.option_value("--regex"), tokens.option_value("--glob")) {
+			(Some(regex), _) => PathFilter::regex(regex),
+			(None, Some(glob)) => PathFilter::glob(glob),
+			(None, None) => return None,
+		};
+		filter.inspect_err(|e| tracing::debug!("Ignoring path filter: {:#}", e)).ok()
+	}
+
+	/// Get the endpoints requested most often through `run`, up to `limit`
+	fn most_used<'a>(endpoints: &'a EndPoints, base_url: &str, limit: usize) -> Vec<&'a EndPoint> {
+		let counts = History::call_counts().unwrap_or_else(|e| {
+			tracing::warn!("Failed to load history: {:#}", e);
+			Default::default()
+		});
+		let mut used: Vec<_> = endpoints
+			.all()
+			.into_iter()
+			.filter_map(|ep| Some((ep, *counts.get(&format!("{}{}", ep.base_url(base_url), ep.path))?)))
+			.collect();
+		used.sort_by(|(_, a), (_, b)| b.cmp(a));
+		used.into_iter().take(limit).map(|(ep, _)| ep).collect()
+	}
+
+	/// Whether a request item assigns the param, with its name and its own
+	/// separator: `page==2` assigns the query param `page` but not a body
+	/// field `page`, and `page:=2` neither a string body field nor a header,
+	/// while typed body fields take `:=` as well
+	fn assigns(item: &str, param: &Param) -> bool {
+		// The colon of a path variable such as `:id=42` is part of its name
+		let name_start = usize::from(item.starts_with(':'));
+		let separator = item.char_indices().skip(name_start).find_map(|(i, _)| {
+			ITEM_SEPARATORS.iter().find(|separator| item[i..].starts_with(**separator)).map(|s| (i, *s))
+		});
+		separator.is_some_and(|(i, separator)| {
+			let name = format!("{}{}", param.source.httpie_param_prefix(), param.name);
+			let raw_json = param.is_raw_json() && separator == ":=";
+			item[..i] == name && (separator == param.source.httpie_operator() || raw_json)
+		})
+	}
+
+	/// Collect value candidates for a param assignment: values declared in the
+	/// spec, values fetched for linked params, the stored variable for path
+	/// params, and values used before in requests to the same URL
+	fn value_candidates(api: &ApiSpec, param: &Param, url_template: &str) -> Vec<Candidate> {
+		let prefix = param.httpie_value_format();
+		let mut candidates: Vec<_> = param
+			.fish_value_complete_formats()
+			.iter()
+			.map(|line| Candidate::from_fish(CandidateKind::Value, line))
+			.collect();
+
+		if let Some(source) = api.values.get(&param.name) {
+			match ValueCache::load(&api.name) {
+				Ok(cache) => {
+					let description = format!("from GET {}", source.path);
+					let values = cache.get(&param.name).into_iter().flat_map(|cached| &cached.values);
+					candidates.extend(values.map(|value| {
+						Candidate::new(CandidateKind::Value, format!("{prefix}{value}"), &description)
+					}));
+				}
+				Err(e) => tracing::warn!("Failed to load linked values: {:#}", e),
+			}
+		}
+
+		if matches!(param.source, ParamSource::Path) {
+			match VarStore::load() {
+				Ok(vars) => {
+					if let Some(value) = vars.get(&api.name, &param.name) {
+						candidates.push(Candidate::new(
+							CandidateKind::Value,
+							format!("{prefix}{value}"),
+							"stored variable",
+						));
+					}
+				}
+				Err(e) => tracing::warn!("Failed to load variables: {:#}", e),
+			}
+		}
+
+		match History::used_values(url_template, &prefix) {
+			Ok(values) => candidates.extend(values.into_iter().map(|(value, used)| {
+				let description = format!("used {}", format_age(used));
+				Candidate::new(CandidateKind::Value, format!("{prefix}{value}"), &description)
+			})),
+			Err(e) => tracing::warn!("Failed to load history: {:#}", e),
+		}
+
+		// Keep the first description of each value
+		let mut seen = HashSet::new();
+		candidates.retain(|candidate| seen.insert(candidate.value.clone()));
+		for candidate in &mut candidates {
+			candidate.source = Some(param.source.clone());
+			candidate.required = param.required;
+		}
+		candidates
+	}
+}
+
+/// Output format of `complete`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum CompleteFormat {
+	/// `value<TAB>description` lines for fish
+	#[default]
+	Fish,
+	/// A JSON array of structured candidates for shells and editors that style
+	/// candidates themselves
+	Json,
+}
+
+/// What a completion candidate stands for
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CandidateKind {
+	Api,
+	Method,
+	Path,
+	Tag,
+	Param,
+	Body,
+	Value,
+}
+
+/// A completion candidate with the structured fields of the JSON format
+#[derive(Debug, Serialize)]
+struct Candidate {
+	value: String,
+	description: String,
+	kind: CandidateKind,
+	/// Where a param or value goes in the request
+	#[serde(skip_serializing_if = "Option::is_none")]
+	source: Option<ParamSource>,
+	#[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+	schema_type: Option<String>,
+	required: bool,
+	deprecated: bool,
+	/// Value a param is expected to take, from its default or examples
+	#[serde(skip_serializing_if = "Option::is_none")]
+	value_hint: Option<String>,
+}
+
+impl Candidate {
+	fn new(kind: CandidateKind, value: String, description: &str) -> Self {
+		Self {
+			value,
+			description: description.to_string(),
+			kind,
+			source: None,
+			schema_type: None,
+			required: false,
+			deprecated: false,
+			value_hint: None,
+		}
+	}
+
+	/// Build a candidate from a `value<TAB>description` line
+	fn from_fish(kind: CandidateKind, line: &str) -> Self {
+		let (value, description) = line.split_once('\t').unwrap_or((line, ""));
+		Self::new(kind, value.to_string(), description)
+	}
+
+	fn endpoint(endpoint: &EndPoint, base_url: &str) -> Self {
+		let value = format!("{}{}", endpoint.base_url(base_url), endpoint.path);
+		let description = endpoint.summary.as_deref().unwrap_or_default();
+		Self { deprecated: endpoint.deprecated, ..Self::new(CandidateKind::Path, value, description) }
+	}
+
+	fn param(param: &Param) -> Self {
+		let description = param.description.as_deref().unwrap_or_default();
+		Self {
+			source: Some(param.source.clone()),
+			schema_type: param.schema_type.clone(),
+			required: param.required,
+			deprecated: param.deprecated,
+			value_hint: param.default.clone().or_else(|| param.examples.first().cloned()),
+			..Self::new(CandidateKind::Param, param.httpie_param_format(), description)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::*;
+
+	#[test]
+	fn test_param_candidate() {
+		let param = Param {
+			name:        "page".to_string(),
+			required:    true,
+			source:      ParamSource::Query,
+			description: Some("Page number".to_string()),
+			default:     None,
+			examples:    vec!["2".to_string()],
+			enum_values: Vec::new(),
+			schema_type: Some("integer".to_string()),
+			pattern:     None,
+			array:       None,
+			deprecated:  true,
+		};
+		let candidate = serde_json::to_value(Candidate::param(&param)).unwrap();
+		assert_eq!(
+			candidate,
+			json!({
+				"value": "page==",
+				"description": "Page number",
+				"kind": "param",
+				"source": "Query",
+				"type": "integer",
+				"required": true,
+				"deprecated": true,
+				"value_hint": "2",
+			})
+		);
+
+		// The brackets and default of optional params are only for fish
+		let param = Param { required: false, default: Some("1".to_string()), ..param };
+		let candidate = serde_json::to_value(Candidate::param(&param)).unwrap();
+		assert_eq!(candidate["value"], "page==");
+		assert_eq!(candidate["description"], "Page number");
+		assert_eq!(candidate["value_hint"], "1");
+	}
+
+	#[test]
+	fn test_assigns() {
+		let param = |name: &str, source: ParamSource| Param {
+			name:        name.to_string(),
+			required:    false,
+			source,
+			description: None,
+			default:     None,
+			examples:    Vec::new(),
+			enum_values: Vec::new(),
+			schema_type: None,
+			pattern:     None,
+			array:       None,
+			deprecated:  false,
+		};
+		let (query, body) = (param("page", ParamSource::Query), param("page", ParamSource::Body));
+		assert!(CompleteCommand::assigns("page==5", &query));
+		assert!(!CompleteCommand::assigns("page==5", &body));
+		assert!(CompleteCommand::assigns("page=5", &body));
+		assert!(!CompleteCommand::assigns("page=5", &query));
+		assert!(!CompleteCommand::assigns("page:=5", &body));
+		assert!(!CompleteCommand::assigns("pages=5", &body));
+		assert!(!CompleteCommand::assigns("page:=5", &param("page", ParamSource::Header)));
+		assert!(CompleteCommand::assigns("X-Page:5", &param("X-Page", ParamSource::Header)));
+		assert!(CompleteCommand::assigns(":id=4", &param("id", ParamSource::Path)));
+		assert!(!CompleteCommand::assigns("id=4", &param("id", ParamSource::Path)));
+		// Typed body fields are assigned JSON with `:=`
+		let views =
+			Param { schema_type: Some("integer".to_string()), ..param("views", ParamSource::Body) };
+		assert!(CompleteCommand::assigns("views:=5", &views));
+		assert!(CompleteCommand::assigns("views=5", &views));
 	}
 }
@@ -1,12 +1,13 @@
 use std::io::Write;
 
-const FISH_COMPLETE_TEMPLATE: &str = r#"
-# Override http command to handle path variables
+const FISH_WRAPPER_TEMPLATE: &str = r#"
+# Override http command to handle path variables and record the request history
 function http --wraps http
-    set -l arguments (httpie-oapi path-var -- $argv)
-    eval command http $arguments
+    httpie-oapi run -- http $argv
 end
+"#;
 
+const FISH_PICKER_TEMPLATE: &str = r#"
 # Function to select an endpoint using fzf and convert it to http command
 function h
     # Get all endpoints and pipe to fzf
@@ -27,11 +28,17 @@ function h
         end
     end
 end
+"#;
 
+const FISH_COMPLETE_TEMPLATE: &str = r#"
 function __httpie_openapi_complete
     set -l cmdline (commandline -cp)
     set -l cursor (commandline -C)
-    httpie-oapi complete --line "$cmdline" --cursor-pos $cursor
+    set -l options
+    # Characters of the path to type before all paths are listed
+    set -q httpie_oapi_min_path_length
+    and set options --min-path-length $httpie_oapi_min_path_length
+    httpie-oapi complete --line "$cmdline" --cursor-pos $cursor $options
 end
 
 # Function to get file completions with prefix
@@ -89,6 +96,15 @@ function __httpie_need_file_completion
     string match -q -r '@' -- $current_token
 end
 
+# Complete the values of the server variables of `spec add` and `spec set`
+# with those the spec of the API named on the command line allows
+function __httpie_oapi_server_vars
+    set -l words (commandline -opc)
+    httpie-oapi spec server-vars -- $words[2..-1] 2>/dev/null
+end
+
+complete -c httpie-oapi -n '__fish_seen_subcommand_from add set' -l server-var -xa '(__httpie_oapi_server_vars)'
+
 # the content come from https://github.com/httpie/cli/blob/master/extras/httpie-completion.fish
 function __fish_httpie_styles
     printf '%s\n' abap algol algol_nu arduino auto autumn borland bw colorful default emacs friendly fruity gruvbox-dark gruvbox-light igor inkpot lovelace manni material monokai murphy native paraiso-dark paraiso-light pastie perldoc pie pie-dark pie-light rainbow_dash rrt sas solarized solarized-dark solarized-light stata stata-dark stata-light tango trac vim vs xcode zenburn
@@ -226,14 +242,42 @@ for cmd in http https
 end
 "#;
 
-pub(super) fn generate_completion(output: Option<String>) -> std::io::Result<()> {
+/// Parts of the fish script to emit
+pub(super) struct FishOptions {
+	/// Emit the `http` wrapper that rewrites arguments through `path-var`
+	pub wrapper: bool,
+	/// Emit the `h` fzf endpoint picker
+	pub picker: bool,
+	/// Key sequence bound to the picker, e.g. `\ch`
+	pub picker_key: Option<String>,
+}
+
+pub(super) fn generate_completion(
+	output: Option<String>,
+	options: &FishOptions,
+) -> std::io::Result<()> {
 	let mut writer: Box<dyn Write> = if let Some(path) = output {
 		Box::new(std::fs::File::create(path)?)
 	} else {
 		Box::new(std::io::stdout())
 	};
 
-	writer.write_all(FISH_COMPLETE_TEMPLATE.as_bytes())?;
+	writer.write_all(render(options).as_bytes())?;
 	writer.flush()?;
 	Ok(())
 }
+
+fn render(options: &FishOptions) -> String {
+	let mut script = String::new();
+	if options.wrapper {
+		script.push_str(FISH_WRAPPER_TEMPLATE);
+	}
+	if options.picker {
+		script.push_str(FISH_PICKER_TEMPLATE);
+		if let Some(key) = &options.picker_key {
+			script.push_str(&format!("\n# Open the endpoint picker\nbind {key} 'h; commandline -f repaint'\n"));
+		}
+	}
+	script.push_str(FISH_COMPLETE_TEMPLATE);
+	script
+}
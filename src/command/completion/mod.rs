@@ -10,6 +10,19 @@ pub struct CompletionsCommand {
 
 	/// Output file path, default to stdout
 	pub output: Option<String>,
+
+	/// Do not emit the `http` wrapper that rewrites path variables
+	#[arg(long)]
+	pub no_wrapper: bool,
+
+	/// Do not emit the `h` fzf endpoint picker
+	#[arg(long)]
+	pub no_picker: bool,
+
+	/// Bind the endpoint picker to a key sequence
+	/// Example: --picker-key \ch
+	#[arg(long, value_name = "KEY", conflicts_with = "no_picker")]
+	pub picker_key: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -22,7 +35,12 @@ impl CompletionsCommand {
 	pub(super) fn run(&self) -> anyhow::Result<()> {
 		match self.shell {
 			Shell::Fish => {
-				if let Err(e) = fish::generate_completion(self.output.clone()) {
+				let options = fish::FishOptions {
+					wrapper: !self.no_wrapper,
+					picker: !self.no_picker,
+					picker_key: self.picker_key.clone(),
+				};
+				if let Err(e) = fish::generate_completion(self.output.clone(), &options) {
 					return Err(anyhow!("Failed to generate fish completion: {}", e));
 				}
 			}
@@ -0,0 +1,305 @@
+use anyhow::{Context, anyhow};
+use clap::Args;
+use serde_json::Value;
+use url::Url;
+
+use super::example::ExampleCommand;
+use crate::config::Config;
+use crate::openapi::endpoint::EndPoint;
+use crate::openapi::param::{ParamSource, insert_nested};
+
+/// Separators of HTTPie request items, longer ones first where they overlap
+const SEPARATORS: [(&str, Separator); 5] = [
+	(":=", Separator::RawJson),
+	("==", Separator::Query),
+	("=", Separator::Value),
+	(":", Separator::Header),
+	("@", Separator::File),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Separator {
+	RawJson,
+	Query,
+	Value,
+	Header,
+	File,
+}
+
+#[derive(Args, Debug)]
+#[command(
+	about = "Print the curl command calling an endpoint",
+	long_about = r#"
+Print the curl command calling an endpoint, for tickets and CI scripts where
+HTTPie isn't installed. Values are given as in HTTPie: `key=value` is put where
+the spec declares the param, in the path, the query string, a header or the
+body, while `key==value`, `Header:value` and `key:=json` say it explicitly.
+Required params that are not given are filled in as `example` does.
+
+Examples:
+  httpie-oapi curl petstore GET /pet/{petId} petId=42
+  httpie-oapi curl petstore POST /pet name=doggie status=available
+  httpie-oapi curl petstore GET /pet/findByStatus status==sold X-Trace:abc
+"#
+)]
+pub(super) struct CurlCommand {
+	/// Name of the API service
+	#[arg(value_name = "NAME")]
+	name: String,
+
+	/// HTTP method of the endpoint (e.g. `POST`)
+	#[arg(value_name = "METHOD")]
+	method: String,
+
+	/// The API path of the endpoint (e.g. `/users/{id}`, `/users/42` or a URL)
+	#[arg(value_name = "PATH")]
+	path: String,
+
+	/// Values of params in HTTPie's request item syntax (e.g. `id=42`,
+	/// `page==2`, `X-Trace:abc` or `tags:='["a"]'`)
+	#[arg(value_name = "KEY=VALUE")]
+	items: Vec<String>,
+}
+
+/// A value of a param, either given or filled in from the spec
+#[derive(Debug)]
+struct Item {
+	name: String,
+	source: ParamSource,
+	value: Value,
+}
+
+impl CurlCommand {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		let api =
+			config.get_api(&self.name).with_context(|| format!("API '{}' not found", self.name))?;
+		let endpoints = api.endpoints(config.is_offline());
+		let ep = endpoints.find_method(&api.base_url, &self.method, &self.path).with_context(|| {
+			format!("No {} endpoint matched path '{}'", self.method.to_uppercase(), self.path)
+		})?;
+		let items = self.items.iter().map(|item| Self::parse_item(ep, item));
+		let mut items = items.collect::<anyhow::Result<Vec<_>>>()?;
+		// Values typed in place of the path params count as given
+		for (name, value) in ep.path_values(&api.base_url, &self.path) {
+			if !items.iter().any(|item| item.source == ParamSource::Path && item.name == name) {
+				items.push(Item { name, source: ParamSource::Path, value: Value::String(value) });
+			}
+		}
+		println!("{}", shell_words::join(Self::invocation(ep, &api.base_url, items)?));
+		Ok(())
+	}
+
+	/// Read a request item, placing `key=value` where the spec declares the
+	/// param and sending the values of typed JSON body params as JSON
+	fn parse_item(ep: &EndPoint, item: &str) -> anyhow::Result<Item> {
+		// The colon of a path variable such as `:id=42` is not a separator
+		let path_var = usize::from(item.starts_with(':'));
+		let separator = item.char_indices().skip(path_var).find_map(|(i, _)| {
+			let rest = &item[i..];
+			SEPARATORS.iter().find(|(separator, _)| rest.starts_with(separator)).map(|s| (i, *s))
+		});
+		let Some((i, (separator, kind))) = separator else {
+			return Err(anyhow!("Expected a request item such as key=value, got '{}'", item));
+		};
+		let (name, value) = (&item[..i], &item[i + separator.len()..]);
+		let string = || Value::String(value.to_string());
+		let (name, source, value) = match kind {
+			Separator::RawJson => {
+				let json = serde_json::from_str(value)
+					.with_context(|| format!("Invalid JSON value of '{}': {}", name, value))?;
+				(name, ParamSource::Body, json)
+			}
+			Separator::Query => (name, ParamSource::Query, string()),
+			Separator::Header => (name, ParamSource::Header, string()),
+			Separator::File => (name, ParamSource::File, string()),
+			Separator::Value => match name.strip_prefix(':') {
+				Some(name) => (name, ParamSource::Path, string()),
+				None => {
+					let param = ep.params.iter().find(|param| param.name == name);
+					let source = match param {
+						Some(param) => param.source.clone(),
+						None if ep.form_flag().is_some() => ParamSource::Form,
+						None => ParamSource::Body,
+					};
+					// Numbers and booleans of the spec are sent as such
+					let json = param.filter(|param| param.is_raw_json()).and_then(|_| {
+						serde_json::from_str(value).ok().filter(|json: &Value| !json.is_string())
+					});
+					(name, source, json.unwrap_or_else(string))
+				}
+			},
+		};
+		Ok(Item { name: name.to_string(), source, value })
+	}
+
+	/// The arguments of a curl command calling the endpoint with the given
+	/// items, and the required params that are not given
+	fn invocation(
+		ep: &EndPoint,
+		base_url: &str,
+		mut items: Vec<Item>,
+	) -> anyhow::Result<Vec<String>> {
+		for param in ExampleCommand::required_params(ep) {
+			let given = items.iter().any(|item| {
+				item.name == param.name
+					|| (param.source.is_body() && item.name.starts_with(&format!("{}[", param.name)))
+			});
+			if !given {
+				let (name, source, value) =
+					(param.name.clone(), param.source.clone(), param.example_json());
+				items.push(Item { name, source, value });
+			}
+		}
+		let value_of = |source: ParamSource| {
+			let items = items.iter().filter(move |item| item.source == source);
+			items.map(|item| (item.name.as_str(), Self::render(&item.value)))
+		};
+
+		let path = ExampleCommand::fill_path(&ep.path, |name| {
+			value_of(ParamSource::Path).find(|(path_name, _)| *path_name == name).map(|(_, v)| v)
+		});
+		let url = format!("{}{}", ep.base_url(base_url), path);
+		let url = match Url::parse(&url) {
+			Ok(mut parsed) => {
+				let mut query = value_of(ParamSource::Query).peekable();
+				if query.peek().is_some() {
+					parsed.query_pairs_mut().extend_pairs(query);
+				}
+				parsed.to_string()
+			}
+			Err(_) => url,
+		};
+
+		let mut args = vec!["curl".to_string()];
+		match ep.method.to_string().as_str() {
+			"GET" => {}
+			// `-X HEAD` would wait for a body that never comes
+			"HEAD" => args.push("--head".to_string()),
+			method => args.extend(["-X".to_string(), method.to_string()]),
+		}
+		args.push(url);
+		for (name, value) in value_of(ParamSource::Header) {
+			args.extend(["-H".to_string(), format!("{}: {}", name, value)]);
+		}
+
+		let mut body = Value::Null;
+		for item in items.iter().filter(|item| item.source == ParamSource::Body) {
+			insert_nested(&mut body, &item.name, item.value.clone());
+		}
+		if !body.is_null() {
+			args.extend(["-H".to_string(), "Content-Type: application/json".to_string()]);
+			args.extend(["--data".to_string(), serde_json::to_string(&body)?]);
+		}
+		let multipart = ep.form_flag() == Some("--multipart")
+			|| items.iter().any(|item| item.source == ParamSource::File);
+		for (name, value) in value_of(ParamSource::Form) {
+			// `--form-string` takes values starting with `@` or `<` literally
+			let option = if multipart { "--form-string" } else { "--data-urlencode" };
+			args.extend([option.to_string(), format!("{}={}", name, value)]);
+		}
+		for (name, value) in value_of(ParamSource::File) {
+			let file = if value.is_empty() || value == "string" { format!("./{}", name) } else { value };
+			args.extend(["-F".to_string(), format!("{}=@{}", name, file)]);
+		}
+		Ok(args)
+	}
+
+	/// Render a value for the command line, strings without their quotes
+	fn render(value: &Value) -> String {
+		match value {
+			Value::String(s) => s.clone(),
+			other => other.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::openapi::EndPoints;
+
+	#[test]
+	fn test_invocation() {
+		let spec = serde_json::json!({
+			"openapi": "3.0.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": {
+				"/users/{id}/posts": {
+					"post": {
+						"parameters": [
+							{ "name": "id", "in": "path", "required": true, "schema": { "type": "integer" } },
+							{ "name": "draft", "in": "query", "schema": { "type": "boolean" } }
+						],
+						"requestBody": {
+							"content": {
+								"application/json": {
+									"schema": {
+										"type": "object",
+										"required": ["title"],
+										"properties": {
+											"title": { "type": "string", "example": "Hello" },
+											"views": { "type": "integer" },
+											"tags": { "type": "array", "items": { "type": "string" } }
+										}
+									}
+								}
+							}
+						},
+						"responses": { "201": { "description": "created" } }
+					}
+				}
+			}
+		});
+		let endpoints = EndPoints::try_from_openapi(spec.to_string()).unwrap();
+		let ep = endpoints.find("/users/:id/posts").unwrap();
+		let items = ["id=42", "draft=true", "views=3", "tags[]=a", "tags[]=b", "X-Trace:a b"];
+		let items = items.iter().map(|item| CurlCommand::parse_item(ep, item).unwrap()).collect();
+		assert_eq!(
+			shell_words::join(CurlCommand::invocation(ep, "https://api.example.com", items).unwrap()),
+			"curl -X POST 'https://api.example.com/users/42/posts?draft=true' -H 'X-Trace: a b' \
+			 -H 'Content-Type: application/json' \
+			 --data '{\"tags\":[\"a\",\"b\"],\"title\":\"Hello\",\"views\":3}'"
+		);
+		assert!(CurlCommand::parse_item(ep, "views").is_err());
+	}
+
+	#[test]
+	fn test_invocation_head_and_multipart() {
+		let spec = serde_json::json!({
+			"openapi": "3.0.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": {
+				"/avatars": {
+					"head": { "responses": { "200": { "description": "ok" } } },
+					"post": {
+						"requestBody": {
+							"content": {
+								"multipart/form-data": {
+									"schema": {
+										"type": "object",
+										"properties": {
+											"caption": { "type": "string" },
+											"image": { "type": "string", "format": "binary" }
+										}
+									}
+								}
+							}
+						},
+						"responses": { "201": { "description": "created" } }
+					}
+				}
+			}
+		});
+		let endpoints = EndPoints::try_from_openapi(spec.to_string()).unwrap();
+		let invocation = |method: &str, items: &[&str]| {
+			let ep = endpoints.all().into_iter().find(|ep| ep.method.to_string() == method).unwrap();
+			let items = items.iter().map(|item| CurlCommand::parse_item(ep, item).unwrap()).collect();
+			shell_words::join(CurlCommand::invocation(ep, "https://api.example.com", items).unwrap())
+		};
+		assert_eq!(invocation("HEAD", &[]), "curl --head https://api.example.com/avatars");
+		assert_eq!(
+			invocation("POST", &["caption=@me", "image@cat.png"]),
+			"curl -X POST https://api.example.com/avatars --form-string 'caption=@me' -F 'image=@cat.png'"
+		);
+	}
+}
@@ -0,0 +1,464 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, Stdio};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{Context, anyhow};
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use super::complete::{CompleteCommand, CompleteOptions};
+use crate::config::Config;
+use crate::time::format_age;
+
+/// How long the client waits for the daemon before completing in-process
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long `start` and `stop` wait for the daemon to come up or go away
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Subcommand, Debug)]
+pub(super) enum DaemonCommands {
+	/// Start the completion daemon in the background
+	Start,
+	/// Stop the completion daemon
+	Stop,
+	/// Show whether the completion daemon is running
+	Status,
+	/// Restart the completion daemon, e.g. after upgrading httpie-oapi
+	Restart,
+	/// Serve completion requests in the foreground
+	#[command(hide = true)]
+	Serve,
+}
+
+/// A request sent to the daemon as a single JSON line
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "lowercase")]
+enum Request {
+	Complete {
+		line: String,
+		cursor_pos: usize,
+		#[serde(default)]
+		options: CompleteOptions,
+	},
+	Status,
+	Stop,
+}
+
+/// First line of the answer to a request handled by the daemon, the lines of
+/// a failed request start with `error: ` followed by its error
+const OK: &str = "ok";
+const ERROR: &str = "error: ";
+
+/// State of the daemon as seen from a client
+#[derive(Debug, PartialEq, Eq)]
+enum DaemonState {
+	/// The daemon accepts connections
+	Running,
+	/// A socket or PID file was left behind by a daemon that is gone or hung
+	Stale,
+	Stopped,
+}
+
+impl DaemonCommands {
+	pub(super) fn run(&self) -> anyhow::Result<()> {
+		match self {
+			DaemonCommands::Start => start(),
+			DaemonCommands::Stop => stop(),
+			DaemonCommands::Status => status(),
+			DaemonCommands::Restart => stop().and_then(|_| start()),
+			DaemonCommands::Serve => serve(),
+		}
+	}
+}
+
+/// Complete through the daemon if it is in use
+///
+/// Returns `false` when the caller should complete in-process: the daemon was
+/// never started, or it crashed, in which case its leftovers are cleaned up
+/// and it is restarted in the background for the next completion.
+pub(super) fn complete(
+	line: &str,
+	cursor_pos: usize,
+	options: CompleteOptions,
+) -> anyhow::Result<bool> {
+	match state() {
+		DaemonState::Stopped => Ok(false),
+		DaemonState::Stale => {
+			warn!("Completion daemon is gone, restarting it");
+			remove_leftovers();
+			if let Err(e) = spawn() {
+				warn!("Failed to restart completion daemon: {:#}", e);
+			}
+			Ok(false)
+		}
+		DaemonState::Running => {
+			let request = Request::Complete { line: line.to_string(), cursor_pos, options };
+			match send(&request) {
+				Ok(Ok(candidates)) => {
+					io::stdout().lock().write_all(candidates.as_bytes())?;
+					Ok(true)
+				}
+				Ok(Err(e)) => Err(anyhow!("Completion daemon failed: {}", e)),
+				Err(e) => {
+					warn!("Completion daemon did not answer: {:#}", e);
+					Ok(false)
+				}
+			}
+		}
+	}
+}
+
+fn start() -> anyhow::Result<()> {
+	match state() {
+		DaemonState::Running => {
+			println!("Completion daemon is already running (pid {})", read_pid().unwrap_or_default());
+			return Ok(());
+		}
+		DaemonState::Stale => remove_leftovers(),
+		DaemonState::Stopped => {}
+	}
+
+	spawn()?;
+	let started = Instant::now();
+	while started.elapsed() < STARTUP_TIMEOUT {
+		if let DaemonState::Running = state() {
+			println!("Started completion daemon (pid {})", read_pid().unwrap_or_default());
+			return Ok(());
+		}
+		std::thread::sleep(Duration::from_millis(50));
+	}
+	Err(anyhow!("Completion daemon did not start, see the log in ~/.local/state/httpie-oapi"))
+}
+
+fn stop() -> anyhow::Result<()> {
+	match state() {
+		DaemonState::Running => {
+			send(&Request::Stop)?.map_err(|e| anyhow!(e))?;
+			let started = Instant::now();
+			while Config::get_daemon_socket_path().exists() {
+				if started.elapsed() > STARTUP_TIMEOUT {
+					return Err(anyhow!("Completion daemon did not stop"));
+				}
+				std::thread::sleep(Duration::from_millis(50));
+			}
+			println!("Stopped completion daemon");
+		}
+		DaemonState::Stale => {
+			// A daemon that still exists but does not accept connections is hung,
+			// while the PID of one that is gone may have been reused since
+			match read_pid().filter(|&pid| is_alive(pid)) {
+				Some(pid) if is_daemon(pid) => {
+					warn!("Terminating unresponsive completion daemon {}", pid);
+					// SAFETY: kill has no memory safety requirements
+					unsafe { libc::kill(pid, libc::SIGTERM) };
+				}
+				Some(pid) => warn!("Process {} is not the completion daemon, leaving it alone", pid),
+				None => {}
+			}
+			remove_leftovers();
+			println!("Completion daemon was not running, removed its stale socket");
+		}
+		DaemonState::Stopped => println!("Completion daemon is not running"),
+	}
+	Ok(())
+}
+
+fn status() -> anyhow::Result<()> {
+	match state() {
+		DaemonState::Running => print!("{}", send(&Request::Status)?.map_err(|e| anyhow!(e))?),
+		DaemonState::Stale => {
+			remove_leftovers();
+			println!("Completion daemon is not running, removed its stale socket");
+		}
+		DaemonState::Stopped => println!("Completion daemon is not running"),
+	}
+	Ok(())
+}
+
+/// Serve requests until a stop request arrives
+///
+/// The config is reloaded whenever it or the refresh journal changed, so
+/// `spec add` and `spec refresh` take effect without a restart. A config that
+/// fails to load is reported to the clients, the last one loaded is kept
+/// until it loads again.
+fn serve() -> anyhow::Result<()> {
+	if let DaemonState::Running = state() {
+		return Err(anyhow!("Completion daemon is already running"));
+	}
+	remove_leftovers();
+
+	let socket_path = Config::get_daemon_socket_path();
+	let listener = UnixListener::bind(&socket_path)
+		.with_context(|| format!("Failed to bind daemon socket: {}", socket_path.display()))?;
+	let pid_path = Config::get_daemon_pid_path();
+	fs::write(&pid_path, std::process::id().to_string())
+		.with_context(|| format!("Failed to write daemon PID file: {}", pid_path.display()))?;
+	info!("Completion daemon listening on {}", socket_path.display());
+
+	let started = crate::time::now();
+	let mut served = 0u64;
+	let mut config = Config::try_load()?;
+	let mut loaded = config_stamp();
+	for stream in listener.incoming() {
+		let mut stream = match stream {
+			Ok(stream) => stream,
+			Err(e) => {
+				warn!("Failed to accept daemon connection: {}", e);
+				continue;
+			}
+		};
+		let request = match read_request(&stream) {
+			Ok(request) => request,
+			Err(e) => {
+				warn!("Invalid daemon request: {:#}", e);
+				continue;
+			}
+		};
+		debug!("Daemon request: {:?}", request);
+
+		let response = match request {
+			Request::Complete { line, cursor_pos, mut options } => {
+				let stamp = config_stamp();
+				let reloaded = match stamp != loaded {
+					true => {
+						info!("Config changed, reloading");
+						Config::try_load().map(|reloaded| {
+							config = reloaded;
+							loaded = stamp;
+						})
+					}
+					false => Ok(()),
+				};
+				served += 1;
+				// Downloading a spec could exit the daemon, so only caches are used
+				// while stale ones are refreshed by a detached `spec refresh`
+				options.cache_only = true;
+				let mut candidates = Vec::new();
+				reloaded
+					.and_then(|_| {
+						CompleteCommand::complete(&line, cursor_pos, options, &config, &mut candidates)
+					})
+					.map(|_| candidates)
+			}
+			Request::Status => Ok(
+				format!(
+					"Completion daemon is running (pid {}, started {}, {} completions served)\n",
+					std::process::id(),
+					format_age(started),
+					served
+				)
+				.into_bytes(),
+			),
+			Request::Stop => {
+				if let Err(e) = writeln!(stream, "{}", OK) {
+					warn!("Failed to answer daemon request: {}", e);
+				}
+				break;
+			}
+		};
+		let answer = match response {
+			Ok(output) => [format!("{}\n", OK).into_bytes(), output].concat(),
+			Err(e) => {
+				warn!("Failed to handle daemon request: {:#}", e);
+				format!("{}{}\n", ERROR, format!("{:#}", e).replace('\n', " ")).into_bytes()
+			}
+		};
+		if let Err(e) = stream.write_all(&answer) {
+			warn!("Failed to answer daemon request: {}", e);
+		}
+		reap_refreshes();
+	}
+
+	info!("Completion daemon stopping");
+	remove_leftovers();
+	Ok(())
+}
+
+/// Wait for the background refreshes of stale caches that exited, so they
+/// don't linger as zombies of the daemon
+fn reap_refreshes() {
+	// SAFETY: waitpid with WNOHANG only collects children that already exited
+	while unsafe { libc::waitpid(-1, std::ptr::null_mut(), libc::WNOHANG) } > 0 {}
+}
+
+fn read_request(stream: &UnixStream) -> anyhow::Result<Request> {
+	stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+	let mut line = String::new();
+	BufReader::new(stream).read_line(&mut line)?;
+	Ok(serde_json::from_str(&line)?)
+}
+
+/// Send a request to the daemon and read its whole answer, the output of the
+/// request or the error it failed with
+fn send(request: &Request) -> anyhow::Result<Result<String, String>> {
+	let mut stream = UnixStream::connect(Config::get_daemon_socket_path())?;
+	stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+	stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+	writeln!(stream, "{}", serde_json::to_string(request)?)?;
+	let mut response = String::new();
+	stream.read_to_string(&mut response)?;
+	parse_answer(&response)
+}
+
+/// Split the answer of the daemon into its status line and the output
+fn parse_answer(answer: &str) -> anyhow::Result<Result<String, String>> {
+	match answer.split_once('\n') {
+		Some((OK, output)) => Ok(Ok(output.to_string())),
+		Some((status, _)) if status.starts_with(ERROR) => Ok(Err(status[ERROR.len()..].to_string())),
+		_ => Err(anyhow!("Unexpected answer of the completion daemon, try `daemon restart`")),
+	}
+}
+
+fn state() -> DaemonState {
+	state_of(&Config::get_daemon_socket_path(), &Config::get_daemon_pid_path())
+}
+
+fn state_of(socket_path: &Path, pid_path: &Path) -> DaemonState {
+	if UnixStream::connect(socket_path).is_ok() {
+		DaemonState::Running
+	} else if socket_path.exists() || pid_path.exists() {
+		DaemonState::Stale
+	} else {
+		DaemonState::Stopped
+	}
+}
+
+/// Start `daemon serve` detached from the terminal and the shell's process
+/// group
+fn spawn() -> anyhow::Result<()> {
+	let exe = std::env::current_exe().context("Failed to locate httpie-oapi executable")?;
+	Process::new(exe)
+		.args(["daemon", "serve"])
+		.stdin(Stdio::null())
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.process_group(0)
+		.spawn()
+		.context("Failed to start completion daemon")?;
+	Ok(())
+}
+
+fn remove_leftovers() {
+	for path in [Config::get_daemon_socket_path(), Config::get_daemon_pid_path()] {
+		if let Err(e) = fs::remove_file(&path)
+			&& e.kind() != io::ErrorKind::NotFound
+		{
+			warn!("Failed to remove {}: {}", path.display(), e);
+		}
+	}
+}
+
+fn read_pid() -> Option<libc::pid_t> {
+	parse_pid(&fs::read_to_string(Config::get_daemon_pid_path()).ok()?)
+}
+
+/// Read the content of a PID file, which never holds 0 or a negative PID:
+/// those would signal process groups rather than the daemon
+fn parse_pid(content: &str) -> Option<libc::pid_t> {
+	content.trim().parse().ok().filter(|&pid| pid > 0)
+}
+
+fn is_alive(pid: libc::pid_t) -> bool {
+	// SAFETY: signal 0 only checks that the process exists
+	unsafe { libc::kill(pid, 0) == 0 }
+}
+
+/// Whether a process is `daemon serve` of this executable, which is only
+/// known where `/proc` exists
+fn is_daemon(pid: libc::pid_t) -> bool {
+	let proc_dir = PathBuf::from(format!("/proc/{}", pid));
+	let (Ok(exe), Ok(current_exe)) = (fs::read_link(proc_dir.join("exe")), std::env::current_exe())
+	else {
+		return false;
+	};
+	// The executable of a daemon started before an upgrade was deleted since
+	let exe = exe.to_string_lossy();
+	let exe = exe.strip_suffix(" (deleted)").unwrap_or(&exe);
+	let Ok(cmdline) = fs::read(proc_dir.join("cmdline")) else {
+		return false;
+	};
+	let args: Vec<&[u8]> = cmdline.split(|&byte| byte == 0).collect();
+	Path::new(exe) == current_exe && args.get(1..3) == Some(&[b"daemon".as_slice(), b"serve"])
+}
+
+/// Modification times of the files the daemon state is derived from
+fn config_stamp() -> [Option<SystemTime>; 2] {
+	[Config::config_file(), Config::get_refresh_journal_path()]
+		.map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_pid() {
+		assert_eq!(parse_pid("1234\n"), Some(1234));
+		assert_eq!(parse_pid("0"), None);
+		assert_eq!(parse_pid("-1"), None);
+		assert_eq!(parse_pid("pid"), None);
+	}
+
+	#[test]
+	fn test_is_daemon() {
+		assert!(!is_daemon(std::process::id() as libc::pid_t));
+	}
+
+	#[test]
+	fn test_state_of() {
+		let dir = std::env::temp_dir().join(format!("httpie-oapi-daemon-{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let (socket_path, pid_path) = (dir.join("daemon.sock"), dir.join("daemon.pid"));
+		assert_eq!(state_of(&socket_path, &pid_path), DaemonState::Stopped);
+
+		let listener = UnixListener::bind(&socket_path).unwrap();
+		fs::write(&pid_path, std::process::id().to_string()).unwrap();
+		assert_eq!(state_of(&socket_path, &pid_path), DaemonState::Running);
+
+		// The socket of a crashed daemon is left behind without a listener
+		drop(listener);
+		assert_eq!(state_of(&socket_path, &pid_path), DaemonState::Stale);
+		fs::remove_file(&socket_path).unwrap();
+		assert_eq!(state_of(&socket_path, &pid_path), DaemonState::Stale);
+		fs::remove_file(&pid_path).unwrap();
+		assert_eq!(state_of(&socket_path, &pid_path), DaemonState::Stopped);
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_parse_answer() {
+		assert_eq!(parse_answer("ok\nhttp://a/\tA\n").unwrap(), Ok("http://a/\tA\n".to_string()));
+		assert_eq!(parse_answer("ok\n").unwrap(), Ok(String::new()));
+		assert_eq!(
+			parse_answer("error: Failed to parse config file: bad\n").unwrap(),
+			Err("Failed to parse config file: bad".to_string())
+		);
+		// A daemon started before the answers had a status line
+		assert!(parse_answer("http://a/\tA\n").is_err());
+		assert!(parse_answer("").is_err());
+	}
+
+	#[test]
+	fn test_request() {
+		let request = Request::Complete {
+			line:       "http ".into(),
+			cursor_pos: 5,
+			options:    Default::default(),
+		};
+		let json = serde_json::to_value(&request).unwrap();
+		assert_eq!(json["request"], "complete");
+		assert_eq!(json["line"], "http ");
+
+		// Clients may leave the options out
+		let request = serde_json::from_str(r#"{"request":"complete","line":"http G","cursor_pos":6}"#);
+		assert!(matches!(request, Ok(Request::Complete { cursor_pos: 6, .. })));
+		assert!(matches!(serde_json::from_str(r#"{"request":"stop"}"#), Ok(Request::Stop)));
+		assert!(serde_json::from_str::<Request>(r#"{"request":"restart"}"#).is_err());
+	}
+}
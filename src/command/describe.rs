@@ -0,0 +1,229 @@
+use anyhow::{Context, anyhow};
+use clap::Args;
+
+use crate::config::Config;
+use crate::openapi::endpoint::EndPoint;
+use crate::openapi::param::ParamSource;
+use crate::openapi::{EndPoints, Param};
+use crate::theme::Theme;
+
+#[derive(Args, Debug)]
+#[command(
+	about = "Describe an endpoint: its params, body, security and responses",
+	long_about = r#"
+Describe an endpoint the way Swagger UI does: its summary and description, its
+params by source with their types, allowed values and defaults, the fields of
+its request body, how it authenticates, its responses and its callbacks.
+
+The method can be left out to describe every operation of the path. Typed
+values match the templated segments of paths, so a URL can be pasted as it is.
+
+Examples:
+  httpie-oapi describe petstore GET /pet/{petId}
+  httpie-oapi describe petstore /pet/42
+  httpie-oapi describe petstore --operation findPetsByStatus
+  httpie-oapi describe petstore --webhook newPet
+"#
+)]
+pub(super) struct DescribeCommand {
+	/// Name of the API service
+	#[arg(value_name = "NAME")]
+	name: String,
+
+	/// HTTP method of the endpoint (e.g. `GET`), every operation of the path
+	/// is described when left out
+	#[arg(value_name = "METHOD", required_unless_present_any = ["operation", "webhook", "callback"])]
+	method: Option<String>,
+
+	/// The API path of the endpoint (e.g. `/users/{id}`, `/users/42` or a URL)
+	#[arg(value_name = "PATH")]
+	path: Option<String>,
+
+	/// The operationId of the endpoint, in place of its method and path
+	#[arg(long, value_name = "OPERATION_ID", conflicts_with_all = ["method", "path"])]
+	operation: Option<String>,
+
+	/// A webhook of an OpenAPI 3.1 spec, in place of a method and path
+	#[arg(long, value_name = "WEBHOOK", conflicts_with_all = ["method", "path", "operation"])]
+	webhook: Option<String>,
+
+	/// A callback of an operation, in place of a method and path
+	#[arg(
+		long,
+		value_name = "CALLBACK",
+		conflicts_with_all = ["method", "path", "operation", "webhook"]
+	)]
+	callback: Option<String>,
+}
+
+/// The sections params are listed in, by source
+const SECTIONS: [(ParamSource, &str); 6] = [
+	(ParamSource::Path, "Path params"),
+	(ParamSource::Query, "Query params"),
+	(ParamSource::Header, "Headers"),
+	(ParamSource::Body, "Body"),
+	(ParamSource::Form, "Form fields"),
+	(ParamSource::File, "Files"),
+];
+
+impl DescribeCommand {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		let api =
+			config.get_api(&self.name).with_context(|| format!("API '{}' not found", self.name))?;
+		let endpoints = api.endpoints(config.is_offline());
+		// Webhooks and callbacks are named rather than served under the base URL
+		let (eps, base_url) = match (&self.callback, &self.webhook, &self.operation) {
+			(Some(callback), ..) => {
+				let ep = endpoints
+					.find_callback(callback)
+					.with_context(|| format!("No callback named '{}'", callback))?;
+				(vec![ep], "")
+			}
+			(None, Some(webhook), _) => {
+				let ep = endpoints
+					.find_webhook(webhook)
+					.with_context(|| format!("No webhook named '{}'", webhook))?;
+				(vec![ep], "")
+			}
+			(None, None, Some(operation)) => {
+				let ep = endpoints
+					.find_operation(operation)
+					.with_context(|| format!("No endpoint has operationId '{}'", operation))?;
+				(vec![ep], api.base_url.as_str())
+			}
+			(None, None, None) => (self.find_path(&endpoints, &api.base_url)?, api.base_url.as_str()),
+		};
+
+		let theme = Theme::for_stdout(config.theme);
+		for (i, ep) in eps.into_iter().enumerate() {
+			if i > 0 {
+				println!();
+			}
+			Self::describe(ep, base_url, &theme);
+		}
+		Ok(())
+	}
+
+	/// The operations of the path, those of the method if given
+	fn find_path<'a>(
+		&self,
+		endpoints: &'a EndPoints,
+		base_url: &str,
+	) -> anyhow::Result<Vec<&'a EndPoint>> {
+		// A single positional is the path
+		let (method, path) = match (&self.method, &self.path) {
+			(Some(method), Some(path)) => (Some(method), path),
+			(Some(path), None) | (None, Some(path)) => (None, path),
+			(None, None) => unreachable!("clap requires METHOD, --operation, --webhook or --callback"),
+		};
+		let mut eps = endpoints.find_operations(base_url, path);
+		if let Some(method) = method {
+			eps.retain(|ep| ep.method.to_string().eq_ignore_ascii_case(method));
+		}
+		if eps.is_empty() {
+			return Err(match method {
+				Some(method) => anyhow!("No {} endpoint matched path '{}'", method.to_uppercase(), path),
+				None => anyhow!("No endpoint matched path '{}'", path),
+			});
+		}
+		Ok(eps)
+	}
+
+	fn describe(ep: &EndPoint, base_url: &str, theme: &Theme) {
+		let deprecated = match ep.deprecated {
+			true => {
+				format!(" {}", theme.deprecated_marker().unwrap_or_else(|| "(deprecated)".to_string()))
+			}
+			false => String::new(),
+		};
+		let url = format!("{}{}", ep.base_url(base_url), ep.path);
+		println!("{} {}{}", theme.method(&ep.method.to_string()), url, deprecated);
+		if let Some(summary) = &ep.summary {
+			println!("{}", summary);
+		}
+		if let Some(description) = ep.description.as_deref().filter(|d| !d.trim().is_empty()) {
+			println!();
+			println!("{}", theme.dim(description.trim()));
+		}
+		if let Some(operation_id) = &ep.operation_id {
+			println!();
+			println!("Operation ID: {}", operation_id);
+		}
+		if !ep.tags.is_empty() {
+			println!("Tags: {}", ep.tags.join(", "));
+		}
+
+		for (source, title) in SECTIONS {
+			let params: Vec<_> = ep.params.iter().filter(|param| param.source == source).collect();
+			if params.is_empty() {
+				continue;
+			}
+			println!();
+			println!("{}", title);
+			for param in params {
+				println!("  {}", Self::param_line(param, theme));
+			}
+		}
+
+		if !ep.security.is_empty() {
+			println!();
+			println!("Security, one of");
+			for alternative in &ep.security {
+				let schemes: Vec<_> = alternative.iter().map(ToString::to_string).collect();
+				match schemes.is_empty() {
+					true => println!("  {}", theme.dim("none")),
+					false => println!("  {}", schemes.join(" + ")),
+				}
+			}
+		}
+
+		if !ep.responses.is_empty() {
+			println!();
+			println!("Responses");
+			for response in &ep.responses {
+				let content_type = response.content_type.as_deref().map(|t| format!(" [{t}]"));
+				let details = format!("{}{}", response.description, content_type.unwrap_or_default());
+				println!("  {}  {}", response.status, theme.dim(details.trim()));
+			}
+		}
+
+		if !ep.callbacks.is_empty() {
+			println!();
+			println!("Callbacks");
+			for callback in &ep.callbacks {
+				let method = theme.method(&callback.endpoint.method.to_string());
+				println!("  {}: {} {}", callback.name, method, callback.endpoint.path);
+			}
+		}
+	}
+
+	/// Describe a param on one line: its name, type, allowed values, default
+	/// and the first line of its description
+	fn param_line(param: &Param, theme: &Theme) -> String {
+		let mut details: Vec<String> = param.schema_type.iter().cloned().collect();
+		let marker = theme.required_marker(param.required);
+		if marker.is_none() && param.required {
+			details.push("required".to_string());
+		}
+		if !param.enum_values.is_empty() {
+			details.push(format!("one of: {}", param.enum_values.join(", ")));
+		}
+		if let Some(default) = &param.default {
+			details.push(format!("default: {}", default));
+		}
+		if param.deprecated {
+			details.push("deprecated".to_string());
+		}
+		let mut line = match marker {
+			Some(marker) => format!("{} {}", marker, param.name),
+			None => param.name.clone(),
+		};
+		if !details.is_empty() {
+			line = format!("{}  {}", line, theme.dim(&details.join(", ")));
+		}
+		if let Some(description) = param.description.as_deref().and_then(|d| d.lines().next()) {
+			line = format!("{}  {}", line, description);
+		}
+		line
+	}
+}
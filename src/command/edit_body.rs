@@ -0,0 +1,170 @@
+use std::fs::{self, File};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, anyhow};
+use clap::{ArgAction, Args};
+
+use super::example::ExampleCommand;
+use crate::config::Config;
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set
+const DEFAULT_EDITOR: &str = "vi";
+
+#[derive(Args, Debug)]
+#[command(
+	about = "Edit the request body of an endpoint in $EDITOR",
+	long_about = r#"
+Open a JSON skeleton of the request body of an endpoint in `$VISUAL` or
+`$EDITOR`, check the edited body against the schema of the spec and print the
+`http` command sending it. A body that does not match the schema can be edited
+again until it does.
+
+Examples:
+  httpie-oapi edit-body petstore POST /pet
+  httpie-oapi edit-body petstore POST /pet --all
+  httpie-oapi edit-body petstore POST /pet --pipe | http POST :8080/pet
+"#
+)]
+pub(super) struct EditBodyCommand {
+	/// Name of the API service
+	#[arg(value_name = "NAME")]
+	name: String,
+
+	/// HTTP method of the endpoint (e.g. `POST`)
+	#[arg(value_name = "METHOD")]
+	method: String,
+
+	/// The API path of the endpoint (e.g. `/users/{id}`, `/users/42` or a URL)
+	#[arg(value_name = "PATH")]
+	path: String,
+
+	/// Start from a skeleton with the optional fields as well
+	#[arg(long, action = ArgAction::SetTrue)]
+	all: bool,
+
+	/// Print the edited body rather than the `http` command, to pipe it into
+	/// HTTPie
+	#[arg(long, action = ArgAction::SetTrue)]
+	pipe: bool,
+}
+
+impl EditBodyCommand {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		let api =
+			config.get_api(&self.name).with_context(|| format!("API '{}' not found", self.name))?;
+		let endpoints = api.endpoints(config.is_offline());
+		let method = self.method.to_uppercase();
+		let ep = endpoints
+			.find_method(&api.base_url, &self.method, &self.path)
+			.with_context(|| format!("No {} endpoint matched path '{}'", method, self.path))?;
+		if !ep.has_json_body() {
+			return Err(anyhow!("{} {} has no JSON body", method, ep.path));
+		}
+
+		let (file, mut writer) = Self::create_body_file()?;
+		let written = writeln!(writer, "{}", serde_json::to_string_pretty(&ep.example_body(self.all))?);
+		drop(writer);
+		if let Err(e) = written {
+			let _ = fs::remove_file(&file);
+			return Err(e).with_context(|| format!("Failed to write {}", file.display()));
+		}
+		let body = Self::edit_until_valid(&file, |body| ep.validate_body(body));
+		let _ = fs::remove_file(&file);
+		let body = body?;
+
+		if self.pipe {
+			println!("{}", serde_json::to_string_pretty(&body)?);
+			return Ok(());
+		}
+		let mut args = ExampleCommand::invocation(ep, &api.base_url, false);
+		args.push(format!("--raw={}", serde_json::to_string(&body)?));
+		println!("{}", shell_words::join(args));
+		Ok(())
+	}
+
+	/// Create a new file for the body in the temp directory that only the user
+	/// can read, never opening a file or symlink planted there under its name
+	fn create_body_file() -> anyhow::Result<(PathBuf, File)> {
+		let dir = std::env::temp_dir();
+		let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.subsec_nanos());
+		for attempt in 0..16 {
+			let name = format!("httpie-oapi-body-{}-{}.json", std::process::id(), nanos + attempt);
+			let file = dir.join(name);
+			let mut options = File::options();
+			options.write(true).create_new(true);
+			#[cfg(unix)]
+			std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+			match options.open(&file) {
+				Ok(writer) => return Ok((file, writer)),
+				Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+				Err(e) => return Err(e).with_context(|| format!("Failed to create {}", file.display())),
+			}
+		}
+		Err(anyhow!("Failed to create a body file in {}", dir.display()))
+	}
+
+	/// Open the file in the editor until it holds JSON without validation
+	/// errors, asking whether to edit again when a terminal is attached
+	fn edit_until_valid(
+		file: &Path,
+		validate: impl Fn(&serde_json::Value) -> Vec<String>,
+	) -> anyhow::Result<serde_json::Value> {
+		loop {
+			Self::open_editor(file)?;
+			let content =
+				fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+			let errors = match serde_json::from_str(&content) {
+				Ok(body) => match validate(&body) {
+					errors if errors.is_empty() => return Ok(body),
+					errors => errors,
+				},
+				Err(err) => vec![format!("Invalid JSON: {}", err)],
+			};
+			eprintln!("The body does not match the schema:");
+			for error in &errors {
+				eprintln!("  {}", error);
+			}
+			if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+				return Err(anyhow!("The edited body is invalid"));
+			}
+			eprint!("Edit again? [Y/n] ");
+			std::io::stderr().flush()?;
+			let mut answer = String::new();
+			std::io::stdin().read_line(&mut answer).context("Failed to read the answer")?;
+			if answer.trim().eq_ignore_ascii_case("n") {
+				return Err(anyhow!("The edited body is invalid"));
+			}
+		}
+	}
+
+	/// Run `$VISUAL` or `$EDITOR` on the file, on the terminal even when the
+	/// output is piped
+	fn open_editor(file: &Path) -> anyhow::Result<()> {
+		let editor = std::env::var("VISUAL")
+			.or_else(|_| std::env::var("EDITOR"))
+			.ok()
+			.filter(|editor| !editor.trim().is_empty())
+			.unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+		// Editors such as `code --wait` come with arguments
+		let words = shell_words::split(&editor)
+			.with_context(|| format!("Invalid editor command '{}'", editor))?;
+		let (program, args) = words.split_first().ok_or_else(|| anyhow!("No editor configured"))?;
+		let stdout = match std::io::stdout().is_terminal() {
+			true => Stdio::inherit(),
+			false => File::options().write(true).open("/dev/tty").map_or(Stdio::inherit(), Stdio::from),
+		};
+		let status = Process::new(program)
+			.args(args)
+			.arg(file)
+			.stdout(stdout)
+			.status()
+			.with_context(|| format!("Failed to run the editor '{}'", editor))?;
+		if !status.success() {
+			return Err(anyhow!("The editor '{}' exited with {}", editor, status));
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,156 @@
+use anyhow::Context;
+use clap::Args;
+
+use crate::config::Config;
+use crate::openapi::endpoint::EndPoint;
+use crate::openapi::param::ParamSource;
+use crate::openapi::Param;
+
+#[derive(Args, Debug)]
+#[command(
+	about = "Print a ready-to-run HTTPie command for an endpoint",
+	long_about = r#"
+Print a complete `http` command for an endpoint, with its required params and
+the required fields of its body filled in. Values are taken from the examples
+of the spec, then from its defaults and allowed values, and are placeholders of
+the declared type otherwise.
+
+Examples:
+  httpie-oapi example petstore GET /pet/{petId}
+  httpie-oapi example petstore POST /pet
+"#
+)]
+pub(super) struct ExampleCommand {
+	/// Name of the API service
+	#[arg(value_name = "NAME")]
+	name: String,
+
+	/// HTTP method of the endpoint (e.g. `POST`)
+	#[arg(value_name = "METHOD")]
+	method: String,
+
+	/// The API path of the endpoint (e.g. `/users/{id}`, `/users/42` or a URL)
+	#[arg(value_name = "PATH")]
+	path: String,
+}
+
+impl ExampleCommand {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		let api =
+			config.get_api(&self.name).with_context(|| format!("API '{}' not found", self.name))?;
+		let endpoints = api.endpoints(config.is_offline());
+		let ep = endpoints.find_method(&api.base_url, &self.method, &self.path).with_context(|| {
+			format!("No {} endpoint matched path '{}'", self.method.to_uppercase(), self.path)
+		})?;
+		println!("{}", shell_words::join(Self::invocation(ep, &api.base_url, true)));
+		Ok(())
+	}
+
+	/// The arguments of an `http` command calling the endpoint with its
+	/// required params, those of the body only if `body`
+	pub(super) fn invocation(ep: &EndPoint, base_url: &str, body: bool) -> Vec<String> {
+		let mut params = Self::required_params(ep);
+		params.retain(|param| body || !param.source.is_body());
+		let mut args = vec!["http".to_string()];
+		args.extend(ep.form_flag().filter(|_| body).map(str::to_string));
+		args.push(ep.method.to_string());
+		let path = Self::fill_path(&ep.path, |name| {
+			let param =
+				params.iter().find(|param| param.source == ParamSource::Path && param.name == name);
+			param.map(|param| param.example_value())
+		});
+		args.push(format!("{}{}", ep.base_url(base_url), path));
+		for param in params {
+			let value = param.example_value();
+			args.push(match param.source {
+				ParamSource::Path => continue,
+				ParamSource::Body if param.is_raw_json() => format!("{}:={}", param.name, value),
+				ParamSource::File => format!("{}@./{}", param.name, param.name),
+				_ => format!("{}{}", param.httpie_param_format(), value),
+			});
+		}
+		args
+	}
+
+	/// The required params of the endpoint, of the body only those that no
+	/// other required field is nested in
+	pub(super) fn required_params(ep: &EndPoint) -> Vec<&Param> {
+		let has_required_children = |param: &Param| {
+			let prefix = format!("{}[", param.name);
+			ep.params
+				.iter()
+				.any(|other| other.source.is_body() && other.required && other.name.starts_with(&prefix))
+		};
+		let params = ep.params.iter().filter(|param| param.required);
+		params.filter(|&param| !param.source.is_body() || !has_required_children(param)).collect()
+	}
+
+	/// Put values in place of the `:param` segments of a path, keeping the
+	/// segments of params without a value
+	pub(super) fn fill_path(path: &str, value: impl Fn(&str) -> Option<String>) -> String {
+		let segments = path.split('/').map(|segment| {
+			let value = segment.strip_prefix(':').and_then(&value);
+			value.unwrap_or_else(|| segment.to_string())
+		});
+		segments.collect::<Vec<_>>().join("/")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::openapi::EndPoints;
+
+	#[test]
+	fn test_invocation() {
+		let spec = serde_json::json!({
+			"openapi": "3.0.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": {
+				"/users/{id}/posts": {
+					"post": {
+						"parameters": [
+							{ "name": "id", "in": "path", "required": true, "schema": { "type": "integer" } },
+							{
+								"name": "X-Trace",
+								"in": "header",
+								"required": true,
+								"schema": { "type": "string" },
+								"example": "abc"
+							},
+							{ "name": "draft", "in": "query", "schema": { "type": "boolean" } }
+						],
+						"requestBody": {
+							"content": {
+								"application/json": {
+									"schema": {
+										"type": "object",
+										"required": ["title", "author", "views"],
+										"properties": {
+											"title": { "type": "string", "example": "Hello world" },
+											"views": { "type": "integer", "default": 0 },
+											"author": {
+												"type": "object",
+												"required": ["name"],
+												"properties": { "name": { "type": "string" } }
+											},
+											"tags": { "type": "array", "items": { "type": "string" } }
+										}
+									}
+								}
+							}
+						},
+						"responses": { "201": { "description": "created" } }
+					}
+				}
+			}
+		});
+		let endpoints = EndPoints::try_from_openapi(spec.to_string()).unwrap();
+		let ep = endpoints.find("/users/:id/posts").unwrap();
+		assert_eq!(
+			shell_words::join(ExampleCommand::invocation(ep, "https://api.example.com", true)),
+			"http POST https://api.example.com/users/1/posts X-Trace:abc \
+			 'author[name]=string' 'title=Hello world' 'views:=0'"
+		);
+	}
+}
@@ -0,0 +1,55 @@
+use anyhow::Context;
+use clap::Args;
+
+use crate::config::Config;
+use crate::theme::Theme;
+
+#[derive(Args, Debug)]
+#[command(
+	about = "Find endpoints by the fields they return",
+	long_about = r#"
+Find endpoints whose successful JSON responses contain a field, which helps
+answering "where can I get this value from?" when chaining requests.
+
+Examples:
+  httpie-oapi grep --response customerId
+  httpie-oapi grep --response customer. --name shop
+"#
+)]
+pub(super) struct GrepCommand {
+	/// Response field to search for, matched case-insensitively against
+	/// dotted field paths such as `customer.customerId`
+	#[arg(long, value_name = "FIELD")]
+	response: String,
+
+	/// Name of the API service (optional, search all APIs if not provided)
+	#[arg(short, long, value_name = "NAME")]
+	name: Option<String>,
+}
+
+impl GrepCommand {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		let apis = match &self.name {
+			Some(name) => {
+				vec![config.get_api(name).with_context(|| format!("API '{}' not found", name))?]
+			}
+			None => config.list_apis(),
+		};
+
+		let theme = Theme::for_stdout(config.theme);
+		for api in apis {
+			let endpoints = api.endpoints(config.is_offline());
+			for endpoint in endpoints.all() {
+				let fields = endpoint.matching_response_fields(&self.response);
+				if !fields.is_empty() {
+					println!(
+						"{}\t{}",
+						endpoint.fzf_list_format(&api.base_url, &theme),
+						theme.dim(&fields.join(", "))
+					);
+				}
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+
+use crate::config::{Config, GROUP_ENV};
+
+#[derive(Subcommand, Debug)]
+pub(super) enum GroupCommands {
+	/// List the groups with their number of APIs, marking the active one
+	#[command(visible_alias = "ls")]
+	List,
+	/// Limit the APIs suggested by completion to a group
+	Use(UseArgs),
+	/// Suggest the APIs of all groups again
+	Clear,
+}
+
+#[derive(Args, Debug)]
+pub(super) struct UseArgs {
+	/// Name of the group, as set with `spec add --group` or `spec set --group`
+	#[arg(value_name = "GROUP")]
+	name: String,
+}
+
+impl GroupCommands {
+	pub(super) fn run(&self, config: &mut Config) -> anyhow::Result<()> {
+		match self {
+			GroupCommands::List => Self::list(config),
+			GroupCommands::Use(args) => Self::use_group(args, config),
+			GroupCommands::Clear => {
+				config.active_group = None;
+				config.save();
+				println!("Completion suggests the APIs of all groups");
+				Self::warn_env();
+				Ok(())
+			}
+		}
+	}
+
+	fn list(config: &Config) -> anyhow::Result<()> {
+		let mut groups: BTreeMap<&str, usize> = BTreeMap::new();
+		for api in config.list_apis() {
+			if let Some(group) = &api.group {
+				*groups.entry(group).or_default() += 1;
+			}
+		}
+		if groups.is_empty() {
+			println!("No groups, add APIs to one with `spec set NAME --group GROUP`");
+			return Ok(());
+		}
+
+		let active = config.active_group();
+		for (group, count) in groups {
+			let marker = if active.as_deref() == Some(group) { "*" } else { " " };
+			println!("{} {}\t{} APIs", marker, group, count);
+		}
+		Ok(())
+	}
+
+	fn use_group(args: &UseArgs, config: &mut Config) -> anyhow::Result<()> {
+		if config.list_apis_in(Some(&args.name)).is_empty() {
+			return Err(anyhow!("No API belongs to group '{}'", args.name));
+		}
+		config.active_group = Some(args.name.clone());
+		config.save();
+		println!("Completion suggests the APIs of group '{}'", args.name);
+		Self::warn_env();
+		Ok(())
+	}
+
+	fn warn_env() {
+		if std::env::var_os(GROUP_ENV).is_some() {
+			eprintln!("Warning: {} is set and takes precedence in this shell", GROUP_ENV);
+		}
+	}
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use clap::{Args, Subcommand};
+
+use crate::config::Config;
+use crate::history::{History, HistoryEntry};
+use crate::theme::Theme;
+use crate::time::{format_age, now, parse_duration};
+
+#[derive(Subcommand, Debug)]
+pub(super) enum HistoryCommands {
+	/// List requests executed through `run`, newest first
+	#[command(visible_alias = "ls")]
+	List(ListArgs),
+	/// Show how often each endpoint was called
+	Usage(UsageArgs),
+}
+
+#[derive(Args, Debug)]
+pub(super) struct FilterArgs {
+	/// Only include failed requests (non-zero exit code or HTTP status >= 400)
+	#[arg(long)]
+	failed: bool,
+
+	/// Only include requests newer than the given duration
+	/// Example: --since 1d (s, m, h, d and w units are supported)
+	#[arg(long, value_name = "DURATION")]
+	since: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(super) struct ListArgs {
+	#[command(flatten)]
+	filter: FilterArgs,
+
+	/// Maximum number of requests to show
+	#[arg(long, short = 'n', default_value_t = 20)]
+	limit: usize,
+}
+
+#[derive(Args, Debug)]
+pub(super) struct UsageArgs {
+	#[command(flatten)]
+	filter: FilterArgs,
+}
+
+impl HistoryCommands {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		let theme = Theme::for_stdout(config.theme);
+		match self {
+			HistoryCommands::List(args) => Self::list(args, &theme),
+			HistoryCommands::Usage(args) => Self::usage(args, &theme),
+		}
+	}
+
+	fn list(args: &ListArgs, theme: &Theme) -> anyhow::Result<()> {
+		let entries = args.filter.load()?;
+		if entries.is_empty() {
+			println!("No requests recorded");
+			return Ok(());
+		}
+
+		for entry in entries.iter().rev().take(args.limit) {
+			println!(
+				"{}\t{}\t{}ms\t{}\t{}",
+				format_age(entry.timestamp),
+				format_outcome(entry),
+				entry.duration_ms,
+				format_size(entry.response_size),
+				format_request(entry.method.as_deref(), &entry.url, theme),
+			);
+		}
+		Ok(())
+	}
+
+	fn usage(args: &UsageArgs, theme: &Theme) -> anyhow::Result<()> {
+		let entries = args.filter.load()?;
+		if entries.is_empty() {
+			println!("No requests recorded");
+			return Ok(());
+		}
+
+		let mut usage: HashMap<(Option<&str>, &str), Vec<&HistoryEntry>> = HashMap::new();
+		for entry in &entries {
+			usage.entry((entry.method.as_deref(), &entry.url_template)).or_default().push(entry);
+		}
+		let mut usage: Vec<_> = usage.into_iter().collect();
+		usage.sort_by(|(a_key, a), (b_key, b)| b.len().cmp(&a.len()).then(a_key.cmp(b_key)));
+
+		println!("CALLS\tFAILED\tAVG\tLAST\tREQUEST");
+		for ((method, url_template), calls) in usage {
+			let failed = calls.iter().filter(|entry| entry.is_failed()).count();
+			let avg = calls.iter().map(|entry| entry.duration_ms).sum::<u64>() / calls.len() as u64;
+			let last = calls.iter().map(|entry| entry.timestamp).max().unwrap_or_default();
+			println!(
+				"{}\t{}\t{}ms\t{}\t{}",
+				calls.len(),
+				failed,
+				avg,
+				format_age(last),
+				format_request(method, url_template, theme),
+			);
+		}
+		Ok(())
+	}
+}
+
+impl FilterArgs {
+	/// Load the history entries matching the filter, oldest first
+	fn load(&self) -> anyhow::Result<Vec<HistoryEntry>> {
+		let since = match &self.since {
+			Some(since) => now().saturating_sub(parse_duration(since)?),
+			None => 0,
+		};
+		Ok(History::load()?
+			.into_iter()
+			.filter(|entry| entry.timestamp >= since)
+			.filter(|entry| !self.failed || entry.is_failed())
+			.collect())
+	}
+}
+
+fn format_outcome(entry: &HistoryEntry) -> String {
+	match (entry.status, entry.exit_code) {
+		(Some(status), _) => status.to_string(),
+		(None, Some(0)) => "ok".to_string(),
+		(None, Some(code)) => format!("exit {code}"),
+		(None, None) => "killed".to_string(),
+	}
+}
+
+fn format_request(method: Option<&str>, url: &str, theme: &Theme) -> String {
+	match method {
+		Some(method) => format!("{} {url}", theme.method(method)),
+		None => url.to_string(),
+	}
+}
+
+pub(super) fn format_size(bytes: u64) -> String {
+	match bytes {
+		0..1024 => format!("{bytes}B"),
+		1024..1048576 => format!("{:.1}KB", bytes as f64 / 1024.0),
+		_ => format!("{:.1}MB", bytes as f64 / 1048576.0),
+	}
+}
@@ -1,23 +1,62 @@
 mod api_spec;
+mod body;
+mod cache;
 mod complete;
 mod completion;
+mod curl;
+#[cfg(unix)]
+mod daemon;
+mod describe;
+mod edit_body;
+mod example;
+mod grep;
+mod group;
+mod history;
 mod param;
 mod path;
 mod path_var;
+mod response;
+mod run;
+mod search;
+mod tags;
+mod values;
+mod var;
 
 use api_spec::ApiSpecCommands;
+use body::BodyCommand;
+use cache::CacheCommands;
 use clap::Parser;
 use complete::CompleteCommand;
 use completion::CompletionsCommand;
+use curl::CurlCommand;
+#[cfg(unix)]
+use daemon::DaemonCommands;
+use describe::DescribeCommand;
+use edit_body::EditBodyCommand;
+use example::ExampleCommand;
+use grep::GrepCommand;
+use group::GroupCommands;
+use history::HistoryCommands;
 use param::ParamCommand;
 use path::PathCommand;
 use path_var::PathVarCommand;
+use response::ResponseCommand;
+use run::RunCommand;
+use search::SearchCommand;
+use tags::TagsCommand;
+use values::ValuesCommands;
+use var::VarCommands;
 
 use crate::config::Config;
 
 #[derive(Parser, Debug)]
 #[command(name = "httpie-oapi", author, version, about = "OpenAPI-aware completion for HTTPie")]
 pub struct Command {
+	/// Never download specs in completion and listing commands, use the caches
+	/// only
+	#[arg(long, global = true)]
+	offline: bool,
+
 	#[command(subcommand)]
 	sub_command: Commands,
 }
@@ -28,6 +67,18 @@ enum Commands {
 	Path(PathCommand),
 	/// List all parameters for a specific path
 	Param(ParamCommand),
+	/// Show the documented responses of an endpoint
+	Response(ResponseCommand),
+	/// Describe an endpoint: its params, body, security and responses
+	Describe(DescribeCommand),
+	/// Print a ready-to-run HTTPie command for an endpoint
+	Example(ExampleCommand),
+	/// Print the curl command calling an endpoint
+	Curl(CurlCommand),
+	/// Print a JSON skeleton of the request body of an endpoint
+	Body(BodyCommand),
+	/// Edit the request body of an endpoint in $EDITOR
+	EditBody(EditBodyCommand),
 	/// Internal command for shell completion
 	Complete(CompleteCommand),
 	/// Generate shell completion scripts
@@ -35,20 +86,81 @@ enum Commands {
 	/// Manage OpenAPI specifications
 	#[command(subcommand)]
 	Spec(ApiSpecCommands),
+	/// Manage the cached specs and endpoints
+	#[command(subcommand)]
+	Cache(CacheCommands),
 	/// Process path variables in URLs
 	#[command(name = "path-var")]
 	PathVar(PathVarCommand),
+	/// Run an HTTPie command and record it in the history
+	Run(RunCommand),
+	/// Find endpoints by the fields they return
+	Grep(GrepCommand),
+	/// Search operations across all APIs
+	Search(SearchCommand),
+	/// Scope completion to a group of APIs
+	#[command(subcommand)]
+	Group(GroupCommands),
+	/// List the tags of an API with their endpoint counts
+	Tags(TagsCommand),
+	/// Manage the background daemon that serves completions from memory
+	#[cfg(unix)]
+	#[command(subcommand)]
+	Daemon(DaemonCommands),
+	/// Inspect requests executed through `run`
+	#[command(subcommand)]
+	History(HistoryCommands),
+	/// Complete param values with data fetched from other endpoints of an API
+	#[command(
+		subcommand,
+		long_about = r#"
+Complete the values of params such as `projectId` with real IDs fetched from
+another endpoint of the API. Link a param to a GET endpoint and a selector of
+the values in its response in `config.toml`:
+
+  [apis.tracker.values.projectId]
+  path = "/projects"
+  select = "$[*].id"
+
+then fetch the values with `httpie-oapi values refresh tracker`. Completion
+only reads the fetched values, it never calls the API.
+"#
+	)]
+	Values(ValuesCommands),
+	/// Store values of path variables for `path-var` and completion
+	#[command(subcommand)]
+	Var(VarCommands),
 }
 
 impl Command {
 	pub fn run(&self, config: &mut Config) -> anyhow::Result<()> {
+		if self.offline {
+			config.set_offline();
+		}
 		match &self.sub_command {
 			Commands::Path(path_command) => path_command.run(config),
 			Commands::Param(param_command) => param_command.run(config),
+			Commands::Response(response_command) => response_command.run(config),
+			Commands::Describe(describe_command) => describe_command.run(config),
+			Commands::Example(example_command) => example_command.run(config),
+			Commands::Curl(curl_command) => curl_command.run(config),
+			Commands::Body(body_command) => body_command.run(config),
+			Commands::EditBody(edit_body_command) => edit_body_command.run(config),
 			Commands::Complete(complete_command) => complete_command.run(config),
 			Commands::Completions(completions_command) => completions_command.run(),
 			Commands::Spec(spec_command) => spec_command.run(config),
-			Commands::PathVar(path_var_command) => path_var_command.run(),
+			Commands::Cache(cache_command) => cache_command.run(config),
+			Commands::PathVar(path_var_command) => path_var_command.run(config),
+			Commands::Run(run_command) => run_command.run(config),
+			Commands::Grep(grep_command) => grep_command.run(config),
+			Commands::Search(search_command) => search_command.run(config),
+			Commands::Group(group_command) => group_command.run(config),
+			Commands::Tags(tags_command) => tags_command.run(config),
+			#[cfg(unix)]
+			Commands::Daemon(daemon_command) => daemon_command.run(),
+			Commands::History(history_command) => history_command.run(config),
+			Commands::Values(values_command) => values_command.run(config),
+			Commands::Var(var_command) => var_command.run(config),
 		}
 	}
 }
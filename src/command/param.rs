@@ -1,7 +1,11 @@
-use anyhow::Context;
-use clap::{ArgAction, Args};
+use anyhow::{Context, anyhow};
+use clap::{ArgAction, Args, ValueEnum};
+use serde::Serialize;
 
 use crate::config::Config;
+use crate::openapi::{EndPoints, Param};
+use crate::openapi::endpoint::EndPoint;
+use crate::theme::Theme;
 
 #[derive(Args, Debug)]
 pub struct ParamCommand {
@@ -9,32 +13,179 @@ pub struct ParamCommand {
 	#[arg(short, long, value_name = "NAME")]
 	name: String,
 
-	/// The API path to extract parameters from (e.g. `/users/{id}`)
-	#[arg(long, value_name = "PATH")]
-	path: String,
+	/// The API path to extract parameters from (e.g. `/users/{id}`), typed
+	/// values match its templated segments so URLs can be pasted (e.g.
+	/// `/users/42` or `https://api.example.com/users/42`)
+	#[arg(
+		long,
+		value_name = "PATH",
+		required_unless_present_any = ["operation", "webhook", "callback"]
+	)]
+	path: Option<String>,
+
+	/// The operationId of the endpoint to extract parameters from, in place
+	/// of its path (e.g. `getUserById`)
+	#[arg(long, value_name = "OPERATION_ID", conflicts_with = "path")]
+	operation: Option<String>,
+
+	/// The webhook of an OpenAPI 3.1 spec to extract the payload parameters
+	/// from, in place of a path (e.g. `newPet`)
+	#[arg(long, value_name = "WEBHOOK", conflicts_with_all = ["path", "operation"])]
+	webhook: Option<String>,
+
+	/// The callback of an operation to extract the payload parameters from,
+	/// in place of a path (e.g. `onPetAdopted`)
+	#[arg(long, value_name = "CALLBACK", conflicts_with_all = ["path", "operation", "webhook"])]
+	callback: Option<String>,
+
+	/// HTTP method of the endpoint when the path has several (e.g. `post`),
+	/// the params of all of them are listed otherwise
+	#[arg(long, value_name = "METHOD", conflicts_with_all = ["operation", "webhook", "callback"])]
+	method: Option<String>,
 
 	/// Optional pattern to filter parameters
 	#[arg(long, value_name = "PATTERN")]
 	pattern: Option<String>,
 
+	/// Output format, `json` lists the params of all matched operations at
+	/// once for scripts
+	#[arg(long, value_enum, default_value_t, conflicts_with_all = ["fish", "fzf", "table"])]
+	format: ParamFormat,
+
 	/// Output in fish shell completion format
-	#[arg(long, action = ArgAction::SetTrue, conflicts_with = "fzf")]
+	#[arg(long, action = ArgAction::SetTrue, conflicts_with_all = ["fzf", "table"])]
 	fish: bool,
 
 	/// Output in fzf-friendly list format (default)
-	#[arg(long, action = ArgAction::SetTrue, conflicts_with = "fish")]
+	#[arg(long, action = ArgAction::SetTrue, conflicts_with_all = ["fish", "table"])]
 	fzf: bool,
+
+	/// Output an aligned table of sources, names, types, requiredness and
+	/// descriptions for reading
+	#[arg(long, action = ArgAction::SetTrue)]
+	table: bool,
+}
+
+/// Formats `param` can write
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ParamFormat {
+	/// One param per line, in fzf-friendly or fish completion format
+	#[default]
+	Text,
+	/// A JSON array with one object per param
+	Json,
+}
+
+/// A param as listed by `param --format json`
+#[derive(Debug, Serialize)]
+struct Listing<'a> {
+	name: &'a str,
+	source: &'static str,
+	#[serde(rename = "type")]
+	schema_type: Option<&'a str>,
+	required: bool,
+	description: Option<&'a str>,
+	#[serde(rename = "enum")]
+	enum_values: &'a [String],
+	default: Option<&'a str>,
+	/// Method and path of the operation, which tell the params of the
+	/// operations of a path apart
+	method: String,
+	path: &'a str,
 }
 
 impl ParamCommand {
 	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
 		let api =
 			config.get_api(&self.name).with_context(|| format!("API '{}' not found", self.name))?;
-		let endpoints = api.get_endpoints();
-		let ep = endpoints
-			.find(&self.path)
-			.with_context(|| format!("No endpoint matched path '{}'", self.path))?;
+		let endpoints = api.endpoints(config.is_offline());
+		if config.is_offline() && endpoints.all().is_empty() {
+			return Ok(());
+		}
+		let eps = match (&self.callback, &self.webhook, &self.operation, &self.path) {
+			(Some(callback), ..) => vec![
+				endpoints
+					.find_callback(callback)
+					.with_context(|| format!("No callback named '{}'", callback))?,
+			],
+			(None, Some(webhook), ..) => vec![
+				endpoints
+					.find_webhook(webhook)
+					.with_context(|| format!("No webhook named '{}'", webhook))?,
+			],
+			(None, None, Some(operation), _) => vec![
+				endpoints
+					.find_operation(operation)
+					.with_context(|| format!("No endpoint has operationId '{}'", operation))?,
+			],
+			(None, None, None, Some(path)) => self.find_path(&endpoints, &api.base_url, path)?,
+			(None, None, None, None) => {
+				unreachable!("clap requires --path, --operation, --webhook or --callback")
+			}
+		};
+
+		if self.format == ParamFormat::Json {
+			let listings: Vec<_> = eps
+				.iter()
+				.flat_map(|ep| {
+					self.params(ep).into_iter().map(|param| Listing {
+						name: &param.name,
+						source: param.source.name(),
+						schema_type: param.schema_type.as_deref(),
+						required: param.required,
+						description: param.description.as_deref(),
+						enum_values: &param.enum_values,
+						default: param.default.as_deref(),
+						method: ep.method.to_string(),
+						path: &ep.path,
+					})
+				})
+				.collect();
+			println!("{}", serde_json::to_string_pretty(&listings)?);
+			return Ok(());
+		}
+
+		let theme = Theme::for_stdout(config.theme);
+		// Name the operation of each list when a path has several, unless the
+		// lists are for fish or fzf to read
+		let several = eps.len() > 1 && !self.fish && !self.fzf;
+		for (i, ep) in eps.into_iter().enumerate() {
+			if several {
+				let separator = if i == 0 { "" } else { "\n" };
+				println!("{}{} {}", separator, theme.method(&ep.method.to_string()), ep.path);
+			}
+			if self.table {
+				self.print_table(ep, &theme);
+			} else {
+				self.print_params(ep, &theme);
+			}
+		}
+		Ok(())
+	}
+
+	/// The operations of the path that a typed path or URL matches best, those
+	/// of `--method` if given
+	fn find_path<'a>(
+		&self,
+		endpoints: &'a EndPoints,
+		base_url: &str,
+		path: &str,
+	) -> anyhow::Result<Vec<&'a EndPoint>> {
+		let mut eps = endpoints.find_operations(base_url, path);
+		if let Some(method) = &self.method {
+			eps.retain(|ep| ep.method.to_string().eq_ignore_ascii_case(method));
+		}
+		if eps.is_empty() {
+			return Err(match &self.method {
+				Some(method) => anyhow!("No {} endpoint matched path '{}'", method.to_uppercase(), path),
+				None => anyhow!("No endpoint matched path '{}'", path),
+			});
+		}
+		Ok(eps)
+	}
 
+	/// The params of an endpoint that match the pattern, required ones first
+	fn params<'a>(&self, ep: &'a EndPoint) -> Vec<&'a Param> {
 		let mut filtered_params: Vec<_> = if let Some(pat) = &self.pattern {
 			ep.params.iter().filter(|param| param.name.contains(pat)).collect()
 		} else {
@@ -42,14 +193,49 @@ impl ParamCommand {
 		};
 
 		filtered_params.sort_by_key(|&p| !p.required);
+		filtered_params
+	}
+
+	fn print_table(&self, ep: &EndPoint, theme: &Theme) {
+		const HEADERS: [&str; 5] = ["SOURCE", "NAME", "TYPE", "REQUIRED", "DESCRIPTION"];
+		let rows: Vec<[&str; 5]> = self
+			.params(ep)
+			.into_iter()
+			.map(|param| {
+				let required = if param.required { "yes" } else { "no" };
+				// Only the first line of a description fits in a column
+				let description = param.description.as_deref().unwrap_or_default();
+				let description = description.lines().next().unwrap_or_default();
+				let schema_type = param.schema_type.as_deref().unwrap_or_default();
+				[param.source.name(), &param.name, schema_type, required, description]
+			})
+			.collect();
+		let mut widths = HEADERS.map(str::len);
+		for row in &rows {
+			for (width, cell) in widths.iter_mut().zip(row) {
+				*width = (*width).max(cell.chars().count());
+			}
+		}
+		let format_row = |row: &[&str; 5]| {
+			let cells = row.iter().zip(widths).map(|(cell, width)| format!("{:<width$}", cell));
+			cells.collect::<Vec<_>>().join("  ").trim_end().to_string()
+		};
+		println!("{}", theme.dim(&format_row(&HEADERS)));
+		for row in &rows {
+			println!("{}", format_row(row));
+		}
+	}
 
-		for param in filtered_params {
+	fn print_params(&self, ep: &EndPoint, theme: &Theme) {
+		for param in self.params(ep) {
 			if self.fish {
 				println!("{}", param);
 			} else {
-				println!("{}", param.fish_complete_format());
+				match theme.required_marker(param.required) {
+					Some(marker) => println!("{} {}", marker, param.fish_complete_format()),
+					None => println!("{}", param.fish_complete_format()),
+				}
 			}
 		}
-		Ok(())
 	}
 }
@@ -1,9 +1,13 @@
 use anyhow::Context;
-use clap::{ArgAction, Args};
+use clap::{ArgAction, Args, ValueEnum};
+use serde::Serialize;
 use tracing::debug;
 
 use crate::config::Config;
-use crate::openapi::ApiSpec;
+use crate::openapi::endpoint::{Callback, EndPoint};
+use crate::openapi::path_filter::PathFilter;
+use crate::openapi::{ApiSpec, EndPoints, Method};
+use crate::theme::Theme;
 
 #[derive(Args, Debug)]
 pub struct PathCommand {
@@ -11,56 +15,325 @@ pub struct PathCommand {
 	#[arg(short, long, value_name = "NAME")]
 	name: Option<String>,
 
+	/// Only show the paths of the APIs of a group
+	#[arg(long, short, value_name = "GROUP", conflicts_with = "name")]
+	group: Option<String>,
+
 	/// Optional filter to match specific paths
 	#[arg(long, value_name = "PATTERN")]
 	pattern: Option<String>,
 
+	/// Only show the paths a regular expression matches (e.g.
+	/// `^/v2/.*/(orders|refunds)`)
+	#[arg(long, value_name = "REGEX", value_parser = PathFilter::regex, conflicts_with = "pattern")]
+	regex: Option<PathFilter>,
+
+	/// Only show the paths a glob matches as a whole, `*` within a segment and
+	/// `**` across segments (e.g. `/v2/**/orders`)
+	#[arg(
+		long,
+		value_name = "GLOB",
+		value_parser = PathFilter::glob,
+		conflicts_with_all = ["pattern", "regex"]
+	)]
+	glob: Option<PathFilter>,
+
+	/// Only show the endpoints of these HTTP methods (e.g. `GET` or
+	/// `post,put,patch,delete`)
+	#[arg(long, short, value_name = "METHODS", value_delimiter = ',', value_parser = parse_method)]
+	method: Vec<Method>,
+
+	/// Only show the endpoints grouped under a tag, see the `tags` command
+	#[arg(long, short, value_name = "TAG")]
+	tag: Option<String>,
+
+	/// List the webhooks of OpenAPI 3.1 specs, the requests the API sends,
+	/// in place of the paths
+	#[arg(long, action = ArgAction::SetTrue)]
+	webhooks: bool,
+
+	/// List the callbacks of the operations, the requests the API sends back
+	/// to the caller, in place of the paths
+	#[arg(long, action = ArgAction::SetTrue, conflicts_with = "webhooks")]
+	callbacks: bool,
+
+	/// Output format, `json` and `tsv` list the endpoints of all APIs at once
+	/// for scripts
+	#[arg(long, value_enum, default_value_t, conflicts_with_all = ["fish", "fzf", "table"])]
+	format: PathFormat,
+
 	/// Output in fish shell completion format
-	#[arg(long, action = ArgAction::SetTrue, conflicts_with = "fzf")]
+	#[arg(long, action = ArgAction::SetTrue, conflicts_with_all = ["fzf", "table"])]
 	fish: bool,
 
 	/// Output in fzf-friendly list format (default)
-	#[arg(long, action = ArgAction::SetTrue, conflicts_with = "fish")]
+	#[arg(long, action = ArgAction::SetTrue, conflicts_with_all = ["fish", "table"])]
 	fzf: bool,
+
+	/// Output an aligned table of methods, paths and summaries for reading
+	#[arg(long, action = ArgAction::SetTrue)]
+	table: bool,
+
+	/// Print the endpoints under a heading for each of their tags, like
+	/// Swagger UI, endpoints without tags last
+	#[arg(long, value_enum, value_name = "GROUP_BY", conflicts_with_all = ["fish", "format"])]
+	group_by: Option<GroupBy>,
+}
+
+/// What `path --group-by` groups the endpoints by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum GroupBy {
+	Tag,
+}
+
+/// Heading of the endpoints without tags in `path --group-by tag`
+const UNTAGGED: &str = "(untagged)";
+
+/// Formats `path` can write
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum PathFormat {
+	/// One endpoint per line, in fzf-friendly or fish completion format
+	#[default]
+	Text,
+	/// A JSON array with one object per endpoint
+	Json,
+	/// Tab separated values with a header row
+	Tsv,
+}
+
+/// An endpoint as listed by `path --format json` or `tsv`
+#[derive(Debug, Serialize)]
+struct Listing<'a> {
+	api: &'a str,
+	method: String,
+	path: &'a str,
+	summary: Option<&'a str>,
+	operation_id: Option<&'a str>,
+	tags: &'a [String],
+	deprecated: bool,
+	/// Name of the callback the endpoint is the request of
+	#[serde(skip_serializing_if = "Option::is_none")]
+	callback: Option<&'a str>,
+}
+
+/// An endpoint that matches the filters, along with the callback it is the
+/// request of and the operation of that callback
+#[derive(Clone, Copy)]
+struct Selected<'a> {
+	endpoint: &'a EndPoint,
+	callback: Option<(&'a Callback, &'a EndPoint)>,
+}
+
+impl<'a> Selected<'a> {
+	/// Tags of the endpoint, callbacks are tagged after their operation
+	fn tags(&self) -> &'a [String] {
+		match self.callback {
+			Some((_, operation)) => &operation.tags,
+			None => &self.endpoint.tags,
+		}
+	}
 }
 
 impl PathCommand {
 	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
-		match &self.name {
+		let theme = Theme::for_stdout(config.theme);
+		let apis = match &self.name {
 			Some(name) => {
 				// Show paths for a specific API
 				debug!("Showing paths for API: {}", name);
-				let api = config.get_api(name)
-					.with_context(|| format!("API '{}' not found", name))?;
-				self.show_api_paths(api)
+				vec![config.get_api(name).with_context(|| format!("API '{}' not found", name))?]
 			}
 			None => {
 				// Show paths for all APIs
 				debug!("Showing paths for all APIs");
-				for api in config.list_apis() {
-					self.show_api_paths(api)?;
-				}
-				Ok(())
+				config.list_apis_in(self.group.as_deref())
 			}
+		};
+		if self.format != PathFormat::Text {
+			return self.print_listings(&apis, config);
+		}
+		let several = apis.len() > 1;
+		for (i, api) in apis.into_iter().enumerate() {
+			// Tables and groups are headed by their API, lines carry its base URL
+			if several && (self.table || self.group_by.is_some()) {
+				let separator = if i == 0 { "" } else { "\n" };
+				println!("{}{} {}", separator, api.name, theme.dim(&api.base_url));
+			}
+			self.show_api_paths(api, config, &theme);
 		}
+		Ok(())
 	}
 
-	fn show_api_paths(&self, api: &ApiSpec) -> anyhow::Result<()> {
-		let endpoints = api.get_endpoints();
-		let filtered: Vec<_> = if let Some(pattern) = &self.pattern {
-			endpoints.filter(pattern)
-		} else {
-			endpoints.all()
+	fn show_api_paths(&self, api: &ApiSpec, config: &Config, theme: &Theme) {
+		let endpoints = api.endpoints(config.is_offline());
+		let selected = self.select(&endpoints);
+		let groups = match self.group_by {
+			Some(GroupBy::Tag) => group_by_tag(selected),
+			None => vec![(None, selected)],
 		};
+		if self.table {
+			Self::print_table(&groups, theme);
+			return;
+		}
+		for (i, (tag, group)) in groups.iter().enumerate() {
+			if let Some(tag) = tag {
+				println!("{}{}", if i == 0 { "" } else { "\n" }, tag);
+			}
+			self.print_lines(api, group, theme);
+		}
+	}
+
+	fn print_table(groups: &[(Option<&str>, Vec<Selected>)], theme: &Theme) {
+		let rows = || groups.iter().flat_map(|(_, group)| group);
+		let method_width =
+			rows().map(|row| row.endpoint.method.to_string().len()).fold("METHOD".len(), usize::max);
+		let path_width =
+			rows().map(|row| row.endpoint.path.chars().count()).fold("PATH".len(), usize::max);
+		let header = format!("{:<method_width$}  {:<path_width$}  SUMMARY", "METHOD", "PATH");
+		println!("{}", theme.dim(header.trim_end()));
+		for (i, (tag, group)) in groups.iter().enumerate() {
+			if let Some(tag) = tag {
+				println!("{}{}", if i == 0 { "" } else { "\n" }, tag);
+			}
+			for Selected { endpoint, .. } in group {
+				let method = endpoint.method.to_string();
+				let mut summary = endpoint.summary.clone().unwrap_or_default();
+				if let Some(marker) = theme.deprecated_marker().filter(|_| endpoint.deprecated) {
+					summary = format!("{} {}", summary, marker).trim_start().to_string();
+				}
+				// Pad outside of the color codes of the method to keep the columns aligned
+				let padding = " ".repeat(method_width - method.len());
+				let line = format!(
+					"{}{}  {:<path_width$}  {}",
+					theme.method(&method),
+					padding,
+					endpoint.path,
+					summary
+				);
+				println!("{}", line.trim_end());
+			}
+		}
+	}
 
-		for endpoint in filtered {
+	fn print_lines(&self, api: &ApiSpec, selected: &[Selected], theme: &Theme) {
+		// Webhooks and callbacks are named rather than served under the base URL
+		let base_url = if self.webhooks || self.callbacks { "" } else { &api.base_url };
+		for &Selected { endpoint, callback } in selected {
+			let Some((callback, operation)) = callback else {
+				if self.fish {
+					println!("{}", endpoint.fish_complete_format(base_url));
+				} else {
+					println!("{}", endpoint.fzf_list_format(base_url, theme));
+				}
+				continue;
+			};
+			let origin = format!("{} of {} {}", callback.name, operation.method, operation.path);
 			if self.fish {
-				println!("{}", endpoint.fish_complete_format(&api.base_url));
+				println!("{}\t{}", endpoint.path, origin);
 			} else {
-				println!("{}", endpoint.fzf_list_format(&api.base_url));
+				println!("{}\t{}", endpoint.fzf_list_format(base_url, theme), theme.dim(&origin));
 			}
 		}
+	}
 
+	fn print_listings(&self, apis: &[&ApiSpec], config: &Config) -> anyhow::Result<()> {
+		let endpoints: Vec<_> =
+			apis.iter().map(|api| (&api.name, api.endpoints(config.is_offline()))).collect();
+		let listings: Vec<_> = endpoints
+			.iter()
+			.flat_map(|(api, endpoints)| {
+				self.select(endpoints).into_iter().map(|Selected { endpoint, callback }| Listing {
+					api,
+					method: endpoint.method.to_string(),
+					path: &endpoint.path,
+					summary: endpoint.summary.as_deref(),
+					operation_id: endpoint.operation_id.as_deref(),
+					tags: &endpoint.tags,
+					deprecated: endpoint.deprecated,
+					callback: callback.map(|(callback, _)| callback.name.as_str()),
+				})
+			})
+			.collect();
+		if self.format == PathFormat::Json {
+			println!("{}", serde_json::to_string_pretty(&listings)?);
+			return Ok(());
+		}
+		println!("api\tmethod\tpath\tsummary\toperation_id\ttags\tdeprecated");
+		for listing in listings {
+			println!(
+				"{}\t{}\t{}\t{}\t{}\t{}\t{}",
+				listing.api,
+				listing.method,
+				listing.path,
+				listing.summary.unwrap_or_default(),
+				listing.operation_id.unwrap_or_default(),
+				listing.tags.join(","),
+				listing.deprecated
+			);
+		}
 		Ok(())
 	}
+
+	/// The endpoints, webhooks or callbacks of an API that match the filters
+	fn select<'a>(&self, endpoints: &'a EndPoints) -> Vec<Selected<'a>> {
+		let filter = match (&self.regex, &self.glob) {
+			(Some(filter), _) | (None, Some(filter)) => filter.clone(),
+			(None, None) => PathFilter::from(self.pattern.as_deref().unwrap_or_default()),
+		};
+		if self.callbacks {
+			// Callbacks are tagged after the operation they belong to
+			let callbacks = endpoints.callbacks().into_iter().filter(|(operation, callback)| {
+				(filter.matches(&callback.name) || filter.matches(&callback.endpoint.path))
+					&& self.has_method(&callback.endpoint)
+					&& self.has_tag(operation)
+			});
+			return callbacks
+				.map(|(operation, callback)| Selected {
+					endpoint: &callback.endpoint,
+					callback: Some((callback, operation)),
+				})
+				.collect();
+		}
+		let mut filtered: Vec<_> = if self.webhooks {
+			endpoints.webhooks().into_iter().filter(|webhook| filter.matches(&webhook.path)).collect()
+		} else {
+			endpoints.filter(filter)
+		};
+		filtered.retain(|endpoint| self.has_method(endpoint) && self.has_tag(endpoint));
+		filtered.into_iter().map(|endpoint| Selected { endpoint, callback: None }).collect()
+	}
+
+	fn has_method(&self, endpoint: &EndPoint) -> bool {
+		self.method.is_empty() || self.method.contains(&endpoint.method)
+	}
+
+	fn has_tag(&self, endpoint: &EndPoint) -> bool {
+		self.tag.as_ref().is_none_or(|tag| endpoint.has_tag(tag))
+	}
+}
+
+/// Group endpoints under each of their tags, in the order the tags first
+/// appear, endpoints without tags last
+fn group_by_tag<'a>(selected: Vec<Selected<'a>>) -> Vec<(Option<&'a str>, Vec<Selected<'a>>)> {
+	let mut groups: Vec<(Option<&str>, Vec<Selected>)> = Vec::new();
+	let mut untagged = Vec::new();
+	for row in selected {
+		if row.tags().is_empty() {
+			untagged.push(row);
+		}
+		for tag in row.tags() {
+			match groups.iter_mut().find(|(name, _)| *name == Some(tag.as_str())) {
+				Some((_, group)) => group.push(row),
+				None => groups.push((Some(tag), vec![row])),
+			}
+		}
+	}
+	if !untagged.is_empty() {
+		groups.push((Some(UNTAGGED), untagged));
+	}
+	groups
+}
+
+fn parse_method(s: &str) -> Result<Method, String> {
+	Method::try_from(s.trim()).map_err(|e| e.to_string())
 }
@@ -1,8 +1,20 @@
 use std::collections::{HashMap, HashSet};
+use std::io::Write;
 
+use anyhow::anyhow;
 use clap::Args;
 use tracing::{debug, info, trace, warn};
 
+use crate::config::Config;
+use crate::openapi::ApiSpec;
+use crate::openapi::endpoint::EndPoint;
+use crate::openapi::param::ParamSource;
+use crate::vars::VarStore;
+
+/// Prefix of the environment variables holding default values of path
+/// variables, e.g. `HTTPIE_OAPI_VAR_tenantId` for `:tenantId`
+const VAR_ENV_PREFIX: &str = "HTTPIE_OAPI_VAR_";
+
 #[derive(Args, Debug)]
 #[command(
 	name = "path-var",
@@ -20,9 +32,31 @@ Examples:
 
   # With other httpie options
   httpie-oapi path-var -- http :8080/api/v1/users/:id -v :id=123 --json --offline
+
+  # Values are checked against the cached spec of the API the URL belongs to
+  httpie-oapi path-var -- http https://api.example.com/users/:id :id=abc
+
+  # Values with spaces or quotes survive when the output is evaluated
+  eval "$(httpie-oapi path-var -- http :8080/users/:id :id=123 name="John Doe")"
+  eval (httpie-oapi path-var --fish -- http :8080/users/:id :id=123 name="John Doe")
+  httpie-oapi path-var --print0 -- http :8080/users/:id :id=123 | string split0
+
+  # Take unassigned variables from the environment, or from the variables
+  # stored with `httpie-oapi var set`
+  export HTTPIE_OAPI_VAR_tenantId=acme
+  httpie-oapi path-var -- http :8080/tenants/:tenantId/users
 "#
 )]
 pub(super) struct PathVarCommand {
+	/// Separate the arguments with NUL bytes rather than quoting them for the
+	/// shell, for `string split0` in fish or `xargs -0`
+	#[arg(long)]
+	print0: bool,
+
+	/// Quote the arguments for fish rather than for POSIX shells
+	#[arg(long, conflicts_with = "print0")]
+	fish: bool,
+
 	/// Raw command line arguments
 	#[arg(raw = true)]
 	args: Vec<String>,
@@ -30,20 +64,22 @@ pub(super) struct PathVarCommand {
 
 impl PathVarCommand {
 	/// Process the command line and execute the path variable replacement
-	pub(super) fn run(&self) -> anyhow::Result<()> {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
 		info!("Processing command line: {:?}", self.args);
-		let result = self.process_command_line();
-		Self::write_result(&result);
+		let result = Self::process_args(&self.args, config)?;
+		Self::write_result(&result, self.print0, self.fish)?;
 		info!("Command processed successfully");
 		Ok(())
 	}
 
 	/// Process the command line and return the processed arguments
-	fn process_command_line(&self) -> Vec<String> {
-		let mut args = self.args.clone();
+	///
+	/// Fails when a value does not fit the path param of the spec.
+	pub(super) fn process_args(args: &[String], config: &Config) -> anyhow::Result<Vec<String>> {
+		let mut args = args.to_vec();
 		if args.is_empty() {
 			debug!("Empty command line, returning as is");
-			return args;
+			return Ok(args);
 		}
 
 		// Find the URL (first argument that matches URL patterns)
@@ -52,7 +88,7 @@ impl PathVarCommand {
 
 		let Some(url_index) = url_index else {
 			debug!("No URL found in command line, returning as is");
-			return args;
+			return Ok(args);
 		};
 
 		let url = &args[url_index];
@@ -64,15 +100,38 @@ impl PathVarCommand {
 
 		if path_vars.is_empty() {
 			debug!("No path variables found in URL, returning as is");
-			return args;
+			return Ok(args);
 		}
 
 		// Process path variable assignments
-		let (var_values, remaining_args) =
+		let (mut var_values, remaining_args) =
 			Self::process_var_assignments(&args[url_index + 1..], &path_vars);
+		Self::fill_unassigned(&path_vars, &mut var_values, |name| {
+			std::env::var(format!("{}{}", VAR_ENV_PREFIX, name)).ok()
+		});
+		let api = Self::api_of(url, config);
+		if path_vars.iter().any(|var| !var_values.contains_key(var)) {
+			let api = api.map(|api| api.name.as_str());
+			match VarStore::load() {
+				Ok(vars) => Self::fill_unassigned(&path_vars, &mut var_values, |name| {
+					vars.get(api.unwrap_or_default(), name).map(str::to_string)
+				}),
+				Err(e) => warn!("Failed to load variables: {:#}", e),
+			}
+		}
 		debug!("Path variable values: {:?}", var_values);
 		debug!("Remaining arguments: {:?}", remaining_args);
 
+		// Only cached specs are used, the request is not held up by a download
+		if let Some(api) = api {
+			let endpoints = api.endpoints(true);
+			let url = Self::expand_shorthand(url);
+			let path = EndPoint::relative_path(&api.base_url, &url);
+			if let Some(ep) = endpoints.find_matching(path.as_ref()).first() {
+				Self::check_values(ep, &var_values)?;
+			}
+		}
+
 		// Replace path variables in URL
 		let processed_url = Self::replace_path_vars(url, &path_vars, &var_values);
 		debug!("Processed URL: {}", processed_url);
@@ -83,7 +142,7 @@ impl PathVarCommand {
 		result.extend(args[..=url_index].iter().cloned());
 		result.extend(remaining_args);
 		debug!("Final command: {:?}", result);
-		result
+		Ok(result)
 	}
 
 	/// Check if a string is a valid URL or URL-like string
@@ -100,17 +159,27 @@ impl PathVarCommand {
 	///
 	/// The host part must be a valid URL-like string (domain:port or port-only),
 	/// while the path part can contain any characters.
-	fn is_url_like(s: &str) -> bool {
+	pub(super) fn is_url_like(s: &str) -> bool {
 		// Check for full URLs
 		if s.starts_with("http://") || s.starts_with("https://") {
 			debug!("String is a full URL: {}", s);
 			return true;
 		}
 
-		// Split into host and path parts
-		let Some((host, _)) = s.split_once('/') else {
-            return false;
-        };
+		// Split into host and path parts; without a path the whole string must be
+		// a host with an explicit port (e.g. `localhost:8080` or `:8080`)
+		let host = match s.split_once('/') {
+			Some((host, _)) => host,
+			None => {
+				let Some((_, port)) = s.rsplit_once(':') else {
+					return false;
+				};
+				if port.is_empty() || !port.chars().all(|c| c.is_ascii_digit()) {
+					return false;
+				}
+				s
+			}
+		};
 
 		// Check if host part is a valid URL-like string
 		let is_valid = host.chars().all(|c| c.is_alphanumeric() || c == '.' || c == '-' || c == ':');
@@ -124,10 +193,12 @@ impl PathVarCommand {
 
 	/// Extract path variables from a URL
 	///
-	/// Returns a HashSet of path variables found in the URL.
-	/// A path variable is a string that starts with ':' followed by
-	/// multiple letters, numbers, or underscores
+	/// Returns a HashSet of path variables found in the URL, as they are
+	/// written there. A path variable is a segment that starts with ':'
+	/// followed by multiple letters, numbers, or underscores
 	/// eg :id, :postId, :id123, :id_123, :_id, :id_123_456
+	/// or one in OpenAPI style braces, as in paths pasted from a spec
+	/// eg {id}, {postId}
 	///
 	/// # Examples
 	/// ```
@@ -141,16 +212,27 @@ impl PathVarCommand {
 	fn extract_path_vars(url: &str) -> HashSet<String> {
 		trace!("Extracting path variables from URL: {}", url);
 		let vars: HashSet<_> = url.split('/')
-			.filter(|s| s.starts_with(':'))
-			.filter(|s| s.len() > 1)
+			.filter(|s| s.starts_with(':') || (s.starts_with('{') && s.ends_with('}')))
+			.filter(|s| !Self::var_name(s).is_empty())
 			.map(|s| s.to_string())
 			.collect();
 		debug!("Found path variables: {:?}", vars);
 		vars
 	}
 
+	/// The name of a path variable without its colon or braces, e.g. `id` for
+	/// `:id` and `{id}`
+	fn var_name(var: &str) -> &str {
+		match var.strip_prefix('{') {
+			Some(var) => var.strip_suffix('}').unwrap_or(var),
+			None => var.strip_prefix(':').unwrap_or(var),
+		}
+	}
+
 	/// Process path variable assignments from command line arguments
 	///
+	/// An assignment `:id=123` assigns both `:id` and `{id}`.
+	///
 	/// Returns a tuple containing:
 	/// - A HashMap of variable names to their values
 	/// - A Vec of remaining arguments that are not path variable assignments
@@ -165,10 +247,15 @@ impl PathVarCommand {
 		let mut remaining_args = Vec::new();
 
 		for arg in args {
-			if let Some((var_name, value)) = arg.split_once('=') {
-				if var_name.starts_with(':') && path_vars.contains(var_name) {
+			if let Some((var_name, value)) = arg.split_once('=')
+				&& let Some(name) = var_name.strip_prefix(':')
+			{
+				let vars: Vec<_> = path_vars.iter().filter(|var| Self::var_name(var) == name).collect();
+				if !vars.is_empty() {
 					debug!("Found variable assignment: {} = {}", var_name, value);
-					var_values.insert(var_name.to_string(), value.to_string());
+					for var in vars {
+						var_values.insert(var.clone(), value.to_string());
+					}
 					continue;
 				}
 			}
@@ -181,6 +268,61 @@ impl PathVarCommand {
 		(var_values, remaining_args)
 	}
 
+	/// The API whose base URL the URL starts with, the longest one when
+	/// several do
+	///
+	/// URLs in HTTPie's `:8080/users` shorthand are on localhost.
+	fn api_of<'a>(url: &str, config: &'a Config) -> Option<&'a ApiSpec> {
+		let url = Self::expand_shorthand(url);
+		let apis = config.list_apis().into_iter();
+		let apis =
+			apis.filter(|api| !api.base_url.is_empty() && ApiSpec::is_under(&url, &api.base_url));
+		apis.max_by_key(|api| api.base_url.len())
+	}
+
+	/// Expand HTTPie's `:8080/users` shorthand for localhost URLs
+	fn expand_shorthand(url: &str) -> String {
+		match url.starts_with(':') {
+			true => format!("http://localhost{}", url),
+			false => url.to_string(),
+		}
+	}
+
+	/// Check the values of the variables against the path params of an
+	/// endpoint, failing on the first that does not fit
+	fn check_values(ep: &EndPoint, var_values: &HashMap<String, String>) -> anyhow::Result<()> {
+		let mut var_values: Vec<_> = var_values.iter().collect();
+		var_values.sort();
+		for (var, value) in var_values {
+			let name = Self::var_name(var);
+			let param = ep.params.iter().find(|p| p.source == ParamSource::Path && p.name == name);
+			if let Some(param) = param
+				&& let Err(reason) = param.check_value(value)
+			{
+				return Err(anyhow!("Invalid value '{}' for :{} of {}: {}", value, name, ep.path, reason));
+			}
+		}
+		Ok(())
+	}
+
+	/// Take the values of the unassigned variables from a fallback source,
+	/// which is given variable names without their colon
+	fn fill_unassigned(
+		path_vars: &HashSet<String>,
+		var_values: &mut HashMap<String, String>,
+		fallback: impl Fn(&str) -> Option<String>,
+	) {
+		for var in path_vars {
+			if var_values.contains_key(var) {
+				continue;
+			}
+			if let Some(value) = fallback(Self::var_name(var)) {
+				debug!("Using fallback value for {}: {}", var, value);
+				var_values.insert(var.clone(), value);
+			}
+		}
+	}
+
 	/// Replace path variables in URL with their values
 	fn replace_path_vars(
 		url: &str,
@@ -204,10 +346,39 @@ impl PathVarCommand {
 		result
 	}
 
-	/// Write the result to stdout
-	fn write_result(result: &[String]) {
+	/// Write the result to stdout, quoted for the shell or separated by NUL
+	/// bytes
+	fn write_result(result: &[String], print0: bool, fish: bool) -> std::io::Result<()> {
 		debug!("Writing result to stdout: {:?}", result);
-		println!("{}", result.join(" "));
+		let mut stdout = std::io::stdout().lock();
+		match (print0, fish) {
+			(true, _) => result.iter().try_for_each(|arg| write!(stdout, "{}\0", arg)),
+			(false, true) => writeln!(stdout, "{}", Self::quote_fish(result)),
+			(false, false) => writeln!(stdout, "{}", Self::quote(result)),
+		}
+	}
+
+	/// Join the arguments into a command line a POSIX shell splits back into
+	/// them
+	fn quote(args: &[String]) -> String { shell_words::join(args) }
+
+	/// Join the arguments into a command line fish splits back into them
+	///
+	/// Quoting follows `string escape`: fish reads `\\` and `\'` as escapes
+	/// inside single quotes, and newlines are escaped outside of them since
+	/// command substitution splits its output into lines.
+	fn quote_fish(args: &[String]) -> String {
+		let quote = |arg: &String| {
+			let plain = |c: char| c.is_ascii_alphanumeric() || "-_=/:.,@+".contains(c);
+			if !arg.is_empty() && arg.chars().all(plain) {
+				return arg.clone();
+			}
+			let lines = arg.split('\n');
+			let lines =
+				lines.map(|line| format!("'{}'", line.replace('\\', "\\\\").replace('\'', "\\'")));
+			lines.collect::<Vec<_>>().join("\\n")
+		};
+		args.iter().map(quote).collect::<Vec<_>>().join(" ")
 	}
 }
 
@@ -226,6 +397,10 @@ mod tests {
 			("/users/:", vec![]),
 			("/users/:123", vec![":123"]),
 			("/users/:_id", vec![":_id"]),
+			("/users/{id}/posts/{postId}", vec!["{id}", "{postId}"]),
+			("/users/{id}/posts/:postId", vec!["{id}", ":postId"]),
+			("/users/{}", vec![]),
+			("/users/{id", vec![]),
 		];
 
 		for (input, expected) in test_cases {
@@ -256,6 +431,133 @@ mod tests {
 		assert_eq!(remaining, vec!["-v", "--json", ":unknown=789"]);
 	}
 
+	#[test]
+	fn test_braced_path_vars() {
+		let url = "/users/{id}/posts/{postId}";
+		let path_vars = PathVarCommand::extract_path_vars(url);
+		let args = vec![":id=123".to_string(), ":postId=456".to_string(), "-v".to_string()];
+
+		let (var_values, remaining) = PathVarCommand::process_var_assignments(&args, &path_vars);
+		assert_eq!(remaining, vec!["-v"]);
+		let result = PathVarCommand::replace_path_vars(url, &path_vars, &var_values);
+		assert_eq!(result, "/users/123/posts/456");
+	}
+
+	#[test]
+	fn test_check_values() {
+		let spec = serde_json::json!({
+			"openapi": "3.0.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": {
+				"/orgs/{org}/users/{id}/keys/{key}": {
+					"get": {
+						"parameters": [
+							{
+								"name": "org",
+								"in": "path",
+								"required": true,
+								"schema": { "type": "string", "enum": ["acme", "globex"] }
+							},
+							{ "name": "id", "in": "path", "required": true, "schema": { "type": "integer" } },
+							{
+								"name": "key",
+								"in": "path",
+								"required": true,
+								"schema": { "type": "string", "format": "uuid", "pattern": "^[0-9a-f-]+$" }
+							}
+						],
+						"responses": { "200": { "description": "ok" } }
+					}
+				}
+			}
+		});
+		let endpoints = crate::openapi::EndPoints::try_from_openapi(spec.to_string()).unwrap();
+		let ep = endpoints.find("/orgs/:org/users/:id/keys/:key").unwrap();
+		let check = |values: &[(&str, &str)]| {
+			let values = values.iter().map(|(var, value)| (var.to_string(), value.to_string()));
+			PathVarCommand::check_values(ep, &values.collect()).map_err(|e| e.to_string())
+		};
+		let uuid = "123e4567-e89b-12d3-a456-426614174000";
+
+		assert!(check(&[(":org", "acme"), (":id", "42"), ("{key}", uuid)]).is_ok());
+		assert_eq!(
+			check(&[(":id", "abc")]),
+			Err("Invalid value 'abc' for :id of /orgs/:org/users/:id/keys/:key: expected integer".into())
+		);
+		assert_eq!(
+			check(&[(":org", "initech")]),
+			Err(
+				"Invalid value 'initech' for :org of /orgs/:org/users/:id/keys/:key: expected one of \
+			     acme, globex"
+					.into()
+			)
+		);
+		assert!(check(&[(":key", "42")]).unwrap_err().ends_with("expected string (uuid)"));
+		assert!(
+			check(&[(":key", "123E4567-E89B-12D3-A456-426614174000")])
+				.unwrap_err()
+				.ends_with("expected a value matching ^[0-9a-f-]+$")
+		);
+		// Variables the endpoint doesn't declare are not checked
+		assert!(check(&[(":other", "x")]).is_ok());
+	}
+
+	#[test]
+	fn test_is_under() {
+		let base_url = "https://api.example.com/v1";
+		assert!(ApiSpec::is_under("https://api.example.com/v1/users/:id", base_url));
+		assert!(ApiSpec::is_under("https://api.example.com/v1", base_url));
+		assert!(ApiSpec::is_under("https://api.example.com/v1?page=2", base_url));
+		assert!(!ApiSpec::is_under("https://api.example.com/v10/users/:id", base_url));
+		assert!(!ApiSpec::is_under("https://api.example.com", base_url));
+		assert!(ApiSpec::is_under("https://api.example.com/users", "https://api.example.com/"));
+	}
+
+	#[test]
+	fn test_quote() {
+		let args: Vec<_> = ["http", ":8080/users/1", "name=John Doe", "note=it's", "-v"]
+			.into_iter()
+			.map(String::from)
+			.collect();
+		let quoted = PathVarCommand::quote(&args);
+		assert_eq!(quoted, r#"http :8080/users/1 'name=John Doe' 'note=it'\''s' -v"#);
+		assert_eq!(shell_words::split(&quoted).unwrap(), args);
+
+		let args: Vec<_> =
+			["path=C:\\", "path=a\\\\b", "note=a\nb", ""].into_iter().map(String::from).collect();
+		assert_eq!(shell_words::split(&PathVarCommand::quote(&args)).unwrap(), args);
+	}
+
+	#[test]
+	fn test_quote_fish() {
+		let quote = |arg: &str| PathVarCommand::quote_fish(&[arg.to_string()]);
+		assert_eq!(quote(":8080/users/1"), ":8080/users/1");
+		assert_eq!(quote("name=John Doe"), "'name=John Doe'");
+		assert_eq!(quote("note=it's"), r"'note=it\'s'");
+		assert_eq!(quote("path=C:\\"), r"'path=C:\\'");
+		assert_eq!(quote("path=a\\\\b"), r"'path=a\\\\b'");
+		assert_eq!(quote("note=a\nb"), r"'note=a'\n'b'");
+		assert_eq!(quote(""), "''");
+		assert_eq!(quote("~/file"), "'~/file'");
+	}
+
+	#[test]
+	fn test_fill_unassigned() {
+		let path_vars: HashSet<_> =
+			vec![":tenantId", ":id", ":postId"].into_iter().map(String::from).collect();
+		let mut var_values = HashMap::from([(":id".to_string(), "123".to_string())]);
+		let fallback = |name: &str| match name {
+			"tenantId" => Some("acme".to_string()),
+			"id" => Some("999".to_string()),
+			_ => None,
+		};
+
+		PathVarCommand::fill_unassigned(&path_vars, &mut var_values, fallback);
+		assert_eq!(var_values.len(), 2);
+		assert_eq!(var_values.get(":tenantId"), Some(&"acme".to_string()));
+		assert_eq!(var_values.get(":id"), Some(&"123".to_string()));
+	}
+
 	#[test]
 	fn test_replace_path_vars() {
 		let path_vars: HashSet<_> = vec![":id", ":postId"].into_iter().map(String::from).collect();
@@ -0,0 +1,89 @@
+use anyhow::Context;
+use clap::Args;
+
+use crate::config::Config;
+use crate::openapi::endpoint::EndPoint;
+use crate::theme::Theme;
+
+#[derive(Args, Debug)]
+#[command(
+	about = "Show the documented responses of an endpoint",
+	long_about = r#"
+Show the status codes, descriptions and body shapes that an endpoint documents,
+with the properties of JSON bodies mapped to their types.
+
+Examples:
+  httpie-oapi response -n petstore --path /pets/{petId}
+  httpie-oapi response -n petstore --operation showPetById --status 200
+"#
+)]
+pub(super) struct ResponseCommand {
+	/// Name of the API service
+	#[arg(short, long, value_name = "NAME")]
+	name: String,
+
+	/// The API path of the endpoint (e.g. `/users/{id}`)
+	#[arg(long, value_name = "PATH", required_unless_present = "operation")]
+	path: Option<String>,
+
+	/// The operationId of the endpoint, in place of its path
+	#[arg(long, value_name = "OPERATION_ID", conflicts_with = "path")]
+	operation: Option<String>,
+
+	/// HTTP method of the endpoint when the path has several (e.g. `post`)
+	#[arg(long, value_name = "METHOD", conflicts_with = "operation")]
+	method: Option<String>,
+
+	/// Only show the response of a status such as `200`, `4XX` or `default`
+	#[arg(long, value_name = "STATUS")]
+	status: Option<String>,
+}
+
+impl ResponseCommand {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		let api =
+			config.get_api(&self.name).with_context(|| format!("API '{}' not found", self.name))?;
+		let endpoints = api.endpoints(config.is_offline());
+		let ep = match (&self.operation, &self.path) {
+			(Some(operation), _) => endpoints
+				.find_operation(operation)
+				.with_context(|| format!("No endpoint has operationId '{}'", operation))?,
+			(None, Some(path)) => {
+				let converted = EndPoint::convert_path_format(path);
+				endpoints
+					.all()
+					.into_iter()
+					.find(|ep| {
+						ep.path == converted
+							&& self
+								.method
+								.as_ref()
+								.is_none_or(|method| ep.method.to_string().eq_ignore_ascii_case(method))
+					})
+					.with_context(|| match &self.method {
+						Some(method) => {
+							format!("No {} endpoint matched path '{}'", method.to_uppercase(), path)
+						}
+						None => format!("No endpoint matched path '{}'", path),
+					})?
+			}
+			(None, None) => unreachable!("clap requires --path or --operation"),
+		};
+
+		let theme = Theme::for_stdout(config.theme);
+		println!("{} {}", theme.method(&ep.method.to_string()), ep.path);
+		let responses = ep.responses.iter().filter(|response| {
+			self.status.as_ref().is_none_or(|status| response.status.eq_ignore_ascii_case(status))
+		});
+		for response in responses {
+			let content_type = response.content_type.as_deref().unwrap_or_default();
+			println!();
+			println!("{}  {}", response.status, theme.dim(&response.description));
+			if let Some(shape) = &response.shape {
+				println!("{}", theme.dim(content_type));
+				println!("{}", serde_json::to_string_pretty(shape)?);
+			}
+		}
+		Ok(())
+	}
+}
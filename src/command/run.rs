@@ -0,0 +1,269 @@
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command as Process, Stdio};
+use std::time::Instant;
+
+use anyhow::{Context, anyhow};
+use clap::Args;
+use tracing::{debug, info, warn};
+
+use super::path_var::PathVarCommand;
+use crate::config::Config;
+use crate::history::{History, HistoryEntry};
+
+/// Number of leading output bytes kept to look for the response status line
+const STATUS_SCAN_LIMIT: usize = 64 * 1024;
+
+/// Long and short options that change what HTTPie prints
+const PRINT_OPTIONS: &[&str] =
+	&["--print", "--headers", "--body", "--meta", "--verbose", "--quiet", "--download", "--offline"];
+
+/// Short flags that change what HTTPie prints
+const PRINT_FLAGS: &[char] = &['p', 'h', 'b', 'm', 'v', 'q', 'd'];
+
+/// Long options that make HTTPie write the response body to a file
+const FILE_OPTIONS: &[&str] = &["--download", "--output"];
+
+/// Short flags that make HTTPie write the response body to a file
+const FILE_FLAGS: &[char] = &['d', 'o'];
+
+#[derive(Args, Debug)]
+#[command(
+	about = "Run an HTTPie command and record it in the history",
+	long_about = r#"
+Run an HTTPie command with path variable support and record its exit status,
+HTTP status, duration and response size in the history.
+
+Examples:
+  httpie-oapi run -- http :8080/users/:id :id=123
+  httpie-oapi run -- http POST :8080/users name=john
+"#
+)]
+pub(super) struct RunCommand {
+	/// HTTPie command line, starting with the program (`http` or `https`)
+	#[arg(raw = true, required = true)]
+	args: Vec<String>,
+}
+
+impl RunCommand {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		info!("Running command: {:?}", self.args);
+		let args = Self::without_tag_option(&self.args);
+		let processed = PathVarCommand::process_args(&args, config)?;
+		let (program, http_args) =
+			processed.split_first().ok_or_else(|| anyhow!("No command given to run"))?;
+		// HTTPie writes downloads to stdout when it is piped, so they keep the
+		// terminal and only their exit code is recorded
+		let writes_file = Self::writes_file(http_args);
+		let http_args = if !writes_file && io::stdout().is_terminal() {
+			Self::with_terminal_defaults(http_args)
+		} else {
+			http_args.to_vec()
+		};
+
+		let url_index = args.iter().skip(1).position(|arg| PathVarCommand::is_url_like(arg));
+		let url_template = url_index.map(|i| args[i + 1].clone()).unwrap_or_default();
+		let url = url_index.and_then(|i| processed.get(i + 1).cloned()).unwrap_or_default();
+		let method = url_index.and_then(|i| Self::method_before(&args[1..], i));
+
+		let timestamp = crate::time::now();
+		let started = Instant::now();
+		let mut process = Process::new(program);
+		process.args(&http_args);
+		if !writes_file {
+			process.stdout(Stdio::piped());
+		}
+		let mut child = process.spawn().with_context(|| format!("Failed to run '{}'", program))?;
+
+		let (response_size, head) = match child.stdout.take() {
+			Some(mut child_stdout) => Self::forward_output(&mut child_stdout)?,
+			None => (0, Vec::new()),
+		};
+		let exit_status = child.wait().context("Failed to wait for HTTPie")?;
+
+		let entry = HistoryEntry {
+			timestamp,
+			method,
+			url_template,
+			url,
+			args,
+			exit_code: exit_status.code(),
+			status: Self::parse_status(&head),
+			duration_ms: started.elapsed().as_millis() as u64,
+			response_size,
+		};
+		debug!("Recording history entry: {:?}", entry);
+		if let Err(e) = History::append(&entry) {
+			warn!("Failed to record history: {:#}", e);
+		}
+
+		match exit_status.code() {
+			Some(0) => Ok(()),
+			Some(code) => std::process::exit(code),
+			None => Err(anyhow!("'{}' was terminated by a signal", program)),
+		}
+	}
+
+	/// Copy the child output to stdout, returning its size and leading bytes
+	fn forward_output(reader: &mut impl Read) -> anyhow::Result<(u64, Vec<u8>)> {
+		let mut stdout = io::stdout().lock();
+		let mut writable = true;
+		let mut size = 0u64;
+		let mut head = Vec::new();
+		let mut buf = [0u8; 8192];
+		loop {
+			let n = reader.read(&mut buf).context("Failed to read HTTPie output")?;
+			if n == 0 {
+				break;
+			}
+			// Keep draining after stdout is closed so the child never blocks
+			writable = writable && stdout.write_all(&buf[..n]).and_then(|_| stdout.flush()).is_ok();
+			size += n as u64;
+			if head.len() < STATUS_SCAN_LIMIT {
+				head.extend_from_slice(&buf[..n]);
+			}
+		}
+		Ok((size, head))
+	}
+
+	/// Keep HTTPie's terminal output style although its stdout is piped
+	///
+	/// HTTPie only prints the body without colors when stdout is not a
+	/// terminal, so the terminal defaults are passed explicitly unless the
+	/// user chose them.
+	fn with_terminal_defaults(args: &[String]) -> Vec<String> {
+		let mut result = args.to_vec();
+		if !args.iter().any(|arg| arg.starts_with("--pretty")) {
+			result.insert(0, "--pretty=all".to_string());
+		}
+		let chooses_output = args.iter().any(|arg| {
+			PRINT_OPTIONS.iter().any(|option| arg.starts_with(option))
+				|| (arg.starts_with('-')
+					&& !arg.starts_with("--")
+					&& arg.chars().skip(1).any(|c| PRINT_FLAGS.contains(&c)))
+		});
+		if !chooses_output {
+			result.insert(0, "--print=hb".to_string());
+		}
+		result
+	}
+
+	/// Whether HTTPie is told to write the response body to a file
+	fn writes_file(args: &[String]) -> bool {
+		args.iter().any(|arg| {
+			FILE_OPTIONS.iter().any(|option| arg == option || arg.starts_with(&format!("{option}=")))
+				|| (arg.starts_with('-')
+					&& !arg.starts_with("--")
+					&& arg.chars().skip(1).any(|c| FILE_FLAGS.contains(&c)))
+		})
+	}
+
+	/// Drop the `--tag` option, which only narrows path completion
+	fn without_tag_option(args: &[String]) -> Vec<String> {
+		let mut result = Vec::with_capacity(args.len());
+		let mut iter = args.iter();
+		while let Some(arg) = iter.next() {
+			if arg == "--tag" {
+				iter.next();
+			} else if !arg.starts_with("--tag=") {
+				result.push(arg.clone());
+			}
+		}
+		result
+	}
+
+	/// Get the explicit HTTP method right before the URL, if any
+	fn method_before(args: &[String], url_index: usize) -> Option<String> {
+		let candidate = args.get(url_index.checked_sub(1)?)?;
+		candidate
+			.chars()
+			.all(|c| c.is_ascii_uppercase())
+			.then(|| candidate.clone())
+			.filter(|method| !method.is_empty())
+	}
+
+	/// Find the status code of the first response status line in the output
+	fn parse_status(output: &[u8]) -> Option<u16> {
+		String::from_utf8_lossy(output).lines().map(strip_ansi).find_map(|line| {
+			let rest = line.trim_start().strip_prefix("HTTP/")?;
+			let (_, rest) = rest.split_once(' ')?;
+			rest.get(..3)?.parse().ok()
+		})
+	}
+}
+
+/// Remove ANSI escape sequences from a line of terminal output
+fn strip_ansi(line: &str) -> String {
+	let mut result = String::with_capacity(line.len());
+	let mut chars = line.chars();
+	while let Some(c) = chars.next() {
+		if c == '\x1b' {
+			// Skip the escape sequence up to its final letter
+			for next in chars.by_ref() {
+				if next.is_ascii_alphabetic() {
+					break;
+				}
+			}
+		} else {
+			result.push(c);
+		}
+	}
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn args(args: &[&str]) -> Vec<String> { args.iter().map(|s| s.to_string()).collect() }
+
+	#[test]
+	fn test_parse_status() {
+		let output = b"GET /users HTTP/1.1\nHost: x\n\nHTTP/1.1 404 Not Found\nContent-Length: 0\n";
+		assert_eq!(RunCommand::parse_status(output), Some(404));
+
+		let colored = b"\x1b[34mHTTP\x1b[39m/\x1b[34m1.1\x1b[39m \x1b[32m200\x1b[39m OK\n";
+		assert_eq!(RunCommand::parse_status(colored), Some(200));
+
+		assert_eq!(RunCommand::parse_status(b"{\"id\": 1}"), None);
+	}
+
+	#[test]
+	fn test_with_terminal_defaults() {
+		let result = RunCommand::with_terminal_defaults(&args(&[":8080/users"]));
+		assert_eq!(result, args(&["--print=hb", "--pretty=all", ":8080/users"]));
+
+		let result = RunCommand::with_terminal_defaults(&args(&["-v", "--pretty=none", ":8080/users"]));
+		assert_eq!(result, args(&["-v", "--pretty=none", ":8080/users"]));
+
+		let result = RunCommand::with_terminal_defaults(&args(&["-jb", ":8080/users"]));
+		assert_eq!(result, args(&["--pretty=all", "-jb", ":8080/users"]));
+	}
+
+	#[test]
+	fn test_writes_file() {
+		assert!(RunCommand::writes_file(&args(&["--download", ":8080/files/1"])));
+		assert!(RunCommand::writes_file(&args(&["-d", ":8080/files/1"])));
+		assert!(RunCommand::writes_file(&args(&["-o", "out.json", ":8080/users"])));
+		assert!(RunCommand::writes_file(&args(&["--output=out.json", ":8080/users"])));
+		assert!(RunCommand::writes_file(&args(&["-vd", ":8080/files/1"])));
+		assert!(!RunCommand::writes_file(&args(&["-v", "--offline", ":8080/users"])));
+		assert!(!RunCommand::writes_file(&args(&[":8080/users", "download=1"])));
+	}
+
+	#[test]
+	fn test_method_before() {
+		let line = args(&["-v", "POST", ":8080/users"]);
+		assert_eq!(RunCommand::method_before(&line, 2), Some("POST".to_string()));
+		assert_eq!(RunCommand::method_before(&line, 1), None);
+		assert_eq!(RunCommand::method_before(&line, 0), None);
+	}
+
+	#[test]
+	fn test_without_tag_option() {
+		let result = RunCommand::without_tag_option(&args(&["http", "--tag", "users", ":8080/users"]));
+		assert_eq!(result, args(&["http", ":8080/users"]));
+
+		let result = RunCommand::without_tag_option(&args(&["http", "--tag=users", ":8080/users"]));
+		assert_eq!(result, args(&["http", ":8080/users"]));
+	}
+}
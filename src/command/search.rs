@@ -0,0 +1,61 @@
+use anyhow::Context;
+use clap::Args;
+
+use crate::config::Config;
+use crate::theme::Theme;
+
+#[derive(Args, Debug)]
+#[command(
+	about = "Search operations across all APIs",
+	long_about = r#"
+Search the paths, summaries, descriptions and operationIds of the endpoints
+of all registered APIs. Every word of the query has to match, compared
+case-insensitively. Matches are printed one per line for fzf, with the API
+name and the summary after a tab, sorted by API name, path and method.
+
+Examples:
+  httpie-oapi search refund
+  httpie-oapi search "create order" --name shop
+"#
+)]
+pub(super) struct SearchCommand {
+	/// Words to search for
+	#[arg(value_name = "QUERY", required = true)]
+	query: Vec<String>,
+
+	/// Name of the API service (optional, search all APIs if not provided)
+	#[arg(short, long, value_name = "NAME")]
+	name: Option<String>,
+}
+
+impl SearchCommand {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		let mut apis = match &self.name {
+			Some(name) => {
+				vec![config.get_api(name).with_context(|| format!("API '{}' not found", name))?]
+			}
+			None => config.list_apis(),
+		};
+		let terms: Vec<String> =
+			self.query.iter().flat_map(|query| query.split_whitespace()).map(str::to_string).collect();
+
+		apis.sort_by_key(|api| &api.name);
+
+		let theme = Theme::for_stdout(config.theme);
+		for api in apis {
+			let endpoints = api.endpoints(config.is_offline());
+			let mut matches: Vec<_> =
+				endpoints.all().into_iter().filter(|ep| ep.matches_terms(&terms)).collect();
+			matches.sort_by_cached_key(|ep| (ep.path.clone(), ep.method.to_string()));
+			for endpoint in matches {
+				println!(
+					"{}\t{}\t{}",
+					endpoint.fzf_list_format(&api.base_url, &theme),
+					api.name,
+					theme.dim(endpoint.summary.as_deref().unwrap_or_default())
+				);
+			}
+		}
+		Ok(())
+	}
+}
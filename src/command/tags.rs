@@ -0,0 +1,36 @@
+use anyhow::Context;
+use clap::Args;
+
+use crate::config::Config;
+
+#[derive(Args, Debug)]
+#[command(
+	about = "List the tags of an API with their endpoint counts",
+	long_about = r#"
+List the tags endpoints are grouped under in the spec of an API, with the
+number of endpoints in each.
+
+A tag narrows path completion: type `<base_url>#<tag>` or add `--tag <tag>`
+to the command line to only complete the paths grouped under it.
+
+Examples:
+  httpie-oapi tags petstore
+"#
+)]
+pub(super) struct TagsCommand {
+	/// Name of the API service
+	#[arg(value_name = "NAME")]
+	name: String,
+}
+
+impl TagsCommand {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		let api =
+			config.get_api(&self.name).with_context(|| format!("API '{}' not found", self.name))?;
+		let endpoints = api.endpoints(config.is_offline());
+		for (tag, count) in endpoints.tags() {
+			println!("{}\t{}", tag, count);
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,105 @@
+use anyhow::{Context, anyhow};
+use clap::{Args, Subcommand};
+
+use crate::config::Config;
+use crate::openapi::ApiSpec;
+use crate::time::format_age;
+use crate::values::ValueCache;
+
+#[derive(Subcommand, Debug)]
+pub(super) enum ValuesCommands {
+	/// Fetch the values of linked params from their source endpoints
+	Refresh(RefreshArgs),
+	/// List the linked params of an API with their fetched values
+	#[command(visible_alias = "ls")]
+	List(ListArgs),
+}
+
+#[derive(Args, Debug)]
+pub(super) struct RefreshArgs {
+	/// Name of the API service
+	#[arg(value_name = "NAME")]
+	name: String,
+
+	/// Only refresh this param
+	#[arg(value_name = "PARAM")]
+	param: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(super) struct ListArgs {
+	/// Name of the API service
+	#[arg(value_name = "NAME")]
+	name: String,
+}
+
+impl ValuesCommands {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		match self {
+			ValuesCommands::Refresh(args) => Self::refresh(args, config),
+			ValuesCommands::List(args) => Self::list(args, config),
+		}
+	}
+
+	fn refresh(args: &RefreshArgs, config: &Config) -> anyhow::Result<()> {
+		let api = Self::get_api(&args.name, config)?;
+		let mut params: Vec<_> = api.values.iter().collect();
+		if let Some(param) = &args.param {
+			params.retain(|(name, _)| *name == param);
+			if params.is_empty() {
+				return Err(anyhow!("Param '{}' of API '{}' is not linked", param, api.name));
+			}
+		}
+		params.sort_by_key(|(name, _)| *name);
+
+		let mut cache = ValueCache::load(&api.name)?;
+		let mut failed = false;
+		for (param, source) in params {
+			match api.fetch_values(source) {
+				Ok(values) => {
+					println!("{}\t{} values", param, values.len());
+					cache.insert(param, values);
+				}
+				Err(e) => {
+					eprintln!("Failed to refresh '{}': {:#}", param, e);
+					failed = true;
+				}
+			}
+		}
+		cache.save(&api.name)?;
+		match failed {
+			true => Err(anyhow!("Some values of API '{}' were not refreshed", api.name)),
+			false => Ok(()),
+		}
+	}
+
+	fn list(args: &ListArgs, config: &Config) -> anyhow::Result<()> {
+		let api = Self::get_api(&args.name, config)?;
+		let cache = ValueCache::load(&api.name)?;
+		let mut params: Vec<_> = api.values.iter().collect();
+		params.sort_by_key(|(name, _)| *name);
+		for (param, source) in params {
+			let state = match cache.get(param) {
+				Some(cached) => {
+					format!("{} values, fetched {}", cached.values.len(), format_age(cached.fetched))
+				}
+				None => "not fetched".to_string(),
+			};
+			println!("{}\tGET {} {}\t{}", param, source.path, source.select, state);
+		}
+		Ok(())
+	}
+
+	fn get_api<'a>(name: &str, config: &'a Config) -> anyhow::Result<&'a ApiSpec> {
+		let api = config.get_api(name).with_context(|| format!("API '{}' not found", name))?;
+		if api.values.is_empty() {
+			return Err(anyhow!(
+				"API '{}' has no linked params, add them under [apis.{}.values] in {}",
+				name,
+				name,
+				Config::config_file().display()
+			));
+		}
+		Ok(api)
+	}
+}
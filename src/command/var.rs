@@ -0,0 +1,101 @@
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+
+use crate::config::Config;
+use crate::vars::VarStore;
+
+#[derive(Subcommand, Debug)]
+pub(super) enum VarCommands {
+	/// Store the value of a path variable
+	Set(SetArgs),
+	/// List the stored path variables
+	#[command(visible_alias = "ls")]
+	List(ListArgs),
+	/// Remove a stored path variable
+	#[command(visible_alias = "rm")]
+	Unset(UnsetArgs),
+}
+
+#[derive(Args, Debug)]
+pub(super) struct SetArgs {
+	/// Name of the variable, without its colon (e.g. `tenantId`)
+	#[arg(value_name = "VAR")]
+	name: String,
+
+	/// Value of the variable
+	#[arg(value_name = "VALUE")]
+	value: String,
+
+	/// Only use the value for this API, it is shared by all APIs otherwise
+	#[arg(long, value_name = "NAME")]
+	api: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(super) struct ListArgs {
+	/// Only list the variables used for this API
+	#[arg(long, value_name = "NAME")]
+	api: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(super) struct UnsetArgs {
+	/// Name of the variable, without its colon (e.g. `tenantId`)
+	#[arg(value_name = "VAR")]
+	name: String,
+
+	/// Remove the value stored for this API rather than the shared one
+	#[arg(long, value_name = "NAME")]
+	api: Option<String>,
+}
+
+impl VarCommands {
+	pub(super) fn run(&self, config: &Config) -> anyhow::Result<()> {
+		match self {
+			VarCommands::Set(args) => Self::set(args, config),
+			VarCommands::List(args) => Self::list(args),
+			VarCommands::Unset(args) => Self::unset(args),
+		}
+	}
+
+	fn set(args: &SetArgs, config: &Config) -> anyhow::Result<()> {
+		if let Some(api) = &args.api
+			&& config.get_api(api).is_none()
+		{
+			return Err(anyhow!("API '{}' not found", api));
+		}
+		let name = args.name.trim_start_matches(':');
+		let mut vars = VarStore::load()?;
+		vars.set(args.api.as_deref(), name, &args.value);
+		vars.save()?;
+		match &args.api {
+			Some(api) => println!("Set :{} for API '{}'", name, api),
+			None => println!("Set :{} for all APIs", name),
+		}
+		Ok(())
+	}
+
+	fn list(args: &ListArgs) -> anyhow::Result<()> {
+		let vars = VarStore::load()?;
+		for (api, name, value) in vars.list() {
+			// The variables of an API are its own and the shared ones
+			if args.api.is_some() && api.is_some() && api != args.api.as_deref() {
+				continue;
+			}
+			println!("{}\t{}\t{}", name, value, api.unwrap_or("*"));
+		}
+		Ok(())
+	}
+
+	fn unset(args: &UnsetArgs) -> anyhow::Result<()> {
+		let name = args.name.trim_start_matches(':');
+		let mut vars = VarStore::load()?;
+		if !vars.unset(args.api.as_deref(), name) {
+			return Err(match &args.api {
+				Some(api) => anyhow!("No variable '{}' is stored for API '{}'", name, api),
+				None => anyhow!("No variable '{}' is stored for all APIs", name),
+			});
+		}
+		vars.save()
+	}
+}
@@ -1,35 +1,127 @@
-use crate::openapi::ApiSpec;
+use crate::credentials::CredentialStore;
+use crate::openapi::{ApiSpec, BodyOptions, DownloadOptions, RefOptions, cache, versions};
+use crate::theme::ThemeName;
+use crate::vars::VarStore;
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use tracing::warn;
 
 pub static CONFIG_DIR: &str = ".config/httpie-oapi";
 pub static CACHE_DIR: &str = ".cache/httpie-oapi";
+pub static STATE_DIR: &str = ".local/state/httpie-oapi";
 pub static CONFIG_FILE: &str = "config.toml";
+/// Environment variable overriding the active group for one shell
+pub static GROUP_ENV: &str = "HTTPIE_OAPI_GROUP";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+	/// Theme of human readable output
+	#[serde(default, skip_serializing_if = "is_default_theme")]
+	pub theme: ThemeName,
+	/// Settings of shell completion
+	#[serde(default, skip_serializing_if = "CompletionConfig::is_default")]
+	pub completion: CompletionConfig,
+	/// Group completion is scoped to, see [`Config::active_group`]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub active_group: Option<String>,
+	/// Never download specs in completion and listing commands, APIs that
+	/// were not cached yet have no endpoints
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub offline: bool,
+	/// Retries and size limit of spec downloads
+	#[serde(default, skip_serializing_if = "DownloadOptions::is_default")]
+	pub download: DownloadOptions,
+	/// How references of specs to other files and URLs are followed
+	#[serde(default, skip_serializing_if = "RefOptions::is_default")]
+	pub refs: RefOptions,
+	/// Preferred media types of request bodies to take body params from
+	#[serde(default, skip_serializing_if = "BodyOptions::is_default")]
+	pub body: BodyOptions,
+	/// Number of previously downloaded specs kept per API for rollbacks, 0
+	/// keeps none
+	#[serde(default = "default_keep_versions", skip_serializing_if = "is_default_keep_versions")]
+	pub keep_versions: usize,
+	/// Offline mode requested with `--offline` for this run only
+	#[serde(skip)]
+	offline_override: bool,
 	/// Map of service name to API specification
 	apis: HashMap<String, ApiSpec>,
 }
 
+/// Settings of shell completion
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompletionConfig {
+	/// Characters of the path to type after the base URL before all paths of
+	/// an API are listed, 0 lists them right away
+	pub min_path_length: usize,
+	/// Number of most used paths listed until `min_path_length` is reached
+	pub top_paths: usize,
+	/// Age of an endpoints cache, such as `1d`, after which completion serves
+	/// it once more and refreshes it in the background
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stale_after: Option<String>,
+	/// Levels of nested body objects and arrays completed in HTTPie's nested
+	/// JSON syntax, such as `address[city]=`, 0 completes only top level
+	/// properties
+	pub body_depth: usize,
+}
+
+impl Default for CompletionConfig {
+	fn default() -> Self {
+		Self { min_path_length: 0, top_paths: 10, stale_after: None, body_depth: 2 }
+	}
+}
+
+impl CompletionConfig {
+	fn is_default(&self) -> bool { *self == Self::default() }
+}
+
 impl Config {
 	pub fn load() -> Self {
+		Self::try_load().unwrap_or_else(|e| {
+			eprintln!("{e:#}");
+			std::process::exit(1);
+		})
+	}
+
+	/// Load the config file, returning errors instead of exiting
+	pub fn try_load() -> anyhow::Result<Self> {
 		let config_path = Self::config_file();
 		if !config_path.exists() {
-			return Self { apis: HashMap::new() };
+			return Ok(Self {
+				theme: ThemeName::default(),
+				completion: CompletionConfig::default(),
+				active_group: None,
+				offline: false,
+				download: DownloadOptions::default(),
+				refs: RefOptions::default(),
+				body: BodyOptions::default(),
+				keep_versions: versions::DEFAULT_KEEP,
+				offline_override: false,
+				apis: HashMap::new(),
+			});
 		}
 
-		let content = fs::read_to_string(&config_path).unwrap_or_else(|e| {
-			eprintln!("Failed to read config file: {}", e);
-			std::process::exit(1);
-		});
+		let content = fs::read_to_string(&config_path).context("Failed to read config file")?;
+		let mut config: Self = toml::from_str(&content).context("Failed to parse config file")?;
+		let mut apis = std::mem::take(&mut config.apis);
+		for api in apis.values_mut() {
+			config.configure(api);
+		}
+		config.apis = apis;
+		Ok(config)
+	}
 
-		toml::from_str(&content).unwrap_or_else(|e| {
-			eprintln!("Failed to parse config file: {}", e);
-			std::process::exit(1);
-		})
+	/// Apply the download settings of the config to an API
+	pub fn configure(&self, api: &mut ApiSpec) {
+		api.download = self.download.clone();
+		api.refs = self.refs.clone();
+		api.body = self.body.clone();
+		api.keep_versions = self.keep_versions;
 	}
 
 	pub fn config_file() -> PathBuf {
@@ -50,6 +142,66 @@ impl Config {
 		path
 	}
 
+	pub fn get_index_cache_path(name: &str) -> PathBuf {
+		let path = Self::cache_dir().join(format!("{}.index.json", name));
+		Self::ensure_dir_exists(&path);
+		path
+	}
+
+	pub fn get_values_cache_path(name: &str) -> PathBuf {
+		let path = Self::cache_dir().join(format!("{}.values.json", name));
+		Self::ensure_dir_exists(&path);
+		path
+	}
+
+	pub fn get_download_path(name: &str) -> PathBuf {
+		let path = Self::cache_dir().join(format!("{}.download", name));
+		Self::ensure_dir_exists(&path);
+		path
+	}
+
+	pub fn get_versions_dir(name: &str) -> PathBuf {
+		let path = Self::cache_dir().join(format!("{}.versions", name));
+		Self::ensure_dir_exists(&path);
+		path
+	}
+
+	pub fn get_credentials_path() -> PathBuf {
+		let path = Self::config_dir().join("credentials.toml");
+		Self::ensure_dir_exists(&path);
+		path
+	}
+
+	pub fn get_vars_path() -> PathBuf {
+		let path = Self::config_dir().join("vars.toml");
+		Self::ensure_dir_exists(&path);
+		path
+	}
+
+	pub fn get_history_path() -> PathBuf {
+		let path = Self::state_dir().join("history.jsonl");
+		Self::ensure_dir_exists(&path);
+		path
+	}
+
+	pub fn get_daemon_socket_path() -> PathBuf {
+		let path = Self::state_dir().join("daemon.sock");
+		Self::ensure_dir_exists(&path);
+		path
+	}
+
+	pub fn get_daemon_pid_path() -> PathBuf {
+		let path = Self::state_dir().join("daemon.pid");
+		Self::ensure_dir_exists(&path);
+		path
+	}
+
+	pub fn get_refresh_journal_path() -> PathBuf {
+		let path = Self::cache_dir().join("refresh.journal");
+		Self::ensure_dir_exists(&path);
+		path
+	}
+
 	fn ensure_dir_exists(path: &Path) {
 		if let Some(parent) = path.parent() {
 			fs::create_dir_all(parent).unwrap_or_else(|e| {
@@ -68,7 +220,7 @@ impl Config {
 			.join(CONFIG_DIR)
 	}
 
-	fn cache_dir() -> PathBuf {
+	pub fn cache_dir() -> PathBuf {
 		dirs::home_dir()
 			.unwrap_or_else(|| {
 				eprintln!("Could not determine home directory");
@@ -76,48 +228,70 @@ impl Config {
 			})
 			.join(CACHE_DIR)
 	}
+
+	fn state_dir() -> PathBuf {
+		dirs::home_dir()
+			.unwrap_or_else(|| {
+				eprintln!("Could not determine home directory");
+				std::process::exit(1);
+			})
+			.join(STATE_DIR)
+	}
 }
 
 impl Config {
 	pub fn save(&self) {
+		self.try_save().unwrap_or_else(|e| {
+			eprintln!("{e:#}");
+			std::process::exit(1);
+		})
+	}
+
+	/// Save the config file, returning errors instead of exiting
+	pub fn try_save(&self) -> anyhow::Result<()> {
 		let config_path = Self::config_file();
 		// Ensure config directory exists
 		if let Some(parent) = config_path.parent() {
-			fs::create_dir_all(parent).unwrap_or_else(|e| {
-				eprintln!("Failed to create config directory: {}", e);
-				std::process::exit(1);
-			});
+			fs::create_dir_all(parent).context("Failed to create config directory")?;
 		}
 
-		let content = toml::to_string_pretty(self).unwrap_or_else(|e| {
-			eprintln!("Failed to serialize config: {}", e);
-			std::process::exit(1);
-		});
-
-		fs::write(&config_path, content).unwrap_or_else(|e| {
-			eprintln!("Failed to write config file: {}", e);
-			std::process::exit(1);
-		});
+		let content = toml::to_string_pretty(self).context("Failed to serialize config")?;
+		fs::write(&config_path, content).context("Failed to write config file")
 	}
 
-	pub fn add_api(&mut self, name: String, url: String, base_url: String) {
-		let api_spec = ApiSpec::new(name.clone(), url, base_url);
-		// cache the api
-		api_spec.refresh_endpoints_cache();
-		self.apis.insert(name, api_spec);
-	}
+	pub fn add_api(&mut self, api_spec: ApiSpec) { self.apis.insert(api_spec.name.clone(), api_spec); }
 
 	pub fn remove_api(&mut self, name: &str) -> bool {
 		match self.apis.remove(name) {
 			Some(_) => {
 				// clear cache
-				let cache_path = Self::get_cache_path(name);
-				if cache_path.exists() {
-					let _ = fs::remove_file(cache_path);
+				cache::remove_caches(name);
+				let values_cache_path = Self::get_values_cache_path(name);
+				if values_cache_path.exists() {
+					let _ = fs::remove_file(values_cache_path);
+				}
+				versions::remove(name);
+				// forget stored credentials
+				match CredentialStore::load() {
+					Ok(mut store) => {
+						if store.remove(name)
+							&& let Err(e) = store.save()
+						{
+							warn!("Failed to remove credentials of '{}': {:#}", name, e);
+						}
+					}
+					Err(e) => warn!("Failed to load credentials: {:#}", e),
 				}
-				let endpoints_cache_path = Self::get_endpoints_cache_path(name);
-				if endpoints_cache_path.exists() {
-					let _ = fs::remove_file(endpoints_cache_path);
+				// and the variables scoped to it
+				match VarStore::load() {
+					Ok(mut vars) => {
+						if vars.remove_api(name)
+							&& let Err(e) = vars.save()
+						{
+							warn!("Failed to remove variables of '{}': {:#}", name, e);
+						}
+					}
+					Err(e) => warn!("Failed to load variables: {:#}", e),
 				}
 				true
 			}
@@ -125,11 +299,101 @@ impl Config {
 		}
 	}
 
+	/// Register an API under a new name and save the config, moving its
+	/// caches, fetched values, stored credentials and scoped variables along
+	///
+	/// The caches of the old name are only removed once the config is saved.
+	/// When a move or the save fails, everything is moved back to the old
+	/// name before the error is returned.
+	pub fn rename_api(&mut self, old: &str, new: &str) -> anyhow::Result<bool> {
+		if !self.apis.contains_key(old) {
+			return Ok(false);
+		}
+		if let Err(e) = cache::copy_caches(old, new) {
+			cache::remove_caches(new);
+			return Err(e);
+		}
+		let renamed = Self::move_api_data(old, new).and_then(|()| {
+			self.rename_in_place(old, new);
+			self.try_save()
+		});
+		if let Err(e) = renamed {
+			self.rename_in_place(new, old);
+			if let Err(undo) = Self::move_api_data(new, old) {
+				warn!("Failed to move the data of '{}' back to '{}': {:#}", new, old, undo);
+			}
+			cache::remove_caches(new);
+			return Err(e);
+		}
+		cache::remove_caches(old);
+		Ok(true)
+	}
+
+	fn rename_in_place(&mut self, old: &str, new: &str) {
+		if let Some(mut api) = self.apis.remove(old) {
+			api.name = new.to_string();
+			self.apis.insert(new.to_string(), api);
+		}
+	}
+
+	/// Move the fetched values, kept versions, stored credentials and scoped
+	/// variables of an API to another name
+	fn move_api_data(from: &str, to: &str) -> anyhow::Result<()> {
+		let values_cache_path = Self::get_values_cache_path(from);
+		if values_cache_path.exists() {
+			fs::rename(&values_cache_path, Self::get_values_cache_path(to))
+				.with_context(|| format!("Failed to move values cache of '{}'", from))?;
+		}
+		versions::rename(from, to)?;
+		let mut store = CredentialStore::load()?;
+		if let Some(credentials) = store.get(from).cloned() {
+			*store.entry(to) = credentials;
+			store.remove(from);
+			store.save()?;
+		}
+		let mut vars = VarStore::load()?;
+		if vars.rename_api(from, to) {
+			vars.save()?;
+		}
+		Ok(())
+	}
+
 	pub fn list_apis(&self) -> Vec<&ApiSpec> {
 		self.apis.values().collect()
 	}
 
+	/// Whether specs must not be downloaded, by the config or `--offline`
+	pub fn is_offline(&self) -> bool { self.offline || self.offline_override }
+
+	/// Work offline for this run, without changing the config file
+	pub fn set_offline(&mut self) { self.offline_override = true; }
+
+	/// List the APIs of a group, or all APIs when no group is given
+	pub fn list_apis_in(&self, group: Option<&str>) -> Vec<&ApiSpec> {
+		match group {
+			Some(group) => self.apis.values().filter(|api| api.group.as_deref() == Some(group)).collect(),
+			None => self.list_apis(),
+		}
+	}
+
+	/// The group completion is scoped to: `HTTPIE_OAPI_GROUP` when set, an
+	/// empty value meaning all APIs, and `active_group` otherwise
+	pub fn active_group(&self) -> Option<String> {
+		match std::env::var(GROUP_ENV) {
+			Ok(group) => Some(group).filter(|group| !group.is_empty()),
+			Err(_) => self.active_group.clone(),
+		}
+	}
+
 	pub fn get_api(&self, name: &str) -> Option<&ApiSpec> {
 		self.apis.get(name)
 	}
+
+	pub fn get_api_mut(&mut self, name: &str) -> Option<&mut ApiSpec> { self.apis.get_mut(name) }
 }
+
+fn is_default_theme(theme: &ThemeName) -> bool { *theme == ThemeName::default() }
+
+fn default_keep_versions() -> usize { versions::DEFAULT_KEEP }
+
+fn is_default_keep_versions(keep: &usize) -> bool { *keep == versions::DEFAULT_KEEP }
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::config::Config;
+
+/// A single request executed through `run`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+	/// Unix timestamp when the request was started
+	pub timestamp: u64,
+	/// HTTP method, if given explicitly on the command line
+	pub method: Option<String>,
+	/// URL as typed, with path variables left in place (e.g. `:8080/users/:id`)
+	pub url_template: String,
+	/// URL after path variable replacement
+	pub url: String,
+	/// Original arguments, including path variable assignments
+	pub args: Vec<String>,
+	/// Exit code of the HTTPie process, None if it was killed by a signal
+	pub exit_code: Option<i32>,
+	/// HTTP status code, when the response headers were printed
+	pub status: Option<u16>,
+	/// Wall clock duration of the request in milliseconds
+	pub duration_ms: u64,
+	/// Number of bytes HTTPie wrote to stdout
+	pub response_size: u64,
+}
+
+impl HistoryEntry {
+	/// Whether the request failed, either in HTTPie or with an HTTP error status
+	pub fn is_failed(&self) -> bool {
+		self.exit_code != Some(0) || self.status.is_some_and(|status| status >= 400)
+	}
+}
+
+pub struct History;
+
+impl History {
+	/// Append an entry to the history file
+	pub fn append(entry: &HistoryEntry) -> Result<()> {
+		let path = Config::get_history_path();
+		let mut file = OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&path)
+			.with_context(|| format!("Failed to open history file: {}", path.display()))?;
+		writeln!(file, "{}", serde_json::to_string(entry)?)
+			.with_context(|| format!("Failed to write history file: {}", path.display()))
+	}
+
+	/// Get the values previously assigned with `prefix` (e.g. `:id=`) in requests
+	/// to `url_template`, most recent first, with the time they were last used
+	pub fn used_values(url_template: &str, prefix: &str) -> Result<Vec<(String, u64)>> {
+		let mut values: Vec<(String, u64)> = Vec::new();
+		for entry in Self::load()?.iter().rev().filter(|entry| entry.url_template == url_template) {
+			for value in entry.args.iter().filter_map(|arg| arg.strip_prefix(prefix)) {
+				if !value.is_empty() && !values.iter().any(|(used, _)| used == value) {
+					values.push((value.to_string(), entry.timestamp));
+				}
+			}
+		}
+		Ok(values)
+	}
+
+	/// Count the requests made to each URL template
+	pub fn call_counts() -> Result<HashMap<String, usize>> {
+		let mut counts = HashMap::new();
+		for entry in Self::load()? {
+			*counts.entry(entry.url_template).or_default() += 1;
+		}
+		Ok(counts)
+	}
+
+	/// Load all history entries, oldest first. Corrupted lines are skipped.
+	pub fn load() -> Result<Vec<HistoryEntry>> {
+		let path = Config::get_history_path();
+		if !path.exists() {
+			return Ok(Vec::new());
+		}
+		let content = fs::read_to_string(&path)
+			.with_context(|| format!("Failed to read history file: {}", path.display()))?;
+		Ok(content
+			.lines()
+			.filter(|line| !line.trim().is_empty())
+			.filter_map(|line| match serde_json::from_str(line) {
+				Ok(entry) => Some(entry),
+				Err(e) => {
+					warn!("Skipping corrupted history line: {}", e);
+					None
+				}
+			})
+			.collect())
+	}
+}
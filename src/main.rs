@@ -5,8 +5,14 @@ use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberI
 
 mod command;
 mod config;
+mod credentials;
+mod history;
 mod openapi;
+mod theme;
+mod time;
 mod tokens;
+mod values;
+mod vars;
 
 pub use command::Command;
 pub use config::Config;
@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, anyhow};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, warn};
+use url::Url;
+
+use super::document;
+
+/// How references to other documents are followed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefOptions {
+	/// Number of documents a chain of references may lead through
+	pub max_depth: usize,
+	/// Hosts other than the host of the spec that references may lead to
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub allowed_hosts: Vec<String>,
+}
+
+impl Default for RefOptions {
+	fn default() -> Self { Self { max_depth: 8, allowed_hosts: Vec::new() } }
+}
+
+impl RefOptions {
+	pub fn is_default(&self) -> bool { *self == Self::default() }
+}
+
+/// Inline the references of a spec document to other files and URLs, such as
+/// `./schemas/user.yaml#/User`, so the document stands on its own
+///
+/// References are resolved against the location of the document they are
+/// found in, and `fetch` reads each referenced document once. References
+/// within a referenced document are followed as well, cyclic ones are cut.
+/// Specs downloaded over HTTP may only reference their own host and the
+/// allowed hosts. A document without external references is returned as it
+/// is, otherwise the bundled document is returned as JSON.
+pub fn bundle(
+	data: &str,
+	location: &Url,
+	options: &RefOptions,
+	fetch: impl FnMut(&Url) -> Result<String>,
+) -> Result<String> {
+	let mut document = document::read(data)?;
+	if !has_external_refs(&document) {
+		return Ok(data.to_string());
+	}
+	debug!("Bundling the external references of {}", location);
+	let mut bundler = Bundler { root: location, options, fetch, documents: HashMap::new() };
+	bundler.inline(&mut document, location, &mut Vec::new(), 0)?;
+	serde_json::to_string(&document).context("Failed to serialize the bundled spec")
+}
+
+fn has_external_refs(value: &Value) -> bool {
+	match value {
+		Value::Object(object) => object.iter().any(|(key, value)| match value {
+			Value::String(reference) if key == "$ref" => !reference.starts_with('#'),
+			value => has_external_refs(value),
+		}),
+		Value::Array(items) => items.iter().any(has_external_refs),
+		_ => false,
+	}
+}
+
+struct Bundler<'a, F> {
+	/// Location of the spec document
+	root: &'a Url,
+	options: &'a RefOptions,
+	fetch: F,
+	/// Referenced documents by location
+	documents: HashMap<Url, Value>,
+}
+
+impl<F: FnMut(&Url) -> Result<String>> Bundler<'_, F> {
+	/// Replace the external references in `value`, found in the document at
+	/// `base`, with what they refer to
+	///
+	/// `chain` holds the references being inlined, innermost last, and
+	/// `depth` the number of documents they led to other documents.
+	fn inline(
+		&mut self,
+		value: &mut Value,
+		base: &Url,
+		chain: &mut Vec<String>,
+		depth: usize,
+	) -> Result<()> {
+		let reference = value.get("$ref").and_then(Value::as_str).map(str::to_string);
+		let Some(reference) = reference else {
+			match value {
+				Value::Object(object) => {
+					for value in object.values_mut() {
+						self.inline(value, base, chain, depth)?;
+					}
+				}
+				Value::Array(items) => {
+					for value in items {
+						self.inline(value, base, chain, depth)?;
+					}
+				}
+				_ => {}
+			}
+			return Ok(());
+		};
+
+		let target =
+			base.join(&reference).with_context(|| format!("Invalid reference '{}'", reference))?;
+		let mut location = target.clone();
+		location.set_fragment(None);
+		if location == *self.root {
+			// References into the spec itself stay local
+			if base != self.root {
+				value["$ref"] = Value::String(format!("#{}", target.fragment().unwrap_or_default()));
+			}
+			return Ok(());
+		}
+
+		let key = target.to_string();
+		if chain.contains(&key) {
+			warn!("Cyclic reference to {} is cut", key);
+			*value = Value::Object(Default::default());
+			return Ok(());
+		}
+		// References within a document don't lead through more documents
+		let depth = depth + usize::from(location != *base);
+		if depth > self.options.max_depth {
+			return Err(anyhow!(
+				"References lead through more than {} documents at {}, raise `max_depth` in the [refs] section of the config",
+				self.options.max_depth,
+				key
+			));
+		}
+		self.check(&location)?;
+
+		let pointer = percent_decode_str(target.fragment().unwrap_or_default()).decode_utf8_lossy();
+		let mut fragment = self
+			.load(&location)?
+			.pointer(&pointer)
+			.cloned()
+			.ok_or_else(|| anyhow!("No '{}' in {} referenced by '{}'", pointer, location, reference))?;
+		chain.push(key);
+		self.inline(&mut fragment, &location, chain, depth)?;
+		chain.pop();
+		*value = fragment;
+		Ok(())
+	}
+
+	/// Check that a referenced document may be read
+	fn check(&self, location: &Url) -> Result<()> {
+		let allowed = match (location.scheme(), self.root.scheme()) {
+			("file", "file") => true,
+			("http" | "https", _) => {
+				let host = location.host_str().unwrap_or_default();
+				self.root.host_str() == Some(host)
+					|| self.options.allowed_hosts.iter().any(|allowed| allowed.eq_ignore_ascii_case(host))
+			}
+			_ => false,
+		};
+		if allowed {
+			return Ok(());
+		}
+		match location.host_str() {
+			Some(host) => Err(anyhow!(
+				"The spec references {} on another host, add '{}' to `allowed_hosts` in the [refs] section of the config to follow it",
+				location,
+				host
+			)),
+			None => Err(anyhow!("The spec may not reference {}", location)),
+		}
+	}
+
+	fn load(&mut self, location: &Url) -> Result<&Value> {
+		if !self.documents.contains_key(location) {
+			debug!("Reading referenced document {}", location);
+			let data = (self.fetch)(location)
+				.with_context(|| format!("Failed to read referenced document {}", location))?;
+			let document = document::read(&data)
+				.with_context(|| format!("Invalid referenced document {}", location))?;
+			self.documents.insert(location.clone(), document);
+		}
+		Ok(&self.documents[location])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::openapi::EndPoints;
+
+	fn fetch_from(files: &[(&str, &str)]) -> impl FnMut(&Url) -> Result<String> {
+		let files: HashMap<String, String> =
+			files.iter().map(|(url, data)| (url.to_string(), data.to_string())).collect();
+		move |url| files.get(url.as_str()).cloned().ok_or_else(|| anyhow!("not found"))
+	}
+
+	#[test]
+	fn test_bundle() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /users:
+    post:
+      parameters:
+        - $ref: "https://schemas.example.com/common.yaml#/Limit"
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "./schemas/user.yaml#/User"
+      responses:
+        "200":
+          description: ok
+"##;
+		let user = r##"
+User:
+  type: object
+  properties:
+    name:
+      type: string
+    address:
+      $ref: "#/Address"
+    manager:
+      $ref: "#/User"
+Address:
+  type: object
+  properties:
+    city:
+      type: string
+"##;
+		let common =
+			r#"{ "Limit": { "name": "limit", "in": "query", "schema": { "type": "integer" } } }"#;
+		let location = Url::parse("file:///specs/openapi.yaml").unwrap();
+		let files = [
+			("file:///specs/schemas/user.yaml", user),
+			("https://schemas.example.com/common.yaml", common),
+		];
+
+		let error = bundle(spec, &location, &RefOptions::default(), fetch_from(&files)).unwrap_err();
+		assert!(error.to_string().contains("add 'schemas.example.com' to `allowed_hosts`"));
+
+		let options =
+			RefOptions { allowed_hosts: vec!["schemas.example.com".to_string()], ..Default::default() };
+		let bundled = bundle(spec, &location, &options, fetch_from(&files)).unwrap();
+		let endpoints = EndPoints::try_from_openapi(bundled).unwrap();
+		let names: Vec<_> = endpoints.find("/users").unwrap().params.iter().map(|p| &p.name).collect();
+		assert_eq!(names, ["limit", "address", "address[city]", "manager", "name"]);
+
+		let local = "openapi: 3.0.0\ninfo: { title: Test API, version: 1.0.0 }\npaths: {}";
+		assert_eq!(bundle(local, &location, &options, fetch_from(&[])).unwrap(), local);
+		let options = RefOptions { max_depth: 0, ..options };
+		assert!(bundle(spec, &location, &options, fetch_from(&files)).is_err());
+	}
+
+	#[test]
+	fn test_bundle_local_chain() {
+		let spec = r#"{
+			"openapi": "3.0.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": {
+				"/users": {
+					"post": {
+						"requestBody": {
+							"content": {
+								"application/json": { "schema": { "$ref": "./schemas.json#/S0" } }
+							}
+						},
+						"responses": { "200": { "description": "ok" } }
+					}
+				}
+			}
+		}"#;
+		// A chain of local references longer than `max_depth` within one file
+		let mut schemas: serde_json::Map<String, Value> = (0..12)
+			.map(|i| (format!("S{}", i), serde_json::json!({ "$ref": format!("#/S{}", i + 1) })))
+			.collect();
+		let leaf =
+			serde_json::json!({ "type": "object", "properties": { "name": { "type": "string" } } });
+		schemas.insert("S12".to_string(), leaf);
+		let schemas = Value::Object(schemas).to_string();
+		let location = Url::parse("file:///specs/openapi.json").unwrap();
+		let files = [("file:///specs/schemas.json", schemas.as_str())];
+
+		let bundled = bundle(spec, &location, &RefOptions::default(), fetch_from(&files)).unwrap();
+		let endpoints = EndPoints::try_from_openapi(bundled).unwrap();
+		let names: Vec<_> = endpoints.find("/users").unwrap().params.iter().map(|p| &p.name).collect();
+		assert_eq!(names, ["name"]);
+		let options = RefOptions { max_depth: 0, ..Default::default() };
+		assert!(bundle(spec, &location, &options, fetch_from(&files)).is_err());
+	}
+}
@@ -0,0 +1,321 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use super::EndPoints;
+use super::path_index::PathIndex;
+use crate::config::Config;
+
+/// A successful cache refresh, appended to the refresh journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshRecord {
+	/// Name of the refreshed API
+	pub name: String,
+	/// Unix timestamp of the refresh
+	pub timestamp: u64,
+	/// Where the spec was read from
+	pub spec_url: String,
+	/// Size of the raw spec document in bytes
+	pub spec_size: u64,
+	/// Number of endpoints parsed from the spec
+	pub endpoints: usize,
+}
+
+/// Write the raw spec, the endpoints cache and the path index of an API as a
+/// unit
+///
+/// All files are written to temporary files first. A commit marker is
+/// created once all are complete, then they are renamed into place and the
+/// marker is removed. An interrupted transaction is completed or discarded by
+/// [`recover`].
+pub fn write_caches(name: &str, spec_json: &str, endpoints: &EndPoints) -> Result<()> {
+	write_caches_in(&Config::cache_dir(), name, spec_json, endpoints)
+}
+
+fn write_caches_in(dir: &Path, name: &str, spec_json: &str, endpoints: &EndPoints) -> Result<()> {
+	recover_in(dir, name);
+	fs::create_dir_all(dir)
+		.with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+	let files = cache_files(dir, name);
+	let [(raw_tmp, _), (endpoints_tmp, _), (index_tmp, _)] = &files;
+
+	fs::write(raw_tmp, spec_json)
+		.with_context(|| format!("Failed to write cache file: {}", raw_tmp.display()))?;
+	endpoints.save_to_file(endpoints_tmp).with_context(|| {
+		format!("Failed to write endpoints cache file: {}", endpoints_tmp.display())
+	})?;
+	fs::write(index_tmp, serde_json::to_string(endpoints.index())?)
+		.with_context(|| format!("Failed to write path index: {}", index_tmp.display()))?;
+
+	let marker = commit_marker(dir, name);
+	fs::write(&marker, "")
+		.with_context(|| format!("Failed to write cache commit marker: {}", marker.display()))?;
+	for (tmp, target) in &files {
+		fs::rename(tmp, target)
+			.with_context(|| format!("Failed to move cache file into place: {}", target.display()))?;
+	}
+	fs::remove_file(&marker)
+		.with_context(|| format!("Failed to remove cache commit marker: {}", marker.display()))
+}
+
+/// Copy the raw spec and the endpoints cache of an API to another name as a
+/// unit, using the same commit protocol as [`write_caches`]
+///
+/// The files are linked rather than copied where possible and the last
+/// refresh is recorded for `to` as well. The caches of `from` are left in place
+/// for [`remove_caches`] once the new name is in use.
+pub fn copy_caches(from: &str, to: &str) -> Result<()> {
+	recover(from);
+	recover(to);
+	let dir = Config::cache_dir();
+	let files = cache_files(&dir, to);
+	for ((tmp, _), source) in files.iter().zip(cache_files(&dir, from).map(|(_, target)| target)) {
+		if !source.exists() {
+			continue;
+		}
+		if fs::hard_link(&source, tmp).is_err() {
+			fs::copy(&source, tmp)
+				.with_context(|| format!("Failed to copy cache file: {}", source.display()))?;
+		}
+	}
+
+	let marker = commit_marker(&dir, to);
+	fs::write(&marker, "")
+		.with_context(|| format!("Failed to write cache commit marker: {}", marker.display()))?;
+	for (tmp, target) in files.iter().filter(|(tmp, _)| tmp.exists()) {
+		fs::rename(tmp, target)
+			.with_context(|| format!("Failed to move cache file into place: {}", target.display()))?;
+	}
+	fs::remove_file(&marker)
+		.with_context(|| format!("Failed to remove cache commit marker: {}", marker.display()))?;
+
+	// Carry the last refresh over so the copy does not look stale
+	if let Some(record) = last_refresh(from) {
+		append_journal(&RefreshRecord { name: to.to_string(), ..record })?;
+	}
+	Ok(())
+}
+
+/// Load the persisted path index of an API, if it exists and parses
+pub fn load_index(name: &str) -> Option<PathIndex> {
+	let content = fs::read_to_string(Config::get_index_cache_path(name)).ok()?;
+	serde_json::from_str(&content)
+		.inspect_err(|e| warn!("Failed to parse path index of '{}': {}", name, e))
+		.ok()
+}
+
+/// Remove the raw spec, the endpoints cache and the path index of an API
+pub fn remove_caches(name: &str) {
+	for (_, target) in cache_files(&Config::cache_dir(), name) {
+		if target.exists() {
+			let _ = fs::remove_file(target);
+		}
+	}
+}
+
+/// Files and directories of the cache directory that belong to no API for
+/// which `is_api` holds, such as the caches of APIs removed from the config
+/// by hand
+pub fn orphans(is_api: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+	let Ok(entries) = fs::read_dir(Config::cache_dir()) else {
+		return Vec::new();
+	};
+	let mut orphans: Vec<_> = entries
+		.filter_map(|entry| entry.ok())
+		.filter(|entry| {
+			let file_name = entry.file_name().to_string_lossy().into_owned();
+			let owners = owners(&file_name);
+			!owners.is_empty() && !owners.into_iter().any(&is_api)
+		})
+		.map(|entry| entry.path())
+		.collect();
+	orphans.sort();
+	orphans
+}
+
+/// Names of the APIs a file of the cache directory may belong to, none for
+/// files shared by all APIs such as the refresh journal
+fn owners(file_name: &str) -> Vec<&str> {
+	const SUFFIXES: &[&str] = &[
+		".endpoints.json",
+		".index.json",
+		".values.json",
+		".json",
+		".versions",
+		".commit",
+		".download",
+	];
+	let file_name = file_name.strip_suffix(".tmp").unwrap_or(file_name);
+	SUFFIXES
+		.iter()
+		.filter_map(|suffix| file_name.strip_suffix(suffix))
+		.filter(|name| !name.is_empty())
+		.collect()
+}
+
+/// Complete or discard a cache transaction interrupted by a crash
+pub fn recover(name: &str) { recover_in(&Config::cache_dir(), name) }
+
+fn recover_in(dir: &Path, name: &str) {
+	let marker = commit_marker(dir, name);
+	let committed = marker.exists();
+	for (tmp, target) in cache_files(dir, name) {
+		if !tmp.exists() {
+			continue;
+		}
+		let result = if committed {
+			debug!("Completing interrupted cache write: {}", target.display());
+			fs::rename(&tmp, &target)
+		} else {
+			debug!("Discarding incomplete cache write: {}", tmp.display());
+			fs::remove_file(&tmp)
+		};
+		if let Err(e) = result {
+			warn!("Failed to recover cache file {}: {}", tmp.display(), e);
+		}
+	}
+	if committed && let Err(e) = fs::remove_file(&marker) {
+		warn!("Failed to remove cache commit marker {}: {}", marker.display(), e);
+	}
+}
+
+/// Seconds since the endpoints cache of an API was written or confirmed
+/// unchanged, if it exists
+pub fn age(name: &str) -> Option<u64> {
+	let modified = fs::metadata(Config::get_endpoints_cache_path(name)).ok()?.modified().ok()?;
+	Some(modified.elapsed().map(|age| age.as_secs()).unwrap_or(0))
+}
+
+/// Mark the endpoints cache of an API as up to date without rewriting it
+pub fn touch(name: &str) -> Result<()> {
+	let path = Config::get_endpoints_cache_path(name);
+	OpenOptions::new()
+		.append(true)
+		.open(&path)
+		.and_then(|file| file.set_modified(SystemTime::now()))
+		.with_context(|| format!("Failed to touch endpoints cache file: {}", path.display()))
+}
+
+/// Append a record to the refresh journal
+pub fn append_journal(record: &RefreshRecord) -> Result<()> {
+	let path = Config::get_refresh_journal_path();
+	let mut file = OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(&path)
+		.with_context(|| format!("Failed to open refresh journal: {}", path.display()))?;
+	writeln!(file, "{}", serde_json::to_string(record)?)
+		.with_context(|| format!("Failed to write refresh journal: {}", path.display()))
+}
+
+/// The most recent refresh recorded for an API, if any
+pub fn last_refresh(name: &str) -> Option<RefreshRecord> {
+	let content = fs::read_to_string(Config::get_refresh_journal_path()).ok()?;
+	content
+		.lines()
+		.rev()
+		.filter_map(|line| serde_json::from_str::<RefreshRecord>(line).ok())
+		.find(|record| record.name == name)
+}
+
+/// Temporary and final paths of the cache files of an API, named as
+/// [`Config::get_cache_path`], [`Config::get_endpoints_cache_path`] and
+/// [`Config::get_index_cache_path`] name them
+fn cache_files(dir: &Path, name: &str) -> [(PathBuf, PathBuf); 3] {
+	[format!("{name}.json"), format!("{name}.endpoints.json"), format!("{name}.index.json")]
+		.map(|file_name| dir.join(file_name))
+		.map(|target| (tmp_path(&target), target))
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+	let mut tmp = path.as_os_str().to_owned();
+	tmp.push(".tmp");
+	PathBuf::from(tmp)
+}
+
+fn commit_marker(dir: &Path, name: &str) -> PathBuf { dir.join(format!("{name}.commit")) }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_owners() {
+		assert_eq!(owners("pets.json"), ["pets"]);
+		assert_eq!(owners("pets.endpoints.json.tmp"), ["pets", "pets.endpoints"]);
+		assert_eq!(owners("pets.values.json"), ["pets", "pets.values"]);
+		assert_eq!(owners("pets.versions"), ["pets"]);
+		assert_eq!(owners("pets.download"), ["pets"]);
+		assert!(owners("refresh.journal").is_empty());
+		assert!(owners(".json").is_empty());
+	}
+
+	/// An empty cache directory of a test
+	fn cache_dir(test: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("httpie-oapi-{}-{}", test, std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		dir
+	}
+
+	#[test]
+	fn test_write_caches() {
+		let dir = cache_dir("write-caches");
+		let spec = serde_json::json!({
+			"openapi": "3.0.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": { "/pets": { "get": { "responses": { "200": { "description": "ok" } } } } }
+		})
+		.to_string();
+		let endpoints = EndPoints::try_from_openapi(&spec).unwrap();
+		write_caches_in(&dir, "pets", &spec, &endpoints).unwrap();
+
+		for (tmp, target) in cache_files(&dir, "pets") {
+			assert!(target.exists() && !tmp.exists());
+		}
+		assert!(!commit_marker(&dir, "pets").exists());
+		assert_eq!(fs::read_to_string(dir.join("pets.json")).unwrap(), spec);
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_recover_committed() {
+		let dir = cache_dir("recover-committed");
+		fs::create_dir_all(&dir).unwrap();
+		// Interrupted after the marker, with one file already in place
+		let [raw, endpoints, index] = cache_files(&dir, "pets");
+		fs::write(&raw.1, "new spec").unwrap();
+		fs::write(&endpoints.0, "new endpoints").unwrap();
+		fs::write(&endpoints.1, "old endpoints").unwrap();
+		fs::write(&index.0, "new index").unwrap();
+		fs::write(commit_marker(&dir, "pets"), "").unwrap();
+
+		recover_in(&dir, "pets");
+		assert_eq!(fs::read_to_string(&raw.1).unwrap(), "new spec");
+		assert_eq!(fs::read_to_string(&endpoints.1).unwrap(), "new endpoints");
+		assert_eq!(fs::read_to_string(&index.1).unwrap(), "new index");
+		assert!(!endpoints.0.exists() && !index.0.exists());
+		assert!(!commit_marker(&dir, "pets").exists());
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_recover_uncommitted() {
+		let dir = cache_dir("recover-uncommitted");
+		fs::create_dir_all(&dir).unwrap();
+		// Interrupted before the marker, while the temporary files were written
+		let [raw, endpoints, _] = cache_files(&dir, "pets");
+		fs::write(&raw.0, "new spec").unwrap();
+		fs::write(&raw.1, "old spec").unwrap();
+		fs::write(&endpoints.0, "new endp").unwrap();
+
+		recover_in(&dir, "pets");
+		assert_eq!(fs::read_to_string(&raw.1).unwrap(), "old spec");
+		assert!(!raw.0.exists() && !endpoints.0.exists() && !endpoints.1.exists());
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+/// Levels of objects, arrays and composed schemas that are flattened into
+/// body params by default
+///
+/// Every level multiplies the params kept in the endpoints cache, while
+/// completion only offers `body_depth` levels of nested objects. The default
+/// `body_depth` of 2 takes 3 levels, and a composed schema such as an `allOf`
+/// on the way takes one more.
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+
+/// Which media type of a request body the body params are taken from, and
+/// how deep its schema is flattened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BodyOptions {
+	/// Media types in order of preference. `json` stands for
+	/// `application/json` and the `+json` types such as
+	/// `application/vnd.api+json`, `form` for
+	/// `application/x-www-form-urlencoded` and `multipart` for the multipart
+	/// types, other entries name a media type
+	pub content_types: Vec<String>,
+	/// Levels of objects, arrays and composed schemas flattened into params
+	pub max_depth: usize,
+}
+
+impl Default for BodyOptions {
+	fn default() -> Self {
+		Self {
+			content_types: ["json", "form", "multipart"].map(str::to_string).to_vec(),
+			max_depth: DEFAULT_MAX_DEPTH,
+		}
+	}
+}
+
+impl BodyOptions {
+	pub fn is_default(&self) -> bool { *self == Self::default() }
+
+	/// Choose the media type of a request body to take params from, the most
+	/// preferred one that is sent as JSON or as a form
+	pub fn choose<'a, T: 'a>(
+		&self,
+		content: impl IntoIterator<Item = (&'a String, &'a T)> + Clone,
+	) -> Option<(BodyKind, &'a T)> {
+		self.content_types.iter().find_map(|preferred| {
+			let (media_type, value) =
+				content.clone().into_iter().find(|(media_type, _)| matches(preferred, media_type))?;
+			Some((BodyKind::of(media_type)?, value))
+		})
+	}
+}
+
+/// How a body of a media type is sent by HTTPie
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyKind {
+	Json,
+	/// A URL encoded or multipart form
+	Form,
+}
+
+impl BodyKind {
+	pub fn of(media_type: &str) -> Option<Self> {
+		let essence = essence(media_type);
+		if essence == "application/json" || essence.ends_with("+json") {
+			Some(Self::Json)
+		} else if essence == "application/x-www-form-urlencoded" || essence.starts_with("multipart/") {
+			Some(Self::Form)
+		} else {
+			None
+		}
+	}
+}
+
+fn matches(preferred: &str, media_type: &str) -> bool {
+	let essence = essence(media_type);
+	match preferred.to_ascii_lowercase().as_str() {
+		"json" => BodyKind::of(&essence) == Some(BodyKind::Json),
+		"form" => essence == "application/x-www-form-urlencoded",
+		"multipart" => essence.starts_with("multipart/"),
+		preferred => essence == preferred,
+	}
+}
+
+/// The media type without its parameters such as `charset`, in lower case
+fn essence(media_type: &str) -> String {
+	media_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_choose() {
+		let content = |types: &[&'static str]| -> Vec<(String, &'static str)> {
+			types.iter().map(|t| (t.to_string(), *t)).collect()
+		};
+		let choose = |options: &BodyOptions, types: &[&'static str]| {
+			let content = content(types);
+			options.choose(content.iter().map(|(k, v)| (k, v))).map(|(kind, v)| (kind, *v))
+		};
+		let default = BodyOptions::default();
+		assert_eq!(
+			choose(&default, &["multipart/form-data", "application/json; charset=utf-8"]),
+			Some((BodyKind::Json, "application/json; charset=utf-8"))
+		);
+		assert_eq!(
+			choose(&default, &["text/plain", "application/vnd.api+json"]),
+			Some((BodyKind::Json, "application/vnd.api+json"))
+		);
+		assert_eq!(choose(&default, &["text/plain"]), None);
+
+		let forms_first = BodyOptions {
+			content_types: vec!["multipart".into(), "json".into()],
+			..BodyOptions::default()
+		};
+		assert_eq!(
+			choose(&forms_first, &["application/json", "multipart/form-data"]),
+			Some((BodyKind::Form, "multipart/form-data"))
+		);
+	}
+}
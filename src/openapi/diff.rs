@@ -0,0 +1,142 @@
+use super::EndPoints;
+use super::endpoint::EndPoint;
+use super::param::Param;
+
+/// Differences between the cached endpoints of an API and a newer version
+#[derive(Debug, Default)]
+pub struct Diff<'a> {
+	/// Endpoints only found in the newer version
+	pub added: Vec<&'a EndPoint>,
+	/// Endpoints no longer found in the newer version
+	pub removed: Vec<&'a EndPoint>,
+	/// Endpoints found in both versions whose params differ
+	pub changed: Vec<(&'a EndPoint, Vec<ParamChange<'a>>)>,
+}
+
+/// A change to a param of an endpoint found in both versions
+#[derive(Debug)]
+pub enum ParamChange<'a> {
+	Added(&'a Param),
+	Removed(&'a Param),
+	/// The param is required in the newer version but was optional before
+	Required(&'a Param),
+	/// The param is optional in the newer version but was required before
+	Optional(&'a Param),
+}
+
+impl<'a> Diff<'a> {
+	/// Compare endpoints by method and path, and their params by source and
+	/// name
+	pub fn new(old: &'a EndPoints, new: &'a EndPoints) -> Self {
+		let mut diff = Self::default();
+		for ep in new.all() {
+			match old.all().into_iter().find(|old_ep| same_endpoint(old_ep, ep)) {
+				Some(old_ep) => {
+					let changes = param_changes(old_ep, ep);
+					if !changes.is_empty() {
+						diff.changed.push((ep, changes));
+					}
+				}
+				None => diff.added.push(ep),
+			}
+		}
+		diff.removed = old
+			.all()
+			.into_iter()
+			.filter(|ep| !new.all().iter().any(|new_ep| same_endpoint(ep, new_ep)))
+			.collect();
+		diff
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+	}
+
+	/// Number of changes that break requests made against the older version
+	pub fn breaking_count(&self) -> usize {
+		let breaking_params =
+			self.changed.iter().flat_map(|(_, changes)| changes).filter(|change| change.is_breaking());
+		self.removed.len() + breaking_params.count()
+	}
+}
+
+impl ParamChange<'_> {
+	/// Whether requests made against the older version may be rejected: a
+	/// param was removed or a required param was added
+	pub fn is_breaking(&self) -> bool {
+		match self {
+			ParamChange::Added(param) => param.required,
+			ParamChange::Removed(_) | ParamChange::Required(_) => true,
+			ParamChange::Optional(_) => false,
+		}
+	}
+}
+
+fn same_endpoint(a: &EndPoint, b: &EndPoint) -> bool { a.method == b.method && a.path == b.path }
+
+fn same_param(a: &Param, b: &Param) -> bool { a.source == b.source && a.name == b.name }
+
+fn param_changes<'a>(old: &'a EndPoint, new: &'a EndPoint) -> Vec<ParamChange<'a>> {
+	let mut changes = Vec::new();
+	for param in &new.params {
+		match old.params.iter().find(|old_param| same_param(old_param, param)) {
+			Some(old_param) if param.required && !old_param.required => {
+				changes.push(ParamChange::Required(param))
+			}
+			Some(old_param) if !param.required && old_param.required => {
+				changes.push(ParamChange::Optional(param))
+			}
+			Some(_) => {}
+			None => changes.push(ParamChange::Added(param)),
+		}
+	}
+	for param in &old.params {
+		if !new.params.iter().any(|new_param| same_param(param, new_param)) {
+			changes.push(ParamChange::Removed(param));
+		}
+	}
+	changes
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn endpoints(paths: &str) -> EndPoints {
+		let spec = format!(
+			r#"{{ "openapi": "3.0.0", "info": {{ "title": "Test", "version": "1" }}, "paths": {{ {paths} }} }}"#
+		);
+		EndPoints::try_from_openapi(spec).unwrap()
+	}
+
+	#[test]
+	fn test_diff() {
+		let old = endpoints(
+			r#""/users": { "get": { "parameters": [
+				{ "name": "page", "in": "query", "schema": {} },
+				{ "name": "sort", "in": "query", "required": true, "schema": {} }
+			], "responses": {} } },
+			"/legacy": { "get": { "responses": {} } }"#,
+		);
+		let new = endpoints(
+			r#""/users": { "get": { "parameters": [
+				{ "name": "page", "in": "query", "required": true, "schema": {} },
+				{ "name": "limit", "in": "query", "schema": {} }
+			], "responses": {} } },
+			"/orders": { "get": { "responses": {} } }"#,
+		);
+		let diff = Diff::new(&old, &new);
+		assert_eq!(diff.added[0].path, "/orders");
+		assert_eq!(diff.removed[0].path, "/legacy");
+		let (ep, changes) = &diff.changed[0];
+		assert_eq!(ep.path, "/users");
+		assert!(matches!(changes[..], [
+			ParamChange::Required(_),
+			ParamChange::Added(_),
+			ParamChange::Removed(_)
+		]));
+		// The removed endpoint, the now required and the removed param
+		assert_eq!(diff.breaking_count(), 3);
+		assert!(Diff::new(&new, &new).is_empty());
+	}
+}
@@ -0,0 +1,473 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result, anyhow};
+use clap::ValueEnum;
+use openapiv3::{OpenAPI, PathItem, Server, ServerVariable};
+use tracing::warn;
+use url::Url;
+
+use super::{har, json, openapi31, postman, swagger2};
+
+/// Formats of the documents an API can be added from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DocumentFormat {
+	/// OpenAPI 3.0 or 3.1
+	#[value(name = "openapi")]
+	OpenApi,
+	/// Swagger 2.0
+	Swagger,
+	/// Postman v2 collection
+	Postman,
+	/// HTTP Archive of recorded traffic
+	Har,
+}
+
+impl DocumentFormat {
+	/// Detect the format of a JSON or YAML document
+	pub fn detect(data: &str) -> Result<Self> { Ok(Self::of(&read(data)?)) }
+
+	fn of(document: &serde_json::Value) -> Self {
+		if swagger2::is_swagger2(document) {
+			Self::Swagger
+		} else if postman::is_postman(document) {
+			Self::Postman
+		} else if har::is_har(document) {
+			Self::Har
+		} else {
+			Self::OpenApi
+		}
+	}
+}
+
+impl std::fmt::Display for DocumentFormat {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::OpenApi => write!(f, "OpenAPI"),
+			Self::Swagger => write!(f, "Swagger 2.0"),
+			Self::Postman => write!(f, "Postman collection"),
+			Self::Har => write!(f, "HTTP Archive"),
+		}
+	}
+}
+
+/// Read a JSON or YAML document
+pub(super) fn read(data: &str) -> Result<serde_json::Value> {
+	// JSON documents are objects, anything else is treated as YAML
+	if data.trim_start().starts_with('{') {
+		json::from_str(data).context("Invalid OpenAPI JSON document")
+	} else {
+		serde_yaml::from_str(data).context("Invalid OpenAPI YAML document")
+	}
+}
+
+/// Top level keys of a document that endpoints are built from
+const ENDPOINT_KEYS: &[&str] =
+	&["openapi", "info", "paths", openapi31::WEBHOOKS, "components", "security"];
+
+/// Components that the references of endpoints are resolved against
+const ENDPOINT_COMPONENTS: &[&str] =
+	&["schemas", "parameters", "requestBodies", "responses", "examples", "securitySchemes"];
+
+/// Keys of operations, responses and media types that endpoints never read
+const UNUSED_OPERATION_KEYS: &[&str] = &["externalDocs"];
+const UNUSED_RESPONSE_KEYS: &[&str] = &["headers", "links"];
+const UNUSED_MEDIA_TYPE_KEYS: &[&str] = &["example", "examples", "encoding"];
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Keys of path items other than their operations
+const PATH_ITEM_KEYS: &[&str] = &["$ref", "summary", "description", "servers", "parameters"];
+
+/// Parse an OpenAPI 3.0/3.1 or Swagger 2 document, a Postman collection or an
+/// HTTP Archive in JSON or YAML
+pub fn parse(data: &str) -> Result<OpenAPI> {
+	let mut document = convert(read(data)?);
+	skip_unknown_methods(&mut document);
+	serde_json::from_value(document).context("Invalid OpenAPI document")
+}
+
+/// Parse only the parts of a document that endpoints are built from
+///
+/// Unlike [`parse`], sections such as tags, response headers and media type
+/// examples are dropped before the document is deserialized, which is most of
+/// the work of parsing a large spec. The result is only meant for
+/// [`super::EndPoints`].
+pub fn parse_endpoints(data: &str) -> Result<OpenAPI> {
+	let mut document = convert(read(data)?);
+	skip_unknown_methods(&mut document);
+	strip_unused(&mut document);
+	serde_json::from_value(document).context("Invalid OpenAPI document")
+}
+
+/// Convert a document of any supported format into the OpenAPI 3.0 shape
+fn convert(document: serde_json::Value) -> serde_json::Value {
+	match DocumentFormat::of(&document) {
+		DocumentFormat::Swagger => swagger2::convert(document),
+		DocumentFormat::Postman => postman::convert(document),
+		DocumentFormat::Har => har::convert(document),
+		DocumentFormat::OpenApi if openapi31::is_openapi31(&document) => openapi31::downgrade(document),
+		DocumentFormat::OpenApi => document,
+	}
+}
+
+/// Drop the operations of methods OpenAPI has no field for, such as custom
+/// verbs of imported traffic, warning about each one
+fn skip_unknown_methods(document: &mut serde_json::Value) {
+	let paths = document.get_mut("paths").and_then(|paths| paths.as_object_mut());
+	for (path, item) in paths.into_iter().flatten() {
+		let Some(item) = item.as_object_mut() else {
+			continue;
+		};
+		item.retain(|key, _| {
+			let known = METHODS.contains(&key.as_str())
+				|| PATH_ITEM_KEYS.contains(&key.as_str())
+				|| key.starts_with("x-");
+			if !known {
+				warn!("Skipping {} {} operation of an unsupported HTTP method", key.to_uppercase(), path);
+			}
+			known
+		});
+	}
+}
+
+/// Drop the parts of an OpenAPI 3.0 document that endpoints never read
+fn strip_unused(document: &mut serde_json::Value) {
+	let Some(document) = document.as_object_mut() else {
+		return;
+	};
+	document.retain(|key, _| ENDPOINT_KEYS.contains(&key.as_str()));
+	if let Some(info) = document.get_mut("info").and_then(|info| info.as_object_mut()) {
+		info.retain(|key, _| key == "title" || key == "version");
+	}
+	if let Some(components) = document.get_mut("components").and_then(|c| c.as_object_mut()) {
+		components.retain(|key, _| ENDPOINT_COMPONENTS.contains(&key.as_str()));
+		let bodies = components.get_mut("requestBodies").and_then(|bodies| bodies.as_object_mut());
+		bodies.into_iter().flat_map(|bodies| bodies.values_mut()).for_each(strip_media_types);
+		let responses = components.get_mut("responses").and_then(|r| r.as_object_mut());
+		responses.into_iter().flat_map(|r| r.values_mut()).for_each(strip_response);
+	}
+	let operations = document
+		.iter_mut()
+		.filter(|(key, _)| *key == "paths" || *key == openapi31::WEBHOOKS)
+		.filter_map(|(_, items)| items.as_object_mut())
+		.flat_map(|items| items.values_mut())
+		.filter_map(|item| item.as_object_mut())
+		.flat_map(|item| item.iter_mut().filter(|(key, _)| METHODS.contains(&key.as_str())))
+		.filter_map(|(_, operation)| operation.as_object_mut());
+	for operation in operations {
+		operation.retain(|key, _| !UNUSED_OPERATION_KEYS.contains(&key.as_str()));
+		if let Some(body) = operation.get_mut("requestBody") {
+			strip_media_types(body);
+		}
+		let responses = operation.get_mut("responses").and_then(|r| r.as_object_mut());
+		responses.into_iter().flat_map(|r| r.values_mut()).for_each(strip_response);
+	}
+}
+
+fn strip_response(response: &mut serde_json::Value) {
+	if let Some(response) = response.as_object_mut() {
+		response.retain(|key, _| !UNUSED_RESPONSE_KEYS.contains(&key.as_str()));
+	}
+	strip_media_types(response);
+}
+
+fn strip_media_types(owner: &mut serde_json::Value) {
+	let content = owner.get_mut("content").and_then(|content| content.as_object_mut());
+	for media_type in content.into_iter().flat_map(|content| content.values_mut()) {
+		if let Some(media_type) = media_type.as_object_mut() {
+			media_type.retain(|key, _| !UNUSED_MEDIA_TYPE_KEYS.contains(&key.as_str()));
+		}
+	}
+}
+
+/// Get the path items of the webhooks of a document, by name
+pub fn webhooks(openapi: &OpenAPI) -> Vec<(String, PathItem)> {
+	let webhooks = openapi.extensions.get(openapi31::WEBHOOKS).and_then(|w| w.as_object());
+	webhooks
+		.into_iter()
+		.flatten()
+		.filter_map(|(name, item)| match serde_json::from_value(item.clone()) {
+			Ok(item) => Some((name.clone(), item)),
+			Err(e) => {
+				warn!("Skipping invalid webhook {}: {}", name, e);
+				None
+			}
+		})
+		.collect()
+}
+
+/// Get the server URLs of a document, with server variables set to `vars` or
+/// to their defaults and relative URLs resolved against the spec URL
+///
+/// Relative URLs of specs that are not downloaded over HTTP are skipped.
+pub fn server_urls(
+	openapi: &OpenAPI,
+	spec_url: &str,
+	vars: &BTreeMap<String, String>,
+) -> Vec<String> {
+	resolve_servers(openapi, spec_url, vars).into_iter().map(|(url, _)| url).collect()
+}
+
+/// Get the servers of a document along with their resolved URLs
+pub(super) fn resolve_servers<'a>(
+	openapi: &'a OpenAPI,
+	spec_url: &str,
+	vars: &BTreeMap<String, String>,
+) -> Vec<(String, &'a Server)> {
+	let spec_url = Url::parse(spec_url).ok().filter(|url| url.scheme().starts_with("http"));
+	openapi
+		.servers
+		.iter()
+		.filter_map(|server| {
+			let url = fill_variables(server, vars);
+			let url = match Url::parse(&url) {
+				Ok(url) => url,
+				Err(_) => spec_url.as_ref()?.join(&url).ok()?,
+			};
+			Some((url.as_str().trim_end_matches('/').to_string(), server))
+		})
+		.collect()
+}
+
+/// Get the path the first server of a document serves the API under, such as
+/// the `basePath` of a Swagger 2 spec or a relative server URL, without a
+/// trailing slash
+///
+/// Servers on another origin than `base_url` serve the API somewhere else,
+/// so they have no path for it.
+pub fn server_path(
+	openapi: &OpenAPI,
+	vars: &BTreeMap<String, String>,
+	base_url: &Url,
+) -> Option<String> {
+	let url = fill_variables(openapi.servers.first()?, vars);
+	let url = match Url::parse(&url) {
+		Ok(url) if url.origin() != base_url.origin() => return None,
+		Ok(url) => url,
+		Err(_) => base_url.join(&url).ok()?,
+	};
+	let path = url.path().trim_end_matches('/');
+	(!path.is_empty()).then(|| path.to_string())
+}
+
+/// Get the URL of a server with its variables set to `vars` or to their
+/// defaults, relative URLs left as they are
+pub fn fill_variables(server: &Server, vars: &BTreeMap<String, String>) -> String {
+	let mut url = server.url.clone();
+	for (name, variable) in server.variables.iter().flatten() {
+		let value = vars.get(name).unwrap_or(&variable.default);
+		url = url.replace(&format!("{{{name}}}"), value);
+	}
+	url
+}
+
+/// Get the variables of the server URLs of a document, the first declaration
+/// of each name
+pub fn server_variables(openapi: &OpenAPI) -> Vec<(&str, &ServerVariable)> {
+	let mut variables: Vec<(&str, &ServerVariable)> = Vec::new();
+	for (name, variable) in
+		openapi.servers.iter().flat_map(|server| server.variables.iter().flatten())
+	{
+		if !variables.iter().any(|(seen, _)| seen == name) {
+			variables.push((name, variable));
+		}
+	}
+	variables
+}
+
+/// Check that `vars` are variables of the server URLs of a document and take
+/// one of their allowed values
+pub fn check_server_vars(openapi: &OpenAPI, vars: &BTreeMap<String, String>) -> Result<()> {
+	let variables = server_variables(openapi);
+	for (name, value) in vars {
+		let Some((_, variable)) = variables.iter().find(|(declared, _)| declared == name) else {
+			let declared: Vec<_> = variables.iter().map(|(name, _)| *name).collect();
+			return Err(anyhow!(
+				"The servers of the spec have no variable '{}'{}",
+				name,
+				if declared.is_empty() {
+					String::new()
+				} else {
+					format!(", they have {}", declared.join(", "))
+				}
+			));
+		};
+		if !variable.enumeration.is_empty() && !variable.enumeration.contains(value) {
+			return Err(anyhow!(
+				"Invalid value '{}' of server variable '{}', expected one of {}",
+				value,
+				name,
+				variable.enumeration.join(", ")
+			));
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::openapi::EndPoints;
+
+	#[test]
+	fn test_server_urls() {
+		let openapi = parse(
+			r#"{
+				"openapi": "3.0.0",
+				"info": { "title": "Test API", "version": "1.0.0" },
+				"paths": {},
+				"servers": [
+					{
+						"url": "https://{region}.example.com/v1/",
+						"variables": { "region": { "default": "eu", "enum": ["eu", "us"] } }
+					},
+					{ "url": "/api" }
+				]
+			}"#,
+		)
+		.unwrap();
+
+		let defaults = BTreeMap::new();
+		assert_eq!(
+			server_urls(&openapi, "https://docs.example.com/spec/openapi.json", &defaults),
+			vec!["https://eu.example.com/v1", "https://docs.example.com/api"]
+		);
+		assert_eq!(server_urls(&openapi, "/tmp/openapi.json", &defaults), vec![
+			"https://eu.example.com/v1"
+		]);
+
+		let vars = BTreeMap::from([("region".to_string(), "us".to_string())]);
+		assert_eq!(server_urls(&openapi, "/tmp/openapi.json", &vars), vec![
+			"https://us.example.com/v1"
+		]);
+		assert!(check_server_vars(&openapi, &vars).is_ok());
+		let vars = BTreeMap::from([("region".to_string(), "ap".to_string())]);
+		let error = check_server_vars(&openapi, &vars).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"Invalid value 'ap' of server variable 'region', expected one of eu, us"
+		);
+		let vars = BTreeMap::from([("stage".to_string(), "dev".to_string())]);
+		assert!(check_server_vars(&openapi, &vars).is_err());
+	}
+
+	#[test]
+	fn test_server_path() {
+		let vars = BTreeMap::new();
+		let base_url = Url::parse("https://example.com").unwrap();
+		let swagger = parse(
+			r#"{
+				"swagger": "2.0",
+				"info": { "title": "Test API", "version": "1.0.0" },
+				"basePath": "/v2/",
+				"paths": {}
+			}"#,
+		)
+		.unwrap();
+		assert_eq!(server_path(&swagger, &vars, &base_url).as_deref(), Some("/v2"));
+
+		let server = |url: &str| {
+			let spec = serde_json::json!({
+				"openapi": "3.0.0",
+				"info": { "title": "Test API", "version": "1.0.0" },
+				"paths": {},
+				"servers": [{ "url": url }]
+			});
+			parse(&spec.to_string()).unwrap()
+		};
+		let path = |url: &str| server_path(&server(url), &vars, &base_url);
+		assert_eq!(path("api/v1").as_deref(), Some("/api/v1"));
+		assert_eq!(path("https://example.com/v3").as_deref(), Some("/v3"));
+		assert_eq!(path("https://example.com"), None);
+		assert_eq!(path("/"), None);
+		assert_eq!(path("https://other.example.com/v3"), None);
+		assert_eq!(path("http://example.com/v3"), None);
+	}
+
+	#[test]
+	fn test_unknown_methods() {
+		let spec = r#"{
+			"openapi": "3.0.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": {
+				"/files": {
+					"summary": "Files",
+					"trace": { "responses": { "200": { "description": "ok" } } },
+					"propfind": { "responses": { "207": { "description": "ok" } } }
+				}
+			}
+		}"#;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let methods: Vec<_> = endpoints.all().iter().map(|ep| ep.method.to_string()).collect();
+		assert_eq!(methods, ["TRACE"]);
+		assert!(parse(spec).is_ok());
+	}
+
+	#[test]
+	fn test_parse_endpoints() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+  description: Long description
+tags:
+  - name: users
+paths:
+  /users/{id}:
+    servers:
+      - url: https://users.example.com
+    get:
+      tags: [users]
+      parameters:
+        - name: id
+          in: path
+          required: true
+          example: 42
+          schema:
+            type: integer
+      security:
+        - token: []
+      responses:
+        "200":
+          description: The user
+          headers:
+            X-Rate-Limit:
+              schema:
+                type: integer
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/User"
+              examples:
+                admin:
+                  value: { name: root }
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        name:
+          type: string
+  examples:
+    User:
+      value: { name: root }
+  securitySchemes:
+    token:
+      type: http
+      scheme: bearer
+"##;
+		let full = EndPoints::from(parse(spec).unwrap());
+		let openapi = parse_endpoints(spec).unwrap();
+		let components = openapi.components.as_ref().unwrap();
+		// Shared examples and security schemes are kept for the endpoints
+		assert!(components.examples.contains_key("User"));
+		assert!(components.security_schemes.contains_key("token"));
+		assert!(openapi.tags.is_empty() && openapi.info.description.is_none());
+		let partial = EndPoints::from(openapi);
+		assert_eq!(serde_json::to_value(&partial).unwrap(), serde_json::to_value(&full).unwrap());
+		let endpoint = partial.find("/users/:id").unwrap();
+		assert_eq!(endpoint.params[0].examples, ["42"]);
+		assert_eq!(endpoint.response_fields, ["name"]);
+	}
+}
@@ -0,0 +1,161 @@
+use std::fs::{self, File};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use reqwest::blocking::Response;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::retry::RetryPolicy;
+use crate::config::Config;
+
+/// Largest spec document downloaded unless configured otherwise
+const DEFAULT_MAX_SIZE: &str = "100MB";
+
+/// Size of the chunks a response body is streamed in
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// How spec documents are downloaded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadOptions {
+	#[serde(flatten)]
+	pub retry: RetryPolicy,
+	/// Largest spec document that is downloaded, such as `100MB`, 0 lifts the
+	/// limit
+	pub max_size: String,
+}
+
+impl Default for DownloadOptions {
+	fn default() -> Self {
+		Self { retry: RetryPolicy::default(), max_size: DEFAULT_MAX_SIZE.to_string() }
+	}
+}
+
+impl DownloadOptions {
+	pub fn is_default(&self) -> bool { *self == Self::default() }
+
+	/// Read the body of a spec download
+	///
+	/// The body is streamed into a temporary file of the cache directory and
+	/// read back once complete, failing as soon as it grows beyond the size
+	/// limit. With `progress` the downloaded size is shown on a terminal.
+	pub fn read_body(&self, mut response: Response, name: &str, progress: bool) -> Result<String> {
+		let max_size = self.max_size();
+		let total = response.content_length();
+		if let Some(total) = total.filter(|&total| max_size > 0 && total > max_size) {
+			return Err(too_large(total, max_size));
+		}
+
+		let path = Config::get_download_path(name);
+		let progress = progress && io::stderr().is_terminal();
+		let result = stream(&mut response, &path, max_size, progress.then_some(total)).and_then(|()| {
+			fs::read_to_string(&path)
+				.with_context(|| format!("Failed to read downloaded spec: {}", path.display()))
+		});
+		if let Err(e) = fs::remove_file(&path) {
+			warn!("Failed to remove downloaded spec {}: {}", path.display(), e);
+		}
+		result
+	}
+
+	/// The size limit in bytes, 0 for none
+	fn max_size(&self) -> u64 {
+		parse_size(&self.max_size).unwrap_or_else(|e| {
+			warn!("Invalid download max_size: {:#}", e);
+			parse_size(DEFAULT_MAX_SIZE).expect("valid default size")
+		})
+	}
+}
+
+/// Copy a response body into `path` chunk by chunk, reporting progress
+/// against the expected total when `progress` is given
+fn stream(
+	response: &mut Response,
+	path: &Path,
+	max_size: u64,
+	progress: Option<Option<u64>>,
+) -> Result<()> {
+	let mut file = File::create(path)
+		.with_context(|| format!("Failed to create download file: {}", path.display()))?;
+	let mut chunk = vec![0; CHUNK_SIZE];
+	let mut size = 0;
+	loop {
+		let read = response.read(&mut chunk).context("Failed to read OpenAPI spec")?;
+		if read == 0 {
+			break;
+		}
+		size += read as u64;
+		if max_size > 0 && size > max_size {
+			return Err(too_large(size, max_size));
+		}
+		file
+			.write_all(&chunk[..read])
+			.with_context(|| format!("Failed to write download file: {}", path.display()))?;
+		if let Some(total) = progress {
+			report(size, total);
+		}
+	}
+	if progress.is_some() {
+		// Clear the progress line
+		eprint!("\r\x1b[K");
+	}
+	Ok(())
+}
+
+fn report(size: u64, total: Option<u64>) {
+	let megabytes = |bytes: u64| bytes as f64 / 1048576.0;
+	match total {
+		Some(total) if total > 0 => eprint!(
+			"\rDownloading spec: {:.1}/{:.1}MB ({}%)",
+			megabytes(size),
+			megabytes(total),
+			size * 100 / total
+		),
+		_ => eprint!("\rDownloading spec: {:.1}MB", megabytes(size)),
+	}
+}
+
+fn too_large(size: u64, max_size: u64) -> anyhow::Error {
+	anyhow!(
+		"Failed to fetch OpenAPI spec: the document exceeds the download limit of {} bytes ({} bytes or more), raise `max_size` in the [download] section of the config",
+		max_size,
+		size
+	)
+}
+
+/// Parse a size like `512KB`, `100MB` or `1GB` into bytes, units are powers
+/// of 1024
+pub fn parse_size(s: &str) -> Result<u64> {
+	let s = s.trim();
+	let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+	let (value, unit) = s.split_at(split);
+	let value: u64 = value.parse().map_err(|_| anyhow!("Invalid size '{}'", s))?;
+	let factor = match unit.trim().to_ascii_uppercase().as_str() {
+		"B" | "" => 1,
+		"KB" | "K" => 1 << 10,
+		"MB" | "M" => 1 << 20,
+		"GB" | "G" => 1 << 30,
+		_ => return Err(anyhow!("Invalid size unit in '{}', expected B, KB, MB or GB", s)),
+	};
+	value.checked_mul(factor).ok_or_else(|| anyhow!("Size '{}' is too large", s))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_size() {
+		assert_eq!(parse_size("512").unwrap(), 512);
+		assert_eq!(parse_size("4KB").unwrap(), 4096);
+		assert_eq!(parse_size("100MB").unwrap(), 100 * 1048576);
+		assert_eq!(parse_size("1gb").unwrap(), 1 << 30);
+		assert!(parse_size("MB").is_err());
+		assert!(parse_size("3TB").is_err());
+		assert!(parse_size("99999999999GB").is_err());
+		assert!(parse_size("99999999999999999999").is_err());
+		assert_eq!(DownloadOptions::default().max_size(), 100 * 1048576);
+	}
+}
@@ -1,24 +1,136 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::path::Path;
+use std::sync::OnceLock;
 
 use anyhow::{Context, Result};
-use openapiv3::{OpenAPI, ReferenceOr, Schema};
-use serde::{Deserialize, Serialize};
+use openapiv3::{OpenAPI, PathItem, ReferenceOr, Schema, SchemaKind, StatusCode, Type};
+use serde::de::{self, MapAccess, SeqAccess};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tracing::{debug, info, warn};
+use url::Url;
 
-use super::reference::resolve_schema_reference;
-use super::{Method, Param};
+use super::content_type::{BodyKind, BodyOptions};
+use super::param::{ParamSource, insert_nested, nested_values};
+use super::path_filter::PathFilter;
+use super::path_index::PathIndex;
+use super::response::Response;
+use super::security::{self, Security};
+use super::reference::{
+	resolve_parameter_reference, resolve_request_body_reference, resolve_response_reference,
+	resolve_schema_reference,
+};
+use super::{document, extensions, json};
+use super::{BodyParams, Method, Param};
+use crate::theme::Theme;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct EndPoints(Vec<EndPoint>);
+#[derive(Debug, Default, Clone)]
+pub struct EndPoints {
+	endpoints: Vec<EndPoint>,
+	/// Operations of the `webhooks` of an OpenAPI 3.1 spec, named after the
+	/// webhook in place of a path
+	webhooks: Vec<EndPoint>,
+	/// Index of the endpoint paths, built on the first lookup unless loaded
+	/// from the cache
+	index: OnceLock<PathIndex>,
+}
+
+/// Endpoints are cached as a list, in an object along with the webhooks when
+/// the spec has any, so caches of specs without webhooks keep their format
+impl Serialize for EndPoints {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		if self.webhooks.is_empty() {
+			return self.endpoints.serialize(serializer);
+		}
+		let mut grouped = serializer.serialize_struct("EndPoints", 2)?;
+		grouped.serialize_field("endpoints", &self.endpoints)?;
+		grouped.serialize_field("webhooks", &self.webhooks)?;
+		grouped.end()
+	}
+}
+
+impl<'de> Deserialize<'de> for EndPoints {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct Visitor;
+
+		impl<'de> de::Visitor<'de> for Visitor {
+			type Value = EndPoints;
+
+			fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+				f.write_str("a list of endpoints or an object of endpoints and webhooks")
+			}
+
+			fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<EndPoints, A::Error> {
+				let endpoints = Vec::deserialize(de::value::SeqAccessDeserializer::new(seq))?;
+				Ok(EndPoints::new(endpoints))
+			}
+
+			fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<EndPoints, A::Error> {
+				#[derive(Deserialize)]
+				struct Grouped {
+					endpoints: Vec<EndPoint>,
+					#[serde(default)]
+					webhooks:  Vec<EndPoint>,
+				}
+				let grouped = Grouped::deserialize(de::value::MapAccessDeserializer::new(map))?;
+				Ok(EndPoints { webhooks: grouped.webhooks, ..EndPoints::new(grouped.endpoints) })
+			}
+		}
+
+		deserializer.deserialize_any(Visitor)
+	}
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EndPoint {
 	pub method: Method,
 	pub path: String,
 	pub summary: Option<String>,
+	/// Longer description of the operation
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub description: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub operation_id: Option<String>,
 	pub params: Vec<Param>,
+	/// Tags the operation is grouped under in the spec
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub tags: Vec<String>,
+	/// Property names of successful JSON responses, nested ones joined with `.`
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub response_fields: Vec<String>,
+	/// Documented responses by status, in the order of the spec
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub responses: Vec<Response>,
+	/// Whether the spec marks the operation as deprecated
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub deprecated: bool,
+	/// Alternative ways the operation authenticates, see
+	/// [`security::requirements`]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub security: Vec<Vec<Security>>,
+	/// Server that the operation or its path declares in place of the servers
+	/// of the spec, with its variables set to their defaults
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub server: Option<String>,
+	/// Requests the API sends once the operation was called
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub callbacks: Vec<Callback>,
+}
+
+/// A request the API sends back to a URL of the caller, given by a runtime
+/// expression such as `{$request.body#/callbackUrl}`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Callback {
+	/// Name of the callback in the operation
+	pub name: String,
+	/// The request, with the URL expression in place of a path
+	pub endpoint: EndPoint,
 }
 
+/// Maximum nesting depth followed when indexing response properties
+const RESPONSE_FIELD_DEPTH: usize = 4;
+
 impl EndPoint {
 	pub fn get_params_sort(&self) -> Vec<Param> {
 		let mut sorted = self.params.clone();
@@ -26,13 +138,216 @@ impl EndPoint {
 		sorted
 	}
 
-	pub fn fzf_list_format(&self, base_url: impl AsRef<str>) -> String {
-		format!("{} {}{}", self.method, base_url.as_ref(), self.path)
+	/// Get the sorted params to offer for completion under the given body mode
+	///
+	/// Body params nested up to `depth` objects and arrays deep are offered in
+	/// place of the object or array they are nested in.
+	pub fn get_completion_params(&self, mode: BodyParams, depth: usize) -> Vec<Param> {
+		let has_children = |param: &Param| {
+			let prefix = format!("{}[", param.name);
+			self.params.iter().any(|other| other.source.is_body() && other.name.starts_with(&prefix))
+		};
+		self
+			.get_params_sort()
+			.into_iter()
+			.filter(|param| {
+				if !param.source.is_body() {
+					return true;
+				}
+				let offered = match mode {
+					BodyParams::All => true,
+					BodyParams::Required => param.required,
+					BodyParams::None => false,
+				};
+				offered && param.nesting() <= depth && (param.nesting() == depth || !has_children(param))
+			})
+			.collect()
+	}
+
+	pub fn has_body_params(&self) -> bool { self.params.iter().any(|param| param.source.is_body()) }
+
+	/// Whether the body is sent as JSON rather than as a form
+	pub fn has_json_body(&self) -> bool {
+		self.params.iter().any(|param| param.source == ParamSource::Body)
+	}
+
+	/// The HTTPie flag sending the form body of the endpoint, `--multipart`
+	/// when it has file parts
+	pub fn form_flag(&self) -> Option<&'static str> {
+		let mut form = self.params.iter().filter(|param| param.source.is_form()).peekable();
+		form.peek()?;
+		Some(if form.any(|param| param.source == ParamSource::File) { "--multipart" } else { "--form" })
+	}
+
+	/// Build a JSON body skeleton containing the required body properties
+	pub fn body_skeleton(&self) -> serde_json::Value {
+		let fields = self
+			.params
+			.iter()
+			.filter(|param| param.source == ParamSource::Body && param.required && param.nesting() == 0)
+			.map(|param| (param.name.clone(), serde_json::Value::Null))
+			.collect();
+		serde_json::Value::Object(fields)
+	}
+
+	/// Build an example JSON body from the body params, of the required ones
+	/// only unless `all`
+	///
+	/// Fields are filled in with the example values of their params, objects
+	/// and arrays with the fields nested in them.
+	pub fn example_body(&self, all: bool) -> serde_json::Value {
+		let fields: Vec<_> = self
+			.params
+			.iter()
+			.filter(|param| param.source == ParamSource::Body && (all || param.required))
+			.collect();
+		let has_fields = |param: &Param| {
+			let prefix = format!("{}[", param.name);
+			fields.iter().any(|other| other.name.starts_with(&prefix))
+		};
+		let mut body = serde_json::Value::Object(Default::default());
+		for field in fields.iter().filter(|&&field| !has_fields(field)) {
+			insert_nested(&mut body, &field.name, field.example_json());
+		}
+		body
+	}
+
+	/// Check a JSON body against the body params, listing the required fields
+	/// it lacks and the fields whose values have another type or are not
+	/// among the allowed values
+	pub fn validate_body(&self, body: &serde_json::Value) -> Vec<String> {
+		let mut errors = Vec::new();
+		if !body.is_object() {
+			errors.push("The body must be a JSON object".to_string());
+			return errors;
+		}
+		for param in self.params.iter().filter(|param| param.source == ParamSource::Body) {
+			let (values, missing) = nested_values(body, &param.name);
+			if param.required && missing > 0 {
+				errors.push(format!("{}: required field is missing", param.name));
+			}
+			for value in values.into_iter().filter(|value| !value.is_null()) {
+				let schema_type = param.schema_type.as_deref().unwrap_or_default();
+				let expected = schema_type.split(' ').next().unwrap_or_default();
+				let matches = match expected {
+					"integer" => value.is_i64() || value.is_u64(),
+					"number" => value.is_number(),
+					"boolean" => value.is_boolean(),
+					"string" => value.is_string(),
+					"object" => value.is_object(),
+					"array" => value.is_array(),
+					_ => true,
+				};
+				if !matches {
+					errors.push(format!("{}: expected {}, got {}", param.name, schema_type, value));
+					continue;
+				}
+				let rendered = match value {
+					serde_json::Value::String(s) => s.clone(),
+					other => other.to_string(),
+				};
+				if !param.enum_values.is_empty() && !param.enum_values.contains(&rendered) {
+					let allowed = param.enum_values.join(", ");
+					errors.push(format!("{}: {} is not one of {}", param.name, value, allowed));
+				}
+			}
+		}
+		errors
+	}
+
+	/// Format the body skeleton as a `--raw` completion candidate
+	pub fn fish_body_skeleton_format(&self) -> String {
+		format!("--raw={}\tbody skeleton", self.body_skeleton())
+	}
+
+	/// Get the response fields matching a pattern, case-insensitively
+	pub fn matching_response_fields(&self, pattern: &str) -> Vec<&str> {
+		let pattern = pattern.to_lowercase();
+		self
+			.response_fields
+			.iter()
+			.filter(|field| field.to_lowercase().contains(&pattern))
+			.map(String::as_str)
+			.collect()
+	}
+
+	/// Whether every term is found in the path, summary, description or
+	/// operationId, compared case-insensitively
+	pub fn matches_terms(&self, terms: &[String]) -> bool {
+		let fields = [
+			Some(&self.path),
+			self.summary.as_ref(),
+			self.description.as_ref(),
+			self.operation_id.as_ref(),
+		];
+		let text = fields.into_iter().flatten().map(|field| field.to_lowercase()).collect::<Vec<_>>();
+		terms.iter().all(|term| {
+			let term = term.to_lowercase();
+			text.iter().any(|field| field.contains(&term))
+		})
+	}
+
+	/// Whether the endpoint is grouped under a tag, compared case-insensitively
+	pub fn has_tag(&self, tag: &str) -> bool { self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) }
+
+	/// The base URL of the endpoint, that of the API unless the operation or
+	/// its path declares its own server
+	///
+	/// Relative servers are resolved against the base URL of the API.
+	pub fn base_url<'a>(&'a self, api_base_url: &'a str) -> Cow<'a, str> {
+		let Some(server) = &self.server else {
+			return Cow::Borrowed(api_base_url);
+		};
+		if Url::parse(server).is_ok() {
+			return Cow::Borrowed(server.trim_end_matches('/'));
+		}
+		match Url::parse(api_base_url).and_then(|base| base.join(server)) {
+			Ok(url) => Cow::Owned(url.as_str().trim_end_matches('/').to_string()),
+			Err(_) => Cow::Borrowed(api_base_url),
+		}
+	}
+
+	pub fn fzf_list_format(&self, base_url: impl AsRef<str>, theme: &Theme) -> String {
+		let base_url = self.base_url(base_url.as_ref());
+		let line = format!("{} {}{}", theme.method(&self.method.to_string()), base_url, self.path);
+		match theme.deprecated_marker().filter(|_| self.deprecated) {
+			Some(marker) => format!("{} {}", line, marker),
+			None => line,
+		}
 	}
 
 	pub fn fish_complete_format(&self, base_url: impl AsRef<str>) -> String {
 		let summary = self.summary.as_deref().unwrap_or(&self.path);
-		format!("{}{}\t{}", base_url.as_ref(), self.path, summary)
+		format!("{}{}\t{}", self.base_url(base_url.as_ref()), self.path, summary)
+	}
+
+	/// The path of a URL below a base URL, paths are kept as they are
+	pub fn relative_path<'a>(base_url: &str, url: &'a str) -> Cow<'a, str> {
+		let rest = url.strip_prefix(base_url.trim_end_matches('/'));
+		if let Some(rest) = rest.filter(|rest| rest.is_empty() || rest.starts_with(['/', '?', '#'])) {
+			return Cow::Borrowed(rest);
+		}
+		let Ok(parsed) = Url::parse(url) else {
+			return Cow::Borrowed(url);
+		};
+		let base_path = Url::parse(base_url).map(|base| base.path().trim_end_matches('/').to_string());
+		let base_path = base_path.unwrap_or_default();
+		let path = parsed.path();
+		Cow::Owned(path.strip_prefix(base_path.as_str()).unwrap_or(path).to_string())
+	}
+
+	/// The values that a typed path or URL matching the path gives its
+	/// `:param` segments, templated segments of the typed path give none
+	pub fn path_values(&self, base_url: &str, typed: &str) -> Vec<(String, String)> {
+		let typed = Self::relative_path(base_url, typed);
+		let typed = typed.split(['?', '#']).next().unwrap_or_default();
+		let segments = self.path.split('/').zip(typed.split('/'));
+		let values = segments.filter_map(|(segment, value)| {
+			let name = segment.strip_prefix(':')?;
+			let templated = value.starts_with(':') || (value.starts_with('{') && value.ends_with('}'));
+			(!templated && !value.is_empty()).then(|| (name.to_string(), value.to_string()))
+		});
+		values.collect()
 	}
 
 	/// Convert OpenAPI path format to our path format
@@ -41,7 +356,7 @@ impl EndPoint {
 	/// Examples:
 	/// - /users/{id} -> /users/:id
 	/// - /users/{userId}/posts/{postId} -> /users/:userId/posts/:postId
-	fn convert_path_format(path: &str) -> String {
+	pub fn convert_path_format(path: &str) -> String {
 		let mut result = String::with_capacity(path.len());
 		let mut chars = path.chars().peekable();
 		
@@ -68,21 +383,153 @@ impl EndPoint {
 }
 
 impl EndPoints {
-	pub fn filter(&self, path: impl AsRef<str>) -> Vec<&EndPoint> {
-		self.0.iter().filter(|&endpoint| endpoint.path.contains(path.as_ref())).collect()
+	fn new(endpoints: Vec<EndPoint>) -> Self {
+		Self { endpoints, webhooks: Vec::new(), index: OnceLock::new() }
+	}
+
+	/// The endpoints whose path matches a filter, text filters match the
+	/// paths that contain the text
+	pub fn filter(&self, filter: impl Into<PathFilter>) -> Vec<&EndPoint> {
+		let filter = filter.into();
+		self.endpoints.iter().filter(|&endpoint| filter.matches(&endpoint.path)).collect()
 	}
 
 	pub fn find(&self, path: impl AsRef<str>) -> Option<&EndPoint> {
-		self.0.iter().find(|e| e.path == path.as_ref())
+		self.endpoints.iter().find(|e| e.path == path.as_ref())
+	}
+
+	/// Find the endpoints a typed path such as `/users/42/posts` or
+	/// `/users/{id}/posts` matches as a whole, the paths with the most literal
+	/// segments first
+	///
+	/// A query string or fragment of the typed path is ignored.
+	pub fn find_matching(&self, path: impl AsRef<str>) -> Vec<&EndPoint> {
+		let path = path.as_ref().split(['?', '#']).next().unwrap_or_default();
+		let path = EndPoint::convert_path_format(path);
+		let path = if path.len() > 1 { path.trim_end_matches('/') } else { &path };
+		self.index().matches(path).into_iter().map(|i| &self.endpoints[i]).collect()
 	}
 
-	pub fn all(&self) -> Vec<&EndPoint> {
-		self.0.iter().collect()
+	/// Find the operations of the path that a typed path or a URL pasted from
+	/// the API matches best, see [`EndPoints::find_matching`]
+	pub fn find_operations(&self, base_url: &str, path: &str) -> Vec<&EndPoint> {
+		let matched = self.find_matching(EndPoint::relative_path(base_url, path));
+		let best = matched.first().map(|ep| ep.path.clone());
+		matched.into_iter().filter(|ep| Some(&ep.path) == best.as_ref()).collect()
 	}
 
+	/// Find the operation of a method, compared case-insensitively, on the
+	/// path that a typed path or URL matches best
+	pub fn find_method(&self, base_url: &str, method: &str, path: &str) -> Option<&EndPoint> {
+		let eps = self.find_operations(base_url, path);
+		eps.into_iter().find(|ep| ep.method.to_string().eq_ignore_ascii_case(method))
+	}
+
+	pub fn all(&self) -> Vec<&EndPoint> { self.endpoints.iter().collect() }
+
+	/// The webhooks of the API, which the API calls rather than serves
+	pub fn webhooks(&self) -> Vec<&EndPoint> { self.webhooks.iter().collect() }
+
+	/// The callbacks of all operations, along with their operation
+	pub fn callbacks(&self) -> Vec<(&EndPoint, &Callback)> {
+		let callbacks = self.endpoints.iter().flat_map(|ep| ep.callbacks.iter().map(move |c| (ep, c)));
+		callbacks.collect()
+	}
+
+	/// Find the request of a callback by name, falling back to a
+	/// case-insensitive match
+	pub fn find_callback(&self, name: &str) -> Option<&EndPoint> {
+		let callbacks = self.callbacks();
+		let find = |exact: bool| {
+			callbacks.iter().find(|(_, callback)| {
+				if exact { callback.name == name } else { callback.name.eq_ignore_ascii_case(name) }
+			})
+		};
+		find(true).or_else(|| find(false)).map(|(_, callback)| &callback.endpoint)
+	}
+
+	/// Find a webhook by name, falling back to a case-insensitive match
+	pub fn find_webhook(&self, name: &str) -> Option<&EndPoint> {
+		let webhooks = || self.webhooks.iter();
+		webhooks()
+			.find(|webhook| webhook.path == name)
+			.or_else(|| webhooks().find(|webhook| webhook.path.eq_ignore_ascii_case(name)))
+	}
+
+	/// The base URLs of the endpoints served from servers of their own
+	pub fn own_base_urls(&self, api_base_url: &str) -> Vec<String> {
+		let mut base_urls: Vec<String> = Vec::new();
+		for endpoint in self.endpoints.iter().filter(|endpoint| endpoint.server.is_some()) {
+			let base_url = endpoint.base_url(api_base_url);
+			if base_url != api_base_url && !base_urls.iter().any(|seen| *seen == base_url) {
+				base_urls.push(base_url.into_owned());
+			}
+		}
+		base_urls
+	}
+
+	/// Find the endpoint of an operationId, falling back to a
+	/// case-insensitive match
+	pub fn find_operation(&self, operation_id: &str) -> Option<&EndPoint> {
+		let has_id = |ep: &&EndPoint, exact: bool| {
+			ep.operation_id.as_deref().is_some_and(|id| {
+				if exact { id == operation_id } else { id.eq_ignore_ascii_case(operation_id) }
+			})
+		};
+		self
+			.endpoints
+			.iter()
+			.find(|ep| has_id(ep, true))
+			.or_else(|| self.endpoints.iter().find(|ep| has_id(ep, false)))
+	}
+
+	/// Get the endpoints whose path starts with `path`, which may end in a
+	/// partially typed segment and have values in place of `:param` segments
+	pub fn with_prefix(&self, path: impl AsRef<str>) -> Vec<&EndPoint> {
+		self.index().prefix(path.as_ref()).into_iter().map(|i| &self.endpoints[i]).collect()
+	}
+
+	/// The path index, built from the endpoints if none was loaded
+	pub fn index(&self) -> &PathIndex {
+		self.index.get_or_init(|| PathIndex::new(self.endpoints.iter().map(|ep| ep.path.as_str())))
+	}
+
+	/// Use an index loaded from the cache, unless it indexes another number of
+	/// endpoints
+	pub fn set_index(&self, index: PathIndex) {
+		if index.len() == self.endpoints.len() {
+			let _ = self.index.set(index);
+		}
+	}
+
+	/// Get the endpoints grouped under a tag
+	pub fn with_tag(&self, tag: &str) -> Vec<&EndPoint> {
+		self.endpoints.iter().filter(|endpoint| endpoint.has_tag(tag)).collect()
+	}
+
+	/// Get all tags with the number of endpoints grouped under each, in the
+	/// order they first appear
+	pub fn tags(&self) -> Vec<(&str, usize)> {
+		let mut tags: Vec<(&str, usize)> = Vec::new();
+		for tag in self.endpoints.iter().flat_map(|endpoint| &endpoint.tags) {
+			match tags.iter_mut().find(|(name, _)| name == tag) {
+				Some((_, count)) => *count += 1,
+				None => tags.push((tag, 1)),
+			}
+		}
+		tags
+	}
+
+	/// Parse endpoints from an OpenAPI 3.0/3.1 or Swagger 2 document in JSON or
+	/// YAML
 	pub fn try_from_openapi(data: impl AsRef<str>) -> Result<Self> {
-		let openapi: OpenAPI = serde_json::from_str(data.as_ref())?;
-		Ok(EndPoints::from(openapi))
+		Self::try_from_openapi_with(data, &BodyOptions::default())
+	}
+
+	/// Parse endpoints like [`EndPoints::try_from_openapi`], taking the body
+	/// params from the preferred media type of each request body
+	pub fn try_from_openapi_with(data: impl AsRef<str>, body: &BodyOptions) -> Result<Self> {
+		Ok(EndPoints::from_openapi(&document::parse_endpoints(data.as_ref())?, body))
 	}
 
 	/// Try to parse endpoints from a JSON file, returning Result
@@ -91,7 +538,7 @@ impl EndPoints {
 		let data = std::fs::read_to_string(path)
 			.with_context(|| format!("Failed to read endpoints file: {}", path.display()))?;
 
-		serde_json::from_str(&data)
+		json::from_str(&data)
 			.with_context(|| format!("Failed to parse endpoints JSON from file: {}", path.display()))
 	}
 
@@ -102,7 +549,11 @@ impl EndPoints {
 }
 
 impl From<OpenAPI> for EndPoints {
-	fn from(api: OpenAPI) -> Self {
+	fn from(api: OpenAPI) -> Self { Self::from_openapi(&api, &BodyOptions::default()) }
+}
+
+impl EndPoints {
+	fn from_openapi(api: &OpenAPI, body: &BodyOptions) -> Self {
 		use ReferenceOr::*;
 		let mut endpoints = vec![];
 
@@ -116,60 +567,157 @@ impl From<OpenAPI> for EndPoints {
 					continue;
 				}
 			};
-
 			// Convert path format from OpenAPI to our format
 			let converted_path = EndPoint::convert_path_format(path_str);
 			debug!("Converted path format: {} -> {}", path_str, converted_path);
+			Self::extract_path_item(&converted_path, path, api, body, &mut endpoints);
+		}
 
-			let common_params = Self::extract_parameters(&path.parameters, &api);
-			debug!("Found {} common parameters for path: {}", common_params.len(), path_str);
-
-			let methods = vec![
-				(Method::Get, &path.get),
-				(Method::Post, &path.post),
-				(Method::Put, &path.put),
-				(Method::Delete, &path.delete),
-				(Method::Patch, &path.patch),
-				(Method::Head, &path.head),
-				(Method::Options, &path.options),
-			];
-
-			for (method_ty, op_opt) in methods {
-				if op_opt.is_none() {
-					continue;
-				}
-				let op = op_opt.as_ref().unwrap();
-				debug!("Processing {} {} operation", method_ty, path_str);
+		let mut webhooks = vec![];
+		for (name, item) in document::webhooks(api) {
+			debug!("Processing webhook: {}", name);
+			Self::extract_path_item(&name, &item, api, body, &mut webhooks);
+		}
 
-				let mut params = common_params.clone();
+		info!("Successfully parsed {} endpoints and {} webhooks", endpoints.len(), webhooks.len());
+		EndPoints { webhooks, ..EndPoints::new(endpoints) }
+	}
 
-				// Add operation-level parameters
-				let op_params = Self::extract_parameters(&op.parameters, &api);
-				debug!("Found {} operation parameters", op_params.len());
-				params.extend(op_params);
+	/// Add the endpoints of the operations of a path item under `path_str`,
+	/// a path in our format, the name of a webhook or the URL expression of a
+	/// callback
+	fn extract_path_item(
+		path_str: &str,
+		path: &PathItem,
+		api: &OpenAPI,
+		body: &BodyOptions,
+		endpoints: &mut Vec<EndPoint>,
+	) {
+		if extensions::is_hidden(|name| path.extensions.get(name)) {
+			debug!("Skipping hidden path: {}", path_str);
+			return;
+		}
 
-				// Add request body parameters
-				if let Some(request_body) = &op.request_body {
-					let body_params = Self::extract_request_body_parameters(request_body, &api);
-					debug!("Found {} request body parameters", body_params.len());
-					params.extend(body_params);
-				}
+		let common_params = Self::extract_parameters(&path.parameters, api);
+		debug!("Found {} common parameters for path: {}", common_params.len(), path_str);
 
-				endpoints.push(EndPoint {
-					method: method_ty,
-					path: converted_path.clone(),
-					summary: op.summary.clone(),
-					params,
-				});
+		let methods = vec![
+			(Method::Get, &path.get),
+			(Method::Post, &path.post),
+			(Method::Put, &path.put),
+			(Method::Delete, &path.delete),
+			(Method::Patch, &path.patch),
+			(Method::Head, &path.head),
+			(Method::Options, &path.options),
+			(Method::Trace, &path.trace),
+		];
+
+		for (method_ty, op_opt) in methods {
+			if op_opt.is_none() {
+				continue;
+			}
+			let op = op_opt.as_ref().unwrap();
+			if extensions::is_hidden(|name| op.extensions.get(name)) {
+				debug!("Skipping hidden {} {} operation", method_ty, path_str);
+				continue;
+			}
+			debug!("Processing {} {} operation", method_ty, path_str);
+
+			let mut params = common_params.clone();
+
+			// Add operation-level parameters, which override path-level
+			// parameters with the same name and location
+			let op_params = Self::extract_parameters(&op.parameters, api);
+			debug!("Found {} operation parameters", op_params.len());
+			params.retain(|common| {
+				!op_params.iter().any(|op| op.name == common.name && op.source == common.source)
+			});
+			params.extend(op_params);
+
+			// Add request body parameters
+			if let Some(request_body) = &op.request_body {
+				let body_params = Self::extract_request_body_parameters(request_body, api, body);
+				debug!("Found {} request body parameters", body_params.len());
+				params.extend(body_params);
 			}
+
+			// Suggest the default headers of the path and the operation
+			let default_headers = extensions::default_headers(|name| path.extensions.get(name))
+				.into_iter()
+				.chain(extensions::default_headers(|name| op.extensions.get(name)));
+			for (name, value) in default_headers {
+				Self::set_default_header(&mut params, name, value);
+			}
+
+			let response_fields = Self::extract_response_fields(&op.responses, api);
+			debug!("Found {} response fields", response_fields.len());
+
+			endpoints.push(EndPoint {
+				method: method_ty,
+				path: path_str.to_string(),
+				summary: op.summary.clone(),
+				description: op.description.clone(),
+				operation_id: op.operation_id.clone(),
+				params,
+				tags: op.tags.clone(),
+				response_fields,
+				responses: Response::extract(&op.responses, api),
+				deprecated: op.deprecated,
+				security: security::requirements(op, api),
+				server: op
+					.servers
+					.first()
+					.or(path.servers.first())
+					.map(|server| document::fill_variables(server, &BTreeMap::new())),
+				callbacks: Self::extract_callbacks(op, api, body),
+			});
 		}
+	}
 
-		info!("Successfully parsed {} endpoints", endpoints.len());
-		EndPoints(endpoints)
+	/// Get the callbacks of an operation, their URL expressions in place of a
+	/// path
+	fn extract_callbacks(
+		op: &openapiv3::Operation,
+		api: &OpenAPI,
+		body: &BodyOptions,
+	) -> Vec<Callback> {
+		let mut callbacks = Vec::new();
+		for (name, callback) in &op.callbacks {
+			for (expression, item) in callback {
+				debug!("Processing callback {}: {}", name, expression);
+				let mut endpoints = Vec::new();
+				Self::extract_path_item(expression, item, api, body, &mut endpoints);
+				callbacks
+					.extend(endpoints.into_iter().map(|endpoint| Callback { name: name.clone(), endpoint }));
+			}
+		}
+		callbacks
+	}
+
+	/// Set the default of a header param, adding the param if the operation
+	/// does not declare it
+	fn set_default_header(params: &mut Vec<Param>, name: String, value: String) {
+		let declared = params
+			.iter_mut()
+			.find(|param| param.source == ParamSource::Header && param.name.eq_ignore_ascii_case(&name));
+		match declared {
+			Some(param) => param.default = Some(value),
+			None => params.push(Param {
+				name,
+				required: false,
+				source: ParamSource::Header,
+				description: None,
+				default: Some(value),
+				examples: Vec::new(),
+				enum_values: Vec::new(),
+				schema_type: None,
+				pattern: None,
+				array: None,
+				deprecated: false,
+			}),
+		}
 	}
-}
 
-impl EndPoints {
 	fn extract_parameters(
 		parameters: &[ReferenceOr<openapiv3::Parameter>],
 		spec: &OpenAPI,
@@ -182,7 +730,7 @@ impl EndPoints {
 		spec: &OpenAPI,
 	) -> Option<Param> {
 		match parameter {
-			ReferenceOr::Item(param) => Param::try_from(param).ok(),
+			ReferenceOr::Item(param) => Param::try_from_parameter(param, spec).ok(),
 			ReferenceOr::Reference { reference } => {
 				debug!("Extracting referenced parameter: {}", reference);
 				Self::extract_referenced_parameter(reference, spec)
@@ -191,23 +739,31 @@ impl EndPoints {
 	}
 
 	fn extract_referenced_parameter(reference: &str, spec: &OpenAPI) -> Option<Param> {
+		if reference.starts_with("#/components/parameters/") {
+			let parameter = resolve_parameter_reference(reference, spec).ok()?;
+			return Param::try_from_parameter(parameter, spec).ok();
+		}
 		let schema = resolve_schema_reference(reference, spec).ok()?;
-		let params = Param::try_from_schema(schema).ok()?;
+		let params = Param::try_from_schema(schema, spec).ok()?;
 		params.into_iter().next()
 	}
 
-	fn extract_schema_parameters(schema: &ReferenceOr<Schema>, spec: &OpenAPI) -> Vec<Param> {
+	fn extract_schema_parameters(
+		schema: &ReferenceOr<Schema>,
+		spec: &OpenAPI,
+		max_depth: usize,
+	) -> Vec<Param> {
 		match schema {
 			ReferenceOr::Item(schema) => {
 				debug!("Processing direct schema");
-				Param::try_from_schema(schema).unwrap_or_default()
+				Param::try_from_schema_with(schema, spec, max_depth).unwrap_or_default()
 			}
 			ReferenceOr::Reference { reference } => {
 				debug!("Resolving schema reference: {}", reference);
 				match resolve_schema_reference(reference, spec) {
 					Ok(resolved_schema) => {
 						debug!("Successfully resolved schema reference");
-						Param::try_from_schema(resolved_schema).unwrap_or_default()
+						Param::try_from_schema_with(resolved_schema, spec, max_depth).unwrap_or_default()
 					}
 					Err(e) => {
 						warn!("Failed to resolve schema reference: {}", e);
@@ -221,22 +777,124 @@ impl EndPoints {
 	fn extract_request_body_parameters(
 		request_body: &ReferenceOr<openapiv3::RequestBody>,
 		spec: &OpenAPI,
+		options: &BodyOptions,
 	) -> Vec<Param> {
-		match request_body {
-			ReferenceOr::Item(body) => {
-				if let Some(media_type) = body.content.get("application/json") {
-					if let Some(schema) = &media_type.schema {
-						debug!("Found request body schema");
-						return Self::extract_schema_parameters(schema, spec);
+		let body = match request_body {
+			ReferenceOr::Item(body) => body,
+			ReferenceOr::Reference { reference } => {
+				debug!("Resolving request body reference: {}", reference);
+				match resolve_request_body_reference(reference, spec) {
+					Ok(body) => body,
+					Err(e) => {
+						warn!("Failed to resolve request body reference: {}", e);
+						return Vec::new();
 					}
 				}
+			}
+		};
+		let schemas = body
+			.content
+			.iter()
+			.filter_map(|(media_type, content)| Some((media_type, content.schema.as_ref()?)));
+		match options.choose(schemas) {
+			Some((BodyKind::Json, schema)) => {
+				debug!("Found request body schema");
+				Self::extract_schema_parameters(schema, spec, options.max_depth)
+			}
+			// Form fields cannot be nested, only top level properties are kept
+			Some((BodyKind::Form, schema)) => {
+				debug!("Found form request body schema");
+				let params = Self::extract_schema_parameters(schema, spec, options.max_depth).into_iter();
+				params.filter(|param| param.nesting() == 0).map(Param::into_form).collect()
+			}
+			None => {
 				debug!("No request body schema found");
 				Vec::new()
 			}
-			ReferenceOr::Reference { .. } => {
-				warn!("Request body is a reference, which is not supported");
-				Vec::new()
+		}
+	}
+}
+
+impl EndPoints {
+	/// Collect the property names of successful JSON responses
+	fn extract_response_fields(responses: &openapiv3::Responses, spec: &OpenAPI) -> Vec<String> {
+		let mut fields = Vec::new();
+		let successful = responses.responses.iter().filter(|(code, _)| match code {
+			StatusCode::Code(code) => (200..300).contains(code),
+			StatusCode::Range(range) => *range == 2,
+		});
+		for (code, response) in successful {
+			let response = match response {
+				ReferenceOr::Item(response) => response,
+				ReferenceOr::Reference { reference } => match resolve_response_reference(reference, spec) {
+					Ok(response) => response,
+					Err(e) => {
+						debug!("Skipping {} response: {:#}", code, e);
+						continue;
+					}
+				},
+			};
+			let schemas = response
+				.content
+				.iter()
+				.filter(|(content_type, _)| content_type.contains("json"))
+				.filter_map(|(_, media_type)| media_type.schema.as_ref());
+			for schema in schemas {
+				if let Some(schema) = Self::resolve_schema(schema, spec) {
+					Self::collect_schema_fields(schema, "", spec, RESPONSE_FIELD_DEPTH, &mut fields);
+				}
+			}
+		}
+		fields
+	}
+
+	fn collect_schema_fields(
+		schema: &Schema,
+		prefix: &str,
+		spec: &OpenAPI,
+		depth: usize,
+		fields: &mut Vec<String>,
+	) {
+		if depth == 0 {
+			return;
+		}
+		match &schema.schema_kind {
+			SchemaKind::Type(Type::Object(object_type)) => {
+				for (name, property) in &object_type.properties {
+					let field = format!("{prefix}{name}");
+					if !fields.contains(&field) {
+						fields.push(field.clone());
+					}
+					if let Some(property) = Self::resolve_boxed_schema(property, spec) {
+						Self::collect_schema_fields(property, &format!("{field}."), spec, depth - 1, fields);
+					}
+				}
 			}
+			SchemaKind::Type(Type::Array(array_type)) => {
+				let items =
+					array_type.items.as_ref().and_then(|items| Self::resolve_boxed_schema(items, spec));
+				if let Some(items) = items {
+					Self::collect_schema_fields(items, prefix, spec, depth - 1, fields);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	fn resolve_schema<'a>(schema: &'a ReferenceOr<Schema>, spec: &'a OpenAPI) -> Option<&'a Schema> {
+		match schema {
+			ReferenceOr::Item(schema) => Some(schema),
+			ReferenceOr::Reference { reference } => resolve_schema_reference(reference, spec).ok(),
+		}
+	}
+
+	fn resolve_boxed_schema<'a>(
+		schema: &'a ReferenceOr<Box<Schema>>,
+		spec: &'a OpenAPI,
+	) -> Option<&'a Schema> {
+		match schema {
+			ReferenceOr::Item(schema) => Some(schema),
+			ReferenceOr::Reference { reference } => resolve_schema_reference(reference, spec).ok(),
 		}
 	}
 }
@@ -244,6 +902,7 @@ impl EndPoints {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::openapi::param::{ArrayStyle, ParamSource};
 
 	#[test]
 	fn test_convert_path_format() {
@@ -263,4 +922,1058 @@ mod tests {
 			assert_eq!(result, expected, "Failed to convert path: {}", input);
 		}
 	}
+
+	#[test]
+	fn test_path_values() {
+		let ep = EndPoint { path: "/users/:id/posts/:postId".to_string(), ..body_endpoint() };
+		let values = |typed| ep.path_values("https://api.example.com/v1", typed);
+		let both = vec![("id".to_string(), "42".to_string()), ("postId".to_string(), "7".to_string())];
+		assert_eq!(values("/users/42/posts/7"), both);
+		assert_eq!(values("https://api.example.com/v1/users/42/posts/7?draft=true"), both);
+		assert_eq!(values("/users/{id}/posts/7"), vec![("postId".to_string(), "7".to_string())]);
+		assert!(values("/users/:id/posts/:postId").is_empty());
+	}
+
+	fn param(name: &str, required: bool, source: ParamSource) -> Param {
+		Param {
+			name: name.to_string(),
+			required,
+			source,
+			description: None,
+			default: None,
+			examples: Vec::new(),
+			enum_values: Vec::new(),
+			schema_type: None,
+			pattern: None,
+			array: None,
+			deprecated: false,
+		}
+	}
+
+	fn body_endpoint() -> EndPoint {
+		EndPoint {
+			method:          Method::Post,
+			path:            "/users".to_string(),
+			summary:         None,
+			description:     None,
+			operation_id:    Some("createUser".to_string()),
+			params:          vec![
+				param("dryRun", false, ParamSource::Query),
+				param("name", true, ParamSource::Body),
+				param("nickname", false, ParamSource::Body),
+			],
+			tags:            vec!["users".to_string()],
+			response_fields: Vec::new(),
+			responses:       Vec::new(),
+			deprecated:      false,
+			security:        Vec::new(),
+			server:          None,
+			callbacks:       Vec::new(),
+		}
+	}
+
+	#[test]
+	fn test_matches_terms() {
+		let ep = body_endpoint();
+		let terms = |query: &str| query.split_whitespace().map(str::to_string).collect::<Vec<_>>();
+		assert!(ep.matches_terms(&terms("users")));
+		assert!(ep.matches_terms(&terms("CREATEUSER /users")));
+		assert!(!ep.matches_terms(&terms("users refund")));
+	}
+
+	#[test]
+	fn test_find_operation() {
+		let endpoints = EndPoints::new(vec![body_endpoint()]);
+		assert_eq!(endpoints.find_operation("createUser").unwrap().path, "/users");
+		assert_eq!(endpoints.find_operation("createuser").unwrap().path, "/users");
+		assert!(endpoints.find_operation("deleteUser").is_none());
+	}
+
+	#[test]
+	fn test_get_completion_params_by_body_mode() {
+		let ep = body_endpoint();
+		let names = |mode| -> Vec<String> {
+			ep.get_completion_params(mode, 2).into_iter().map(|p| p.name).collect()
+		};
+
+		assert_eq!(names(BodyParams::All), vec!["name", "dryRun", "nickname"]);
+		assert_eq!(names(BodyParams::Required), vec!["name", "dryRun"]);
+		assert_eq!(names(BodyParams::None), vec!["dryRun"]);
+	}
+
+	#[test]
+	fn test_extract_response_fields() {
+		let spec = serde_json::json!({
+			"openapi": "3.0.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": {
+				"/orders": {
+					"get": {
+						"responses": {
+							"200": {
+								"description": "ok",
+								"content": {
+									"application/json": {
+										"schema": { "type": "array", "items": { "$ref": "#/components/schemas/Order" } }
+									}
+								}
+							},
+							"404": {
+								"description": "missing",
+								"content": {
+									"application/json": {
+										"schema": { "type": "object", "properties": { "error": { "type": "string" } } }
+									}
+								}
+							}
+						}
+					}
+				}
+			},
+			"components": {
+				"schemas": {
+					"Order": {
+						"type": "object",
+						"properties": {
+							"id": { "type": "string" },
+							"customer": {
+								"type": "object",
+								"properties": { "customerId": { "type": "string" } }
+							}
+						}
+					}
+				}
+			}
+		});
+
+		let endpoints = EndPoints::try_from_openapi(spec.to_string()).unwrap();
+		let ep = endpoints.find("/orders").unwrap();
+		assert_eq!(ep.response_fields, vec!["customer", "customer.customerId", "id"]);
+		assert_eq!(ep.matching_response_fields("customerid"), vec!["customer.customerId"]);
+	}
+
+	#[test]
+	fn test_body_skeleton_contains_required_fields() {
+		let ep = body_endpoint();
+		assert_eq!(ep.body_skeleton(), serde_json::json!({ "name": null }));
+		assert_eq!(ep.fish_body_skeleton_format(), "--raw={\"name\":null}\tbody skeleton");
+	}
+
+	#[test]
+	fn test_tags() {
+		let mut other = body_endpoint();
+		other.path = "/users/:id".to_string();
+		other.tags = vec!["users".to_string(), "admin".to_string()];
+		let endpoints = EndPoints::new(vec![body_endpoint(), other]);
+
+		assert_eq!(endpoints.tags(), vec![("users", 2), ("admin", 1)]);
+		assert_eq!(endpoints.with_tag("Admin").len(), 1);
+		assert!(endpoints.with_tag("orders").is_empty());
+	}
+
+	#[test]
+	fn test_try_from_openapi_yaml() {
+		let spec = r#"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /users/{id}:
+    get:
+      summary: Get user
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: string
+            format: uuid
+      responses:
+        "200":
+          description: ok
+"#;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let ep = endpoints.find("/users/:id").unwrap();
+		assert_eq!(ep.summary.as_deref(), Some("Get user"));
+		assert_eq!(ep.params[0].name, "id");
+		assert_eq!(ep.params[0].schema_type.as_deref(), Some("string (uuid)"));
+
+		assert!(EndPoints::try_from_openapi("openapi: [").is_err());
+	}
+
+	#[test]
+	fn test_operation_params_override_path_params() {
+		let spec = r#"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /users/{id}:
+    parameters:
+      - name: id
+        in: path
+        required: true
+        schema:
+          type: string
+      - name: expand
+        in: query
+        schema:
+          type: string
+    get:
+      parameters:
+        - name: expand
+          in: query
+          required: true
+          description: Relations to expand
+          schema:
+            type: string
+        - name: expand
+          in: header
+          schema:
+            type: string
+      responses:
+        "200":
+          description: ok
+"#;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let ep = endpoints.find("/users/:id").unwrap();
+		let params: Vec<_> = ep.params.iter().map(|p| (p.httpie_param_format(), p.required)).collect();
+		assert_eq!(params, [
+			(":id=".to_string(), true),
+			("expand==".to_string(), true),
+			("expand:".to_string(), false)
+		]);
+		assert_eq!(ep.params[1].description.as_deref(), Some("Relations to expand"));
+	}
+
+	#[test]
+	fn test_referenced_parameters() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /users:
+    get:
+      parameters:
+        - $ref: "#/components/parameters/Page"
+        - $ref: "#/components/parameters/Limit"
+      responses:
+        "200":
+          description: ok
+components:
+  parameters:
+    Page:
+      name: page
+      in: query
+      schema:
+        type: integer
+    Limit:
+      name: limit
+      in: query
+      schema:
+        type: integer
+        default: 20
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let ep = endpoints.find("/users").unwrap();
+		let params: Vec<_> = ep.params.iter().map(|p| p.httpie_param_format()).collect();
+		assert_eq!(params, ["page==", "limit=="]);
+		assert_eq!(ep.params[1].default.as_deref(), Some("20"));
+	}
+
+	#[test]
+	fn test_referenced_request_body() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /users:
+    post:
+      requestBody:
+        $ref: "#/components/requestBodies/NewUser"
+      responses:
+        "201":
+          description: created
+components:
+  requestBodies:
+    NewUser:
+      content:
+        application/json:
+          schema:
+            $ref: "#/components/schemas/User"
+  schemas:
+    User:
+      type: object
+      required: [name]
+      properties:
+        name:
+          type: string
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let ep = endpoints.find("/users").unwrap();
+		let params: Vec<_> = ep.params.iter().map(|p| (p.httpie_param_format(), p.required)).collect();
+		assert_eq!(params, [("name=".to_string(), true)]);
+	}
+
+	#[test]
+	fn test_array_params() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: tag
+          in: query
+          schema:
+            type: array
+            items:
+              type: string
+              example: cat
+        - name: ids
+          in: query
+          explode: false
+          example: [1, 2, 3]
+          schema:
+            type: array
+            items:
+              type: integer
+        - name: sizes
+          in: query
+          style: pipeDelimited
+          schema:
+            type: array
+            items:
+              type: string
+      responses:
+        "200":
+          description: ok
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let ep = endpoints.find("/pets").unwrap();
+		let arrays: Vec<_> = ep.params.iter().map(|p| p.array.clone().unwrap()).collect();
+		assert_eq!(arrays, [
+			ArrayStyle::Repeated,
+			ArrayStyle::Joined { separator: ',' },
+			ArrayStyle::Joined { separator: '|' },
+		]);
+		assert!(ep.params[0].is_repeatable() && !ep.params[1].is_repeatable());
+		assert_eq!(ep.params[0].examples, ["cat"]);
+		assert_eq!(ep.params[1].examples, ["1,2,3"]);
+		assert_eq!(ep.params[1].schema_type.as_deref(), Some("array of integer"));
+		assert_eq!(ep.params[0].fish_complete_format(), "tag==\t[tag (array of string, repeatable)]");
+	}
+
+	#[test]
+	fn test_body_defaults() {
+		let spec = serde_json::json!({
+			"openapi": "3.0.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": {
+				"/pets": {
+					"post": {
+						"parameters": [
+							{ "name": "page", "in": "query", "schema": { "type": "integer", "default": 1 } }
+						],
+						"requestBody": {
+							"content": {
+								"application/json": {
+									"schema": {
+										"type": "object",
+										"properties": {
+											"active": { "type": "boolean", "default": true },
+											"code": { "type": "string", "default": "42" },
+											"tags": { "type": "array", "items": { "type": "string" }, "default": ["a"] }
+										}
+									}
+								}
+							}
+						},
+						"responses": { "200": { "description": "ok" } }
+					}
+				}
+			}
+		});
+		let endpoints = EndPoints::try_from_openapi(spec.to_string()).unwrap();
+		let ep = endpoints.find("/pets").unwrap();
+		let items: Vec<_> = ep
+			.params
+			.iter()
+			.map(|param| param.fish_complete_format().split('\t').next().unwrap().to_string())
+			.collect();
+		assert_eq!(items, ["page==1", "active:=true", "code=42", "tags:=[\"a\"]", "tags[]="]);
+		assert_eq!(ep.params[1].fish_value_complete_formats(), ["active:=true\tdefault value"]);
+	}
+
+	#[test]
+	fn test_referenced_parameter_schemas() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: limit
+          in: query
+          schema:
+            $ref: "#/components/schemas/Limit"
+        - name: sort
+          in: query
+          schema:
+            type: string
+          examples:
+            newest:
+              $ref: "#/components/examples/Newest"
+      responses:
+        "200":
+          description: ok
+components:
+  schemas:
+    Limit:
+      type: integer
+      default: 20
+      example: 50
+  examples:
+    Newest:
+      value: -created
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let ep = endpoints.find("/pets").unwrap();
+		assert_eq!(ep.params[0].schema_type.as_deref(), Some("integer"));
+		assert_eq!(ep.params[0].default.as_deref(), Some("20"));
+		assert_eq!(ep.params[0].examples, ["50"]);
+		assert_eq!(ep.params[1].examples, ["-created"]);
+	}
+
+	#[test]
+	fn test_deprecated() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: ok
+    post:
+      deprecated: true
+      parameters:
+        - name: legacy
+          in: query
+          deprecated: true
+          schema:
+            type: boolean
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              properties:
+                name:
+                  type: string
+                owner:
+                  type: object
+                  deprecated: true
+                  properties:
+                    id:
+                      type: integer
+      responses:
+        "200":
+          description: ok
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let deprecated: Vec<_> = endpoints.endpoints.iter().map(|ep| ep.deprecated).collect();
+		assert_eq!(deprecated, [false, true]);
+		let post = endpoints.endpoints.iter().find(|ep| ep.method == Method::Post).unwrap();
+		let params: Vec<_> = post.params.iter().map(|p| (p.name.as_str(), p.deprecated)).collect();
+		assert_eq!(params, [("legacy", true), ("name", false), ("owner", true), ("owner[id]", true)]);
+	}
+
+	#[test]
+	fn test_enum_values() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /pets:
+    post:
+      parameters:
+        - name: status
+          in: query
+          schema:
+            type: string
+            enum: [available, sold]
+        - name: sizes
+          in: query
+          schema:
+            type: array
+            items:
+              type: integer
+              enum: [1, 2, 3]
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              properties:
+                vaccinated:
+                  type: boolean
+                  enum: [true]
+      responses:
+        "200":
+          description: ok
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let ep = endpoints.find("/pets").unwrap();
+		let values: Vec<_> = ep.params.iter().map(|p| p.enum_values.clone()).collect();
+		assert_eq!(values, [vec!["available", "sold"], vec!["1", "2", "3"], vec!["true"]]);
+		let cached = serde_json::to_value(&ep.params[0]).unwrap();
+		assert_eq!(cached["enum"], serde_json::json!(["available", "sold"]));
+	}
+
+	#[test]
+	fn test_webhooks() {
+		let spec = r##"
+openapi: 3.1.0
+info:
+  title: Test API
+  version: 1.0.0
+webhooks:
+  newPet:
+    post:
+      summary: A pet was added
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Pet"
+      responses:
+        "200":
+          description: ok
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name:
+          type: [string, "null"]
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		assert!(endpoints.all().is_empty());
+		let webhook = endpoints.find_webhook("newpet").unwrap();
+		assert_eq!((&webhook.method, webhook.path.as_str()), (&Method::Post, "newPet"));
+		assert_eq!(webhook.params[0].name, "name");
+
+		// Caches of specs without webhooks stay a list of endpoints
+		let cached = serde_json::to_string(&endpoints).unwrap();
+		let loaded: EndPoints = json::from_str(&cached).unwrap();
+		assert_eq!(loaded.webhooks().len(), 1);
+		let plain = EndPoints::new(vec![body_endpoint()]);
+		let cached = serde_json::to_value(&plain).unwrap();
+		assert!(cached.is_array());
+		assert_eq!(serde_json::from_value::<EndPoints>(cached).unwrap().all().len(), 1);
+	}
+
+	#[test]
+	fn test_callbacks() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /subscriptions:
+    post:
+      callbacks:
+        onEvent:
+          "{$request.body#/callbackUrl}":
+            post:
+              requestBody:
+                content:
+                  application/json:
+                    schema:
+                      type: object
+                      properties:
+                        event:
+                          type: string
+              responses:
+                "200":
+                  description: ok
+      responses:
+        "201":
+          description: created
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let callbacks = endpoints.callbacks();
+		let (endpoint, callback) = callbacks[0];
+		assert_eq!((endpoint.path.as_str(), callback.name.as_str()), ("/subscriptions", "onEvent"));
+		let request = endpoints.find_callback("onevent").unwrap();
+		assert_eq!(request.path, "{$request.body#/callbackUrl}");
+		assert_eq!(request.params[0].name, "event");
+		// Callbacks are no paths of the API
+		assert_eq!(endpoints.all().len(), 1);
+	}
+
+	#[test]
+	fn test_server_overrides() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /files:
+    servers:
+      - url: https://{region}.uploads.example.com/
+        variables:
+          region:
+            default: eu
+    get:
+      responses:
+        "200":
+          description: ok
+    post:
+      servers:
+        - url: /upload
+      responses:
+        "200":
+          description: ok
+  /users:
+    get:
+      responses:
+        "200":
+          description: ok
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let base_url = "https://api.example.com/v1";
+		let files = endpoints.filter("/files");
+		assert_eq!(files[0].base_url(base_url), "https://eu.uploads.example.com");
+		assert_eq!(files[1].base_url(base_url), "https://api.example.com/upload");
+		assert_eq!(endpoints.find("/users").unwrap().base_url(base_url), base_url);
+		assert_eq!(
+			files[0].fish_complete_format(base_url),
+			"https://eu.uploads.example.com/files\t/files"
+		);
+		assert_eq!(endpoints.own_base_urls(base_url), [
+			"https://eu.uploads.example.com",
+			"https://api.example.com/upload"
+		]);
+	}
+
+	#[test]
+	fn test_vendor_extensions() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /pets:
+    x-default-header:
+      X-Client: httpie
+    get:
+      x-default-header:
+        Accept-Version: 2
+      parameters:
+        - name: status
+          in: query
+          example: sold
+          x-example-values: [available, sold]
+          schema:
+            type: string
+        - name: debug
+          in: query
+          x-httpie-oapi-hidden: true
+          schema:
+            type: boolean
+        - name: accept-version
+          in: header
+          schema:
+            type: string
+      responses:
+        "200":
+          description: ok
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              properties:
+                name:
+                  type: string
+                  x-example-values: Rex
+                internalId:
+                  type: string
+                  x-httpie-oapi-hidden: true
+      responses:
+        "200":
+          description: ok
+  /internal:
+    x-httpie-oapi-hidden: true
+    get:
+      responses:
+        "200":
+          description: ok
+  /admin:
+    get:
+      x-httpie-oapi-hidden: true
+      responses:
+        "200":
+          description: ok
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		assert!(endpoints.find("/internal").is_none() && endpoints.find("/admin").is_none());
+		let pets = endpoints.filter("/pets");
+		let params: Vec<_> =
+			pets[0].params.iter().map(|p| (p.name.as_str(), p.default.as_deref())).collect();
+		assert_eq!(params, [
+			("status", None),
+			("accept-version", Some("2")),
+			("X-Client", Some("httpie"))
+		]);
+		assert_eq!(pets[0].params[0].examples, ["available", "sold"]);
+		let names: Vec<_> = pets[1].params.iter().map(|p| p.name.as_str()).collect();
+		assert_eq!(names, ["name", "X-Client"]);
+		assert_eq!(pets[1].params[0].examples, ["Rex"]);
+	}
+
+	#[test]
+	fn test_form_body_params() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /login:
+    post:
+      requestBody:
+        content:
+          application/x-www-form-urlencoded:
+            schema:
+              type: object
+              required: [username]
+              properties:
+                username:
+                  type: string
+      responses:
+        "200":
+          description: ok
+  /avatars:
+    post:
+      requestBody:
+        content:
+          multipart/form-data:
+            schema:
+              type: object
+              properties:
+                caption:
+                  type: string
+                file:
+                  type: string
+                  format: binary
+      responses:
+        "201":
+          description: created
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let login = endpoints.find("/login").unwrap();
+		assert_eq!(login.params[0].source, ParamSource::Form);
+		assert_eq!(login.form_flag(), Some("--form"));
+		assert!(!login.has_json_body());
+		let avatars = endpoints.find("/avatars").unwrap();
+		let params: Vec<_> = avatars.params.iter().map(|p| p.httpie_param_format()).collect();
+		assert_eq!(params, ["caption=", "file@"]);
+		assert_eq!(avatars.form_flag(), Some("--multipart"));
+	}
+
+	#[test]
+	fn test_body_media_type_preference() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /articles:
+    post:
+      requestBody:
+        content:
+          application/vnd.api+json:
+            schema:
+              type: object
+              properties:
+                title:
+                  type: string
+      responses:
+        "201":
+          description: created
+  /pets:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              properties:
+                name:
+                  type: string
+          application/x-www-form-urlencoded:
+            schema:
+              type: object
+              properties:
+                name:
+                  type: string
+      responses:
+        "201":
+          description: created
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let articles = endpoints.find("/articles").unwrap();
+		assert_eq!(articles.params[0].name, "title");
+		assert_eq!(articles.params[0].source, ParamSource::Body);
+		assert_eq!(endpoints.find("/pets").unwrap().params[0].source, ParamSource::Body);
+
+		let forms_first =
+			BodyOptions { content_types: vec!["form".into(), "json".into()], ..BodyOptions::default() };
+		let endpoints = EndPoints::try_from_openapi_with(spec, &forms_first).unwrap();
+		assert_eq!(endpoints.find("/pets").unwrap().params[0].source, ParamSource::Form);
+	}
+
+	#[test]
+	fn test_nested_body_params() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /users:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              required: [address]
+              properties:
+                address:
+                  $ref: "#/components/schemas/Address"
+                tags:
+                  type: array
+                  items:
+                    type: string
+      responses:
+        "201":
+          description: created
+components:
+  schemas:
+    Address:
+      type: object
+      required: [city]
+      properties:
+        city:
+          type: string
+        geo:
+          type: object
+          properties:
+            lat:
+              type: number
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let ep = endpoints.find("/users").unwrap();
+		let names = |depth| -> Vec<String> {
+			let params = ep.get_completion_params(BodyParams::All, depth).into_iter();
+			params.map(|p| p.httpie_param_format()).collect()
+		};
+		assert_eq!(names(0), ["address=", "tags="]);
+		assert_eq!(names(1), ["address[city]=", "address[geo]=", "tags[]="]);
+		assert_eq!(names(2), ["address[city]=", "address[geo][lat]=", "tags[]="]);
+		let required: Vec<_> =
+			ep.params.iter().filter(|p| p.required).map(|p| p.name.as_str()).collect();
+		assert_eq!(required, ["address", "address[city]"]);
+		assert_eq!(ep.body_skeleton(), serde_json::json!({ "address": null }));
+		assert_eq!(ep.example_body(false), serde_json::json!({ "address": { "city": "string" } }));
+		assert_eq!(
+			ep.example_body(true),
+			serde_json::json!({
+				"address": { "city": "string", "geo": { "lat": 1 } },
+				"tags": ["string"]
+			})
+		);
+	}
+
+	#[test]
+	fn test_validate_body() {
+		let spec = serde_json::json!({
+			"openapi": "3.0.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": {
+				"/pets": {
+					"post": {
+						"requestBody": {
+							"content": {
+								"application/json": {
+									"schema": {
+										"type": "object",
+										"required": ["name", "owner"],
+										"properties": {
+											"name": { "type": "string" },
+											"age": { "type": "integer" },
+											"status": { "type": "string", "enum": ["available", "sold"] },
+											"owner": {
+												"type": "object",
+												"required": ["id"],
+												"properties": { "id": { "type": "integer" } }
+											},
+											"tags": { "type": "array", "items": { "type": "string" } }
+										}
+									}
+								}
+							}
+						},
+						"responses": { "201": { "description": "created" } }
+					}
+				}
+			}
+		});
+		let endpoints = EndPoints::try_from_openapi(spec.to_string()).unwrap();
+		let ep = endpoints.find("/pets").unwrap();
+		assert!(ep.validate_body(&ep.example_body(true)).is_empty());
+		let body = serde_json::json!({
+			"age": "3",
+			"status": "lost",
+			"owner": {},
+			"tags": ["a", 1]
+		});
+		assert_eq!(ep.validate_body(&body), [
+			"age: expected integer, got \"3\"",
+			"name: required field is missing",
+			"owner[id]: required field is missing",
+			"status: \"lost\" is not one of available, sold",
+			"tags[]: expected string, got 1",
+		]);
+		assert_eq!(ep.validate_body(&serde_json::json!([])), ["The body must be a JSON object"]);
+	}
+
+	#[test]
+	fn test_insert_nested() {
+		let mut body = serde_json::Value::Null;
+		insert_nested(&mut body, "address[city]", "Paris".into());
+		insert_nested(&mut body, "address[zip]", 75001.into());
+		insert_nested(&mut body, "tags[]", "a".into());
+		insert_nested(&mut body, "tags[]", "b".into());
+		insert_nested(&mut body, "items[][id]", 1.into());
+		insert_nested(&mut body, "items[][name]", "one".into());
+		insert_nested(&mut body, "items[][id]", 2.into());
+		assert_eq!(
+			body,
+			serde_json::json!({
+				"address": { "city": "Paris", "zip": 75001 },
+				"tags": ["a", "b"],
+				"items": [{ "id": 1, "name": "one" }, { "id": 2 }]
+			})
+		);
+	}
+
+	#[test]
+	fn test_composed_body_schemas() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /users:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              allOf:
+                - $ref: "#/components/schemas/Base"
+                - type: object
+                  required: [name]
+                  properties:
+                    name:
+                      type: string
+                - required: [email]
+      responses:
+        "201":
+          description: created
+  /pets:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              oneOf:
+                - $ref: "#/components/schemas/Cat"
+                - $ref: "#/components/schemas/Dog"
+      responses:
+        "201":
+          description: created
+components:
+  schemas:
+    Base:
+      type: object
+      required: [id]
+      properties:
+        id:
+          type: integer
+        email:
+          type: string
+    Cat:
+      type: object
+      required: [name, lives]
+      properties:
+        name:
+          type: string
+        lives:
+          type: integer
+          description: Remaining lives
+    Dog:
+      type: object
+      required: [name]
+      properties:
+        name:
+          type: string
+        breed:
+          type: string
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let summary = |path| {
+			let ep = endpoints.find(path).unwrap();
+			let params = ep.params.iter().map(|p| (p.name.clone(), p.required, p.description.clone()));
+			params.collect::<Vec<_>>()
+		};
+		assert_eq!(summary("/users"), [
+			("email".to_string(), true, None),
+			("id".to_string(), true, None),
+			("name".to_string(), true, None)
+		]);
+		assert_eq!(summary("/pets"), [
+			("lives".to_string(), false, Some("Remaining lives (Cat only)".to_string())),
+			("name".to_string(), true, None),
+			("breed".to_string(), false, Some("breed (Dog only)".to_string())),
+		]);
+	}
 }
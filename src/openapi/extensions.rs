@@ -0,0 +1,42 @@
+use serde_json::Value;
+
+/// Hides an operation, path, parameter or property from completion when `true`
+const HIDDEN: &str = "x-httpie-oapi-hidden";
+
+/// Values offered for a parameter or property, a list or a single value
+const EXAMPLE_VALUES: &str = "x-example-values";
+
+/// Headers to suggest with an operation or the operations of a path, as a map
+/// of header names to values
+const DEFAULT_HEADER: &str = "x-default-header";
+
+/// Whether the extensions of an object hide it from completion
+pub fn is_hidden<'a>(get: impl Fn(&str) -> Option<&'a Value>) -> bool {
+	get(HIDDEN) == Some(&Value::Bool(true))
+}
+
+/// The values of `x-example-values`, in the order of the spec
+pub fn example_values<'a>(get: impl Fn(&str) -> Option<&'a Value>) -> Vec<&'a Value> {
+	match get(EXAMPLE_VALUES) {
+		Some(Value::Array(values)) => values.iter().collect(),
+		Some(Value::Null) | None => Vec::new(),
+		Some(value) => vec![value],
+	}
+}
+
+/// The headers of `x-default-header` with their values
+pub fn default_headers<'a>(get: impl Fn(&str) -> Option<&'a Value>) -> Vec<(String, String)> {
+	let Some(Value::Object(headers)) = get(DEFAULT_HEADER) else {
+		return Vec::new();
+	};
+	headers
+		.iter()
+		.map(|(name, value)| {
+			let value = match value {
+				Value::String(value) => value.clone(),
+				value => value.to_string(),
+			};
+			(name.clone(), value)
+		})
+		.collect()
+}
@@ -0,0 +1,217 @@
+use anyhow::{Context, Result, anyhow};
+use openapiv3::{OpenAPI, PathItem, ReferenceOr};
+use serde::Deserialize;
+use tracing::warn;
+use url::Url;
+
+use super::document;
+
+/// Springdoc endpoint listing the groups of a grouped spec, relative to the
+/// api-docs URL
+const SWAGGER_CONFIG: &str = "swagger-config";
+
+#[derive(Debug, Deserialize)]
+struct SwaggerConfig {
+	#[serde(default)]
+	urls: Vec<GroupUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupUrl {
+	url: String,
+}
+
+/// Get the URL listing the groups of a Springdoc api-docs URL such as
+/// `https://example.com/v3/api-docs`
+pub fn config_url(spec_url: &Url) -> Result<Url> {
+	if spec_url.path().ends_with(SWAGGER_CONFIG) {
+		return Ok(spec_url.clone());
+	}
+	let mut url = spec_url.clone();
+	url
+		.path_segments_mut()
+		.map_err(|_| anyhow!("Invalid api-docs URL '{}'", spec_url))?
+		.pop_if_empty()
+		.push(SWAGGER_CONFIG);
+	Ok(url)
+}
+
+/// Get the spec URLs of the groups listed by a Springdoc swagger-config
+/// document, resolved against the URL it was downloaded from
+pub fn group_urls(config_json: &str, config_url: &Url) -> Result<Vec<Url>> {
+	let config: SwaggerConfig =
+		serde_json::from_str(config_json).context("Invalid swagger-config document")?;
+	if config.urls.is_empty() {
+		return Err(anyhow!("'{}' lists no spec groups", config_url));
+	}
+	config
+		.urls
+		.iter()
+		.map(|group| {
+			config_url.join(&group.url).with_context(|| format!("Invalid group URL '{}'", group.url))
+		})
+		.collect()
+}
+
+/// Merge the spec documents of all groups into a single OpenAPI document
+///
+/// The first group provides the info and servers. The operations of a path
+/// declared by several groups are merged, an operation declared twice is
+/// taken from the first group declaring it, like tags and components.
+pub fn merge(documents: &[String]) -> Result<String> {
+	let mut documents = documents.iter().map(|data| document::parse(data));
+	let mut merged = documents.next().ok_or_else(|| anyhow!("No spec groups to merge"))??;
+	for openapi in documents {
+		merge_into(&mut merged, openapi?);
+	}
+	serde_json::to_string(&merged).context("Failed to serialize merged OpenAPI document")
+}
+
+fn merge_into(merged: &mut OpenAPI, openapi: OpenAPI) {
+	for (path, item) in openapi.paths.paths {
+		match (merged.paths.paths.get_mut(&path), item) {
+			(Some(ReferenceOr::Item(target)), ReferenceOr::Item(item)) => {
+				merge_path_item(&path, target, item)
+			}
+			(Some(_), _) => {
+				warn!("Several groups declare {} by reference, keeping the one of the first group", path)
+			}
+			(None, item) => {
+				merged.paths.paths.insert(path, item);
+			}
+		}
+	}
+	for tag in openapi.tags {
+		if !merged.tags.iter().any(|t| t.name == tag.name) {
+			merged.tags.push(tag);
+		}
+	}
+	let Some(components) = openapi.components else {
+		return;
+	};
+	let target = merged.components.get_or_insert_with(Default::default);
+	for (name, schema) in components.schemas {
+		target.schemas.entry(name).or_insert(schema);
+	}
+	for (name, parameter) in components.parameters {
+		target.parameters.entry(name).or_insert(parameter);
+	}
+	for (name, body) in components.request_bodies {
+		target.request_bodies.entry(name).or_insert(body);
+	}
+	for (name, response) in components.responses {
+		target.responses.entry(name).or_insert(response);
+	}
+	for (name, header) in components.headers {
+		target.headers.entry(name).or_insert(header);
+	}
+	for (name, example) in components.examples {
+		target.examples.entry(name).or_insert(example);
+	}
+}
+
+/// Add the operations of a path of another group to the path, keeping the
+/// operations the path already has
+fn merge_path_item(path: &str, target: &mut PathItem, item: PathItem) {
+	let operations = [
+		("GET", &mut target.get, item.get),
+		("PUT", &mut target.put, item.put),
+		("POST", &mut target.post, item.post),
+		("DELETE", &mut target.delete, item.delete),
+		("OPTIONS", &mut target.options, item.options),
+		("HEAD", &mut target.head, item.head),
+		("PATCH", &mut target.patch, item.patch),
+		("TRACE", &mut target.trace, item.trace),
+	];
+	for (method, slot, operation) in operations {
+		match (slot.is_some(), operation) {
+			(_, None) => {}
+			(false, operation) => *slot = operation,
+			(true, Some(_)) => {
+				warn!("Several groups declare {} {}, keeping the one of the first group", method, path)
+			}
+		}
+	}
+	for parameter in item.parameters {
+		if !target.parameters.contains(&parameter) {
+			target.parameters.push(parameter);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_config_url() {
+		let url = Url::parse("http://localhost:8080/v3/api-docs/").unwrap();
+		assert_eq!(
+			config_url(&url).unwrap().as_str(),
+			"http://localhost:8080/v3/api-docs/swagger-config"
+		);
+		let url = Url::parse("http://localhost:8080/v3/api-docs/swagger-config").unwrap();
+		assert_eq!(config_url(&url).unwrap(), url);
+	}
+
+	#[test]
+	fn test_group_urls() {
+		let url = Url::parse("http://localhost:8080/v3/api-docs/swagger-config").unwrap();
+		let config = r#"{
+			"configUrl": "/v3/api-docs/swagger-config",
+			"urls": [
+				{ "url": "/v3/api-docs/public", "name": "public" },
+				{ "url": "/v3/api-docs/admin", "name": "admin" }
+			]
+		}"#;
+		let urls: Vec<_> = group_urls(config, &url).unwrap().into_iter().map(String::from).collect();
+		assert_eq!(urls, [
+			"http://localhost:8080/v3/api-docs/public",
+			"http://localhost:8080/v3/api-docs/admin"
+		]);
+		assert!(group_urls(r#"{ "urls": [] }"#, &url).is_err());
+	}
+
+	#[test]
+	fn test_merge() {
+		let document = |title: &str, path: &str, schema: &str| {
+			format!(
+				r#"{{
+					"openapi": "3.0.1",
+					"info": {{ "title": "{title}", "version": "1.0" }},
+					"tags": [{{ "name": "shared" }}],
+					"paths": {{ "{path}": {{ "get": {{ "responses": {{}} }} }} }},
+					"components": {{ "schemas": {{ "{schema}": {{ "type": "object" }} }} }}
+				}}"#
+			)
+		};
+		let merged =
+			merge(&[document("Public", "/pets", "Pet"), document("Admin", "/users", "User")]).unwrap();
+		let merged = document::parse(&merged).unwrap();
+		assert_eq!(merged.info.title, "Public");
+		assert_eq!(merged.paths.paths.keys().collect::<Vec<_>>(), ["/pets", "/users"]);
+		assert_eq!(merged.tags.len(), 1);
+		let schemas = &merged.components.unwrap().schemas;
+		assert!(schemas.contains_key("Pet") && schemas.contains_key("User"));
+	}
+
+	#[test]
+	fn test_merge_operations() {
+		let document = |method: &str, summary: &str| {
+			format!(
+				r#"{{
+					"openapi": "3.0.1",
+					"info": {{ "title": "Test", "version": "1.0" }},
+					"paths": {{ "/pets": {{ "{method}": {{ "summary": "{summary}", "responses": {{}} }} }} }}
+				}}"#
+			)
+		};
+		let merged =
+			merge(&[document("get", "public"), document("post", "admin"), document("get", "other")])
+				.unwrap();
+		let merged = document::parse(&merged).unwrap();
+		let item = merged.paths.paths["/pets"].as_item().unwrap();
+		assert_eq!(item.get.as_ref().unwrap().summary.as_deref(), Some("public"));
+		assert_eq!(item.post.as_ref().unwrap().summary.as_deref(), Some("admin"));
+	}
+}
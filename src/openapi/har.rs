@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value, json};
+use tracing::debug;
+use url::Url;
+
+use super::postman::{parameter, schema_of};
+
+/// Headers set by browsers or carrying session state, which are not part of
+/// the API
+const SKIPPED_HEADERS: &[&str] = &[
+	"accept",
+	"accept-encoding",
+	"accept-language",
+	"cache-control",
+	"connection",
+	"content-length",
+	"content-type",
+	"cookie",
+	"dnt",
+	"host",
+	"origin",
+	"pragma",
+	"priority",
+	"referer",
+	"te",
+	"upgrade-insecure-requests",
+	"user-agent",
+];
+
+/// Headers whose recorded values are secrets and are not kept as examples
+const SECRET_HEADERS: &[&str] = &["authorization", "proxy-authorization", "x-api-key"];
+
+/// Whether a document is an HTTP Archive
+pub fn is_har(document: &Value) -> bool {
+	document.pointer("/log/entries").is_some_and(Value::is_array)
+		&& document.pointer("/log/version").is_some()
+}
+
+/// Convert the requests recorded in an HTTP Archive to an OpenAPI 3.0
+/// document
+///
+/// Only API calls are kept: fetch and XHR requests, requests answered with
+/// JSON and requests other than GET. The most common origin among them
+/// becomes the server and requests to other origins are dropped. Path
+/// segments that look like identifiers become path params named after the
+/// segment before them, query params, headers and JSON or form bodies are
+/// kept with their recorded values as examples.
+pub fn convert(document: Value) -> Value {
+	debug!("Converting HTTP Archive to OpenAPI 3");
+	let entries: Vec<&Value> = document
+		.pointer("/log/entries")
+		.and_then(Value::as_array)
+		.into_iter()
+		.flatten()
+		.filter(|entry| is_api_call(entry))
+		.collect();
+	let requests: Vec<(&Value, Url)> = entries
+		.iter()
+		.filter_map(|entry| {
+			let request = entry.get("request")?;
+			let url = Url::parse(request.get("url")?.as_str()?).ok()?;
+			Some((request, url))
+		})
+		.collect();
+
+	let mut origins: HashMap<String, usize> = HashMap::new();
+	for (_, url) in &requests {
+		*origins.entry(url.origin().ascii_serialization()).or_default() += 1;
+	}
+	let origin =
+		origins.into_iter().max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)));
+
+	let mut paths = Map::new();
+	for (request, url) in &requests {
+		if origin.as_ref().is_some_and(|(origin, _)| url.origin().ascii_serialization() != *origin) {
+			debug!("Skipping request to another origin: {}", url);
+			continue;
+		}
+		add_request(request, url, &mut paths);
+	}
+
+	let title = document
+		.pointer("/log/pages/0/title")
+		.and_then(Value::as_str)
+		.filter(|title| !title.is_empty() && !title.contains("://"))
+		.map(str::to_string)
+		.or_else(|| {
+			let (origin, _) = origin.as_ref()?;
+			Some(Url::parse(origin).ok()?.host_str()?.to_string())
+		})
+		.unwrap_or_else(|| "Recorded traffic".to_string());
+	let mut result = Map::new();
+	result.insert("openapi".to_string(), json!("3.0.3"));
+	result.insert("info".to_string(), json!({ "title": title, "version": "1.0.0" }));
+	if let Some((origin, _)) = origin {
+		result.insert("servers".to_string(), json!([{ "url": origin }]));
+	}
+	result.insert("paths".to_string(), Value::Object(paths));
+	Value::Object(result)
+}
+
+/// Whether a recorded entry is a call to an API rather than a page or asset
+/// load
+fn is_api_call(entry: &Value) -> bool {
+	if let Some(resource_type) = entry.get("_resourceType").and_then(Value::as_str) {
+		return matches!(resource_type, "xhr" | "fetch");
+	}
+	let method = entry.pointer("/request/method").and_then(Value::as_str).unwrap_or("GET");
+	let mime_type = entry.pointer("/response/content/mimeType").and_then(Value::as_str);
+	!method.eq_ignore_ascii_case("GET") || mime_type.is_some_and(|mime| mime.contains("json"))
+}
+
+fn add_request(request: &Value, url: &Url, paths: &mut Map<String, Value>) {
+	let method = request.get("method").and_then(Value::as_str).unwrap_or("GET").to_lowercase();
+	let (path, path_params) = infer_path(url);
+
+	let mut parameters: Vec<Value> = path_params
+		.into_iter()
+		.map(|(name, example)| parameter(&name, "path", true, Some(example)))
+		.collect();
+	for (name, value) in url.query_pairs() {
+		parameters.push(parameter(&name, "query", false, Some(value.to_string())));
+	}
+	for header in request.get("headers").and_then(Value::as_array).into_iter().flatten() {
+		let Some(name) = header.get("name").and_then(Value::as_str) else {
+			continue;
+		};
+		let lowercase = name.to_lowercase();
+		if name.starts_with(':')
+			|| lowercase.starts_with("sec-")
+			|| SKIPPED_HEADERS.contains(&lowercase.as_str())
+		{
+			continue;
+		}
+		let example = header.get("value").and_then(Value::as_str).map(str::to_string);
+		let example = example.filter(|_| !SECRET_HEADERS.contains(&lowercase.as_str()));
+		parameters.push(parameter(name, "header", false, example));
+	}
+
+	let item = paths.entry(path).or_insert_with(|| json!({}));
+	let Value::Object(item) = item else {
+		return;
+	};
+	match item.get_mut(&method).and_then(|operation| operation.get_mut("parameters")) {
+		// Requests seen again add the params they use
+		Some(Value::Array(known)) => {
+			for parameter in parameters {
+				if !known.iter().any(|p| p["name"] == parameter["name"] && p["in"] == parameter["in"]) {
+					known.push(parameter);
+				}
+			}
+		}
+		_ => {
+			let mut operation = Map::new();
+			operation.insert("parameters".to_string(), Value::Array(parameters));
+			if let Some(request_body) = request.get("postData").and_then(request_body) {
+				operation.insert("requestBody".to_string(), request_body);
+			}
+			operation.insert("responses".to_string(), json!({}));
+			item.insert(method, Value::Object(operation));
+		}
+	}
+}
+
+/// Turn the path of a recorded URL into a path template, replacing segments
+/// that look like identifiers with path params named after the segment
+/// before them, e.g. `/users/42` becomes `/users/{userId}`
+fn infer_path(url: &Url) -> (String, Vec<(String, String)>) {
+	let mut path = String::new();
+	let mut params: Vec<(String, String)> = Vec::new();
+	let mut previous: Option<&str> = None;
+	for segment in url.path_segments().into_iter().flatten().filter(|s| !s.is_empty()) {
+		path.push('/');
+		if is_identifier(segment) {
+			let base = match previous {
+				Some(previous) => format!("{}Id", previous.strip_suffix('s').unwrap_or(previous)),
+				None => "id".to_string(),
+			};
+			let mut name = base.clone();
+			let mut n = 2;
+			while params.iter().any(|(used, _)| *used == name) {
+				name = format!("{base}{n}");
+				n += 1;
+			}
+			path.push_str(&format!("{{{name}}}"));
+			params.push((name, segment.to_string()));
+		} else {
+			path.push_str(segment);
+			previous = Some(segment);
+		}
+	}
+	if path.is_empty() {
+		path.push('/');
+	}
+	(path, params)
+}
+
+/// Whether a path segment is a number, a UUID or a long hex string
+fn is_identifier(segment: &str) -> bool {
+	let is_number = segment.chars().all(|c| c.is_ascii_digit());
+	let is_hex = segment.len() >= 16 && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+	is_number || is_hex
+}
+
+/// Build a request body from the recorded post data
+fn request_body(post_data: &Value) -> Option<Value> {
+	let mime_type = post_data.get("mimeType").and_then(Value::as_str).unwrap_or_default();
+	let (media_type, schema) = if mime_type.contains("json") {
+		let text = post_data.get("text").and_then(Value::as_str)?;
+		("application/json", schema_of(&serde_json::from_str(text).ok()?))
+	} else if mime_type.contains("x-www-form-urlencoded") || mime_type.contains("multipart") {
+		let properties: Map<String, Value> = post_data
+			.get("params")
+			.and_then(Value::as_array)
+			.into_iter()
+			.flatten()
+			.filter_map(|param| {
+				let name = param.get("name")?.as_str()?;
+				let mut schema = json!({ "type": "string" });
+				if param.get("fileName").is_some() {
+					schema["format"] = json!("binary");
+				} else if let Some(value) = param.get("value").and_then(Value::as_str) {
+					schema["example"] = json!(value);
+				}
+				Some((name.to_string(), schema))
+			})
+			.collect();
+		let media_type = match mime_type.contains("multipart") {
+			true => "multipart/form-data",
+			false => "application/x-www-form-urlencoded",
+		};
+		(media_type, json!({ "type": "object", "properties": properties }))
+	} else {
+		return None;
+	};
+	Some(json!({ "content": { media_type: { "schema": schema } } }))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::openapi::EndPoints;
+
+	fn entry(method: &str, url: &str, resource_type: &str) -> Value {
+		let mut entry = json!({
+			"_resourceType": resource_type,
+			"request": {
+				"method": method,
+				"url": url,
+				"headers": [
+					{ "name": ":authority", "value": "shop.example.com" },
+					{ "name": "Authorization", "value": "Bearer secret" },
+					{ "name": "X-Tenant", "value": "acme" },
+					{ "name": "User-Agent", "value": "Mozilla/5.0" }
+				],
+			}
+		});
+		if method != "GET" {
+			entry["request"]["postData"] =
+				json!({ "mimeType": "application/json", "text": "{\"quantity\": 2}" });
+		}
+		entry
+	}
+
+	#[test]
+	fn test_infer_path() {
+		let url =
+			Url::parse("https://shop.example.com/users/42/orders/0c8f4d1e-8e1b-4c59-9b1a-7b1c3e2f9a10")
+				.unwrap();
+		let (path, params) = infer_path(&url);
+		assert_eq!(path, "/users/{userId}/orders/{orderId}");
+		assert_eq!(params[0], ("userId".to_string(), "42".to_string()));
+	}
+
+	#[test]
+	fn test_convert() {
+		let har = json!({
+			"log": {
+				"version": "1.2",
+				"pages": [{ "title": "Shop" }],
+				"entries": [
+					entry("GET", "https://shop.example.com/app.js", "script"),
+					entry("GET", "https://shop.example.com/api/orders/7?expand=items", "fetch"),
+					entry("GET", "https://shop.example.com/api/orders/8?page=2", "xhr"),
+					entry("POST", "https://shop.example.com/api/orders", "fetch"),
+					entry("POST", "https://analytics.example.com/collect", "xhr")
+				]
+			}
+		});
+		assert!(is_har(&har));
+		let openapi = convert(har);
+		assert_eq!(openapi["info"]["title"], "Shop");
+		assert_eq!(openapi["servers"], json!([{ "url": "https://shop.example.com" }]));
+
+		let endpoints = EndPoints::try_from_openapi(openapi.to_string()).unwrap();
+		let paths: Vec<_> = endpoints.all().iter().map(|ep| ep.path.as_str()).collect();
+		assert_eq!(paths, ["/api/orders", "/api/orders/:orderId"]);
+
+		let get = endpoints.find("/api/orders/:orderId").unwrap();
+		let params: Vec<_> = get.params.iter().map(|p| p.httpie_param_format()).collect();
+		assert_eq!(params, [":orderId=", "expand==", "Authorization:", "X-Tenant:", "page=="]);
+		assert!(get.params[2].examples.is_empty());
+
+		let create = endpoints.find("/api/orders").unwrap();
+		assert!(create.params.iter().any(|p| p.name == "quantity"));
+	}
+}
@@ -0,0 +1,17 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+
+/// Deserialize a JSON document
+///
+/// Built with the `simd-json` feature, documents are parsed with simd-json,
+/// which is faster on the multi-megabyte specs of API gateways but needs a
+/// mutable copy of the document.
+#[cfg(feature = "simd-json")]
+pub fn from_str<T: DeserializeOwned>(data: &str) -> Result<T> {
+	let mut bytes = data.as_bytes().to_vec();
+	Ok(simd_json::serde::from_slice(&mut bytes)?)
+}
+
+/// Deserialize a JSON document
+#[cfg(not(feature = "simd-json"))]
+pub fn from_str<T: DeserializeOwned>(data: &str) -> Result<T> { Ok(serde_json::from_str(data)?) }
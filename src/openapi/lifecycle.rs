@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+use openapiv3::OpenAPI;
+use serde_json::Value;
+
+use super::{ApiSpec, document};
+
+/// Extensions holding the lifecycle of an API or a server, either as a status
+/// string or as an object with `status` and `sunset` fields
+const LIFECYCLE_EXTENSIONS: &[&str] = &["x-api-lifecycle", "x-lifecycle"];
+
+/// Extensions holding a lifecycle status string
+const STATUS_EXTENSIONS: &[&str] = &["x-api-status", "x-status"];
+
+/// Extensions holding a sunset date
+const SUNSET_EXTENSIONS: &[&str] = &["x-sunset", "x-sunset-date"];
+
+/// Statuses of servers that should no longer be used
+const RETIRED_STATUSES: &[&str] = &["deprecated", "sunset", "retired", "decommissioned"];
+
+/// Version and lifecycle of an API, as declared by its spec
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Lifecycle {
+	/// `info.version` of the spec
+	pub version: String,
+	/// Lifecycle status such as `beta` or `deprecated`
+	pub status: Option<String>,
+	/// Date after which the API is no longer served
+	pub sunset: Option<String>,
+	/// URLs of servers marked as deprecated or sunset
+	pub deprecated_servers: Vec<String>,
+}
+
+impl Lifecycle {
+	/// Read the lifecycle from `info` extensions and the servers of a document,
+	/// whose URLs take the server variables `vars`
+	pub fn from_document(openapi: &OpenAPI, spec_url: &str, vars: &BTreeMap<String, String>) -> Self {
+		let info = &openapi.info.extensions;
+		let (mut status, sunset) = extensions_lifecycle(|name| info.get(name));
+		if status.is_none() && info.get("x-deprecated") == Some(&Value::Bool(true)) {
+			status = Some("deprecated".to_string());
+		}
+		let deprecated_servers = document::resolve_servers(openapi, spec_url, vars)
+			.into_iter()
+			.filter(|(_, server)| {
+				let (status, sunset) = extensions_lifecycle(|name| server.extensions.get(name));
+				server.extensions.get("x-deprecated") == Some(&Value::Bool(true))
+					|| sunset.is_some()
+					|| status.is_some_and(|status| RETIRED_STATUSES.contains(&status.as_str()))
+					|| server
+						.description
+						.as_deref()
+						.is_some_and(|description| description.to_lowercase().contains("deprecated"))
+			})
+			.map(|(url, _)| url)
+			.collect();
+		Self { version: openapi.info.version.clone(), status, sunset, deprecated_servers }
+	}
+
+	/// Describe the status and sunset date, e.g. `deprecated, sunset 2025-06-30`
+	pub fn describe(&self) -> Option<String> {
+		match (&self.status, &self.sunset) {
+			(Some(status), Some(sunset)) => Some(format!("{status}, sunset {sunset}")),
+			(Some(status), None) => Some(status.clone()),
+			(None, Some(sunset)) => Some(format!("sunset {sunset}")),
+			(None, None) => None,
+		}
+	}
+
+	/// Whether a base URL points at a deprecated or sunset server
+	pub fn is_deprecated_server(&self, base_url: &str) -> bool {
+		self.deprecated_servers.iter().any(|server| ApiSpec::is_under(base_url, server))
+	}
+}
+
+/// Read the status and sunset date from the lifecycle extensions of an object
+fn extensions_lifecycle<'a>(
+	get: impl Fn(&str) -> Option<&'a Value>,
+) -> (Option<String>, Option<String>) {
+	let mut status = None;
+	let mut sunset = None;
+	for value in LIFECYCLE_EXTENSIONS.iter().filter_map(|name| get(name)) {
+		match value {
+			Value::String(s) => status = status.or(Some(s.clone())),
+			Value::Object(map) => {
+				status = status.or_else(|| string_field(map, &["status", "stage", "state"]));
+				sunset = sunset.or_else(|| string_field(map, &["sunset", "sunsetDate", "sunset-date"]));
+			}
+			_ => {}
+		}
+	}
+	status = status
+		.or_else(|| STATUS_EXTENSIONS.iter().find_map(|name| get(name)?.as_str().map(str::to_string)));
+	sunset = sunset
+		.or_else(|| SUNSET_EXTENSIONS.iter().find_map(|name| get(name)?.as_str().map(str::to_string)));
+	(status.map(|status| status.to_lowercase()), sunset)
+}
+
+fn string_field(map: &serde_json::Map<String, Value>, names: &[&str]) -> Option<String> {
+	names.iter().find_map(|name| map.get(*name)?.as_str().map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_from_document() {
+		let openapi = document::parse(
+			r#"{
+				"openapi": "3.0.0",
+				"info": {
+					"title": "Test API",
+					"version": "2.1.0",
+					"x-api-lifecycle": { "status": "Deprecated", "sunset": "2025-06-30" }
+				},
+				"paths": {},
+				"servers": [
+					{ "url": "https://api.example.com/v2" },
+					{ "url": "https://api.example.com/v1", "x-sunset": "2024-12-31" },
+					{ "url": "https://legacy.example.com", "description": "Deprecated EU cluster" }
+				]
+			}"#,
+		)
+		.unwrap();
+		let lifecycle =
+			Lifecycle::from_document(&openapi, "https://api.example.com/openapi.json", &BTreeMap::new());
+		assert_eq!(lifecycle.version, "2.1.0");
+		assert_eq!(lifecycle.describe().as_deref(), Some("deprecated, sunset 2025-06-30"));
+		assert_eq!(lifecycle.deprecated_servers, [
+			"https://api.example.com/v1",
+			"https://legacy.example.com"
+		]);
+		assert!(lifecycle.is_deprecated_server("https://api.example.com/v1"));
+		assert!(!lifecycle.is_deprecated_server("https://api.example.com/v2"));
+		assert!(!lifecycle.is_deprecated_server("https://api.example.com/v10"));
+	}
+
+	#[test]
+	fn test_without_lifecycle() {
+		let openapi = document::parse(
+			r#"{ "openapi": "3.0.0", "info": { "title": "Test API", "version": "1.0" }, "paths": {} }"#,
+		)
+		.unwrap();
+		let lifecycle = Lifecycle::from_document(&openapi, "", &BTreeMap::new());
+		assert_eq!(lifecycle.describe(), None);
+		assert!(lifecycle.deprecated_servers.is_empty());
+	}
+}
@@ -0,0 +1,256 @@
+use openapiv3::{OpenAPI, Operation, Parameter, PathItem, ReferenceOr, RequestBody, Schema};
+
+use super::Param;
+use super::content_type::BodyOptions;
+use super::endpoint::EndPoint;
+use super::reference::{
+	resolve_parameter_reference, resolve_request_body_reference, resolve_schema_reference,
+};
+
+/// A construct of a spec that is skipped when parsing endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Issue {
+	ReferencedPath,
+	ReferencedParameter,
+	CookieParameter,
+	ReferencedRequestBody,
+	UnsupportedMediaType,
+	UnresolvedSchema,
+	NonObjectBody,
+}
+
+/// Where a skipped construct was found and what it is
+#[derive(Debug)]
+pub struct Finding {
+	pub issue: Issue,
+	/// The path, or the method and path of the operation
+	pub location: String,
+	/// The reference, param name or schema that was skipped
+	pub detail: String,
+}
+
+impl Issue {
+	pub fn title(&self) -> &'static str {
+		match self {
+			Issue::ReferencedPath => "Referenced path items",
+			Issue::ReferencedParameter => "Unresolved parameter references",
+			Issue::CookieParameter => "Cookie parameters",
+			Issue::ReferencedRequestBody => "Unresolved request body references",
+			Issue::UnsupportedMediaType => "Request bodies without JSON or form content",
+			Issue::UnresolvedSchema => "Unresolved body schemas",
+			Issue::NonObjectBody => "Non-object body schemas",
+		}
+	}
+
+	/// What is missing from completion because of the construct
+	pub fn consequence(&self) -> &'static str {
+		match self {
+			Issue::ReferencedPath => "all operations of the path are missing",
+			Issue::ReferencedParameter => "the parameter reference is not found, the param is missing",
+			Issue::CookieParameter => "HTTPie has no cookie param syntax, the param is missing",
+			Issue::ReferencedRequestBody => {
+				"the request body reference is not found, the body params are missing"
+			}
+			Issue::UnsupportedMediaType => {
+				"only JSON and form bodies are flattened, the body params are missing"
+			}
+			Issue::UnresolvedSchema => "the schema reference is not found, the body params are missing",
+			Issue::NonObjectBody => "only object bodies are flattened into params",
+		}
+	}
+}
+
+/// Find the constructs of a spec that parsing endpoints skips, mirroring the
+/// rules of [`super::EndPoints`]
+pub fn lint(openapi: &OpenAPI, body_options: &BodyOptions) -> Vec<Finding> {
+	let mut findings = Vec::new();
+	for (path, item) in &openapi.paths.paths {
+		let item = match item {
+			ReferenceOr::Item(item) => item,
+			ReferenceOr::Reference { reference } => {
+				findings.push(Finding {
+					issue: Issue::ReferencedPath,
+					location: path.clone(),
+					detail: reference.clone(),
+				});
+				continue;
+			}
+		};
+		let path = EndPoint::convert_path_format(path);
+		lint_parameters(&item.parameters, &path, openapi, &mut findings);
+		for (method, operation) in operations(item) {
+			let location = format!("{} {}", method, path);
+			lint_parameters(&operation.parameters, &location, openapi, &mut findings);
+			if let Some(body) = &operation.request_body {
+				lint_request_body(body, &location, openapi, body_options, &mut findings);
+			}
+		}
+	}
+	findings.sort_by_key(|finding| finding.issue);
+	findings
+}
+
+fn operations(item: &PathItem) -> impl Iterator<Item = (&'static str, &Operation)> {
+	[
+		("GET", &item.get),
+		("POST", &item.post),
+		("PUT", &item.put),
+		("DELETE", &item.delete),
+		("PATCH", &item.patch),
+		("HEAD", &item.head),
+		("OPTIONS", &item.options),
+	]
+	.into_iter()
+	.filter_map(|(method, operation)| Some((method, operation.as_ref()?)))
+}
+
+fn lint_parameters(
+	parameters: &[ReferenceOr<Parameter>],
+	location: &str,
+	openapi: &OpenAPI,
+	findings: &mut Vec<Finding>,
+) {
+	let unresolved = |reference: &String| Finding {
+		issue: Issue::ReferencedParameter,
+		location: location.to_string(),
+		detail: reference.clone(),
+	};
+	for parameter in parameters {
+		let parameter = match parameter {
+			ReferenceOr::Item(parameter) => parameter,
+			ReferenceOr::Reference { reference } if reference.starts_with("#/components/parameters/") => {
+				match resolve_parameter_reference(reference, openapi) {
+					Ok(parameter) => parameter,
+					Err(_) => {
+						findings.push(unresolved(reference));
+						continue;
+					}
+				}
+			}
+			ReferenceOr::Reference { reference } => {
+				let resolved = resolve_schema_reference(reference, openapi)
+					.is_ok_and(|schema| Param::try_from_schema(schema, openapi).is_ok_and(|p| !p.is_empty()));
+				if !resolved {
+					findings.push(unresolved(reference));
+				}
+				continue;
+			}
+		};
+		if let Parameter::Cookie { parameter_data, .. } = parameter {
+			findings.push(Finding {
+				issue: Issue::CookieParameter,
+				location: location.to_string(),
+				detail: parameter_data.name.clone(),
+			});
+		}
+	}
+}
+
+fn lint_request_body(
+	body: &ReferenceOr<RequestBody>,
+	location: &str,
+	openapi: &OpenAPI,
+	body_options: &BodyOptions,
+	findings: &mut Vec<Finding>,
+) {
+	let mut push = |issue, detail: String| {
+		findings.push(Finding { issue, location: location.to_string(), detail });
+	};
+	let body = match body {
+		ReferenceOr::Item(body) => body,
+		ReferenceOr::Reference { reference } => {
+			match resolve_request_body_reference(reference, openapi) {
+				Ok(body) => body,
+				Err(_) => return push(Issue::ReferencedRequestBody, reference.clone()),
+			}
+		}
+	};
+	let Some((_, media_type)) = body_options.choose(&body.content) else {
+		if !body.content.is_empty() {
+			let media_types: Vec<_> = body.content.keys().map(String::as_str).collect();
+			push(Issue::UnsupportedMediaType, media_types.join(", "));
+		}
+		return;
+	};
+	let schema: &Schema = match &media_type.schema {
+		Some(ReferenceOr::Item(schema)) => schema,
+		Some(ReferenceOr::Reference { reference }) => {
+			match resolve_schema_reference(reference, openapi) {
+				Ok(schema) => schema,
+				Err(_) => return push(Issue::UnresolvedSchema, reference.clone()),
+			}
+		}
+		None => return,
+	};
+	if Param::try_from_schema_with(schema, openapi, body_options.max_depth).is_err() {
+		let kind = serde_json::to_value(&schema.schema_kind).ok();
+		let kind = kind.as_ref().and_then(|kind| kind.get("type")).and_then(|kind| kind.as_str());
+		push(Issue::NonObjectBody, kind.unwrap_or("composed schema").to_string());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::openapi::document;
+
+	#[test]
+	fn test_lint() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /shared:
+    $ref: "#/components/pathItems/Shared"
+  /users:
+    parameters:
+      - $ref: "#/components/parameters/Page"
+      - $ref: "#/components/parameters/Limit"
+    post:
+      parameters:
+        - name: session
+          in: cookie
+          schema:
+            type: string
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: array
+              items:
+                type: string
+      responses: {}
+    put:
+      requestBody:
+        $ref: "#/components/requestBodies/User"
+      responses: {}
+    patch:
+      requestBody:
+        content:
+          application/xml:
+            schema:
+              type: object
+      responses: {}
+components:
+  parameters:
+    Page:
+      name: page
+      in: query
+      schema:
+        type: integer
+"##;
+		let findings = lint(&document::parse(spec).unwrap(), &BodyOptions::default());
+		let summary: Vec<_> =
+			findings.iter().map(|f| (f.issue, f.location.as_str(), f.detail.as_str())).collect();
+		assert_eq!(summary, [
+			(Issue::ReferencedPath, "/shared", "#/components/pathItems/Shared"),
+			(Issue::ReferencedParameter, "/users", "#/components/parameters/Limit"),
+			(Issue::CookieParameter, "POST /users", "session"),
+			(Issue::ReferencedRequestBody, "PUT /users", "#/components/requestBodies/User"),
+			(Issue::UnsupportedMediaType, "PATCH /users", "application/xml"),
+			(Issue::NonObjectBody, "POST /users", "array"),
+		]);
+	}
+}
@@ -1,6 +1,7 @@
+use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum Method {
 	Get,
 	Post,
@@ -9,11 +10,48 @@ pub enum Method {
 	Head,
 	Patch,
 	Options,
+	Trace,
+	/// A method outside of HTTP/1.1 and PATCH, such as WebDAV's `PROPFIND`,
+	/// in upper case
+	Other(String),
 }
 
-impl From<&str> for Method {
-	fn from(s: &str) -> Self {
-		match s.to_uppercase().as_str() {
+impl Method {
+	/// All standard methods, in the order they are suggested
+	pub const ALL: [Method; 8] = [
+		Method::Get,
+		Method::Post,
+		Method::Put,
+		Method::Patch,
+		Method::Delete,
+		Method::Head,
+		Method::Options,
+		Method::Trace,
+	];
+
+	/// Short description of what the method does
+	pub fn description(&self) -> &'static str {
+		match self {
+			Method::Get => "retrieve a resource",
+			Method::Post => "create a resource or submit data",
+			Method::Put => "replace a resource",
+			Method::Delete => "delete a resource",
+			Method::Head => "retrieve headers only",
+			Method::Patch => "partially update a resource",
+			Method::Options => "list supported methods",
+			Method::Trace => "echo the request back",
+			Method::Other(_) => "custom method",
+		}
+	}
+}
+
+impl TryFrom<&str> for Method {
+	type Error = anyhow::Error;
+
+	/// Parse a method name case-insensitively, names that are not a valid
+	/// HTTP token fail
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		let method = match s.to_uppercase().as_str() {
 			"GET" => Method::Get,
 			"POST" => Method::Post,
 			"PUT" => Method::Put,
@@ -21,11 +59,21 @@ impl From<&str> for Method {
 			"PATCH" => Method::Patch,
 			"HEAD" => Method::Head,
 			"OPTIONS" => Method::Options,
-			_ => panic!("Unsupported HTTP method: {}", s),
-		}
+			"TRACE" => Method::Trace,
+			name if !name.is_empty() && name.bytes().all(is_token_char) => {
+				Method::Other(name.to_string())
+			}
+			_ => return Err(anyhow!("Invalid HTTP method '{}'", s)),
+		};
+		Ok(method)
 	}
 }
 
+/// Whether a byte may appear in an HTTP token such as a method name
+fn is_token_char(byte: u8) -> bool {
+	byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
 impl std::fmt::Display for Method {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		let name = match self {
@@ -36,7 +84,25 @@ impl std::fmt::Display for Method {
 			Method::Head => "HEAD",
 			Method::Patch => "PATCH",
 			Method::Options => "OPTIONS",
+			Method::Trace => "TRACE",
+			Method::Other(name) => name,
 		};
 		write!(f, "{}", name)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_try_from() {
+		assert_eq!(Method::try_from("get").unwrap(), Method::Get);
+		assert_eq!(Method::try_from("Trace").unwrap(), Method::Trace);
+		let propfind = Method::try_from("propfind").unwrap();
+		assert_eq!(propfind, Method::Other("PROPFIND".to_string()));
+		assert_eq!(propfind.to_string(), "PROPFIND");
+		assert!(Method::try_from("").is_err());
+		assert!(Method::try_from("GET /").is_err());
+	}
+}
@@ -1,10 +1,35 @@
 mod api_spec;
+mod bundle;
+pub mod cache;
+pub mod content_type;
+pub mod diff;
+mod document;
+pub mod download;
 pub mod endpoint;
+mod extensions;
+mod groups;
+mod har;
+mod json;
+pub mod lifecycle;
+pub mod lint;
 pub mod method;
+mod openapi31;
 pub mod param;
+pub mod path_filter;
+pub mod path_index;
+pub mod postman;
 mod reference;
+pub mod response;
+pub mod retry;
+pub mod security;
+mod swagger2;
+pub mod versions;
 
-pub use api_spec::ApiSpec;
+pub use api_spec::{ApiSpec, BodyParams, Refresh, TlsOptions};
+pub use bundle::RefOptions;
+pub use content_type::BodyOptions;
+pub use document::DocumentFormat;
+pub use download::DownloadOptions;
 pub use endpoint::EndPoints;
 pub use method::Method;
 pub use param::Param;
@@ -0,0 +1,209 @@
+use serde_json::{Map, Value, json};
+use tracing::debug;
+
+/// Keys whose values map names to objects rather than being schemas themselves
+const NAME_MAPS: &[&str] = &[
+	"properties",
+	"patternProperties",
+	"$defs",
+	"schemas",
+	"responses",
+	"parameters",
+	"examples",
+	"requestBodies",
+	"headers",
+	"securitySchemes",
+	"links",
+	"callbacks",
+	"pathItems",
+	"content",
+	"paths",
+	WEBHOOKS,
+	"encoding",
+	"mapping",
+];
+
+/// Top level keys that OpenAPI 3.0 does not have and completion does not need
+const UNSUPPORTED_KEYS: &[&str] = &["jsonSchemaDialect"];
+
+/// Extension that the `webhooks` of a 3.1 document are kept under, since
+/// OpenAPI 3.0 has no place for them
+pub const WEBHOOKS: &str = "x-webhooks";
+
+/// Whether a document is an OpenAPI 3.1 spec
+pub fn is_openapi31(document: &Value) -> bool {
+	document.get("openapi").and_then(Value::as_str).is_some_and(|v| v.starts_with("3.1"))
+}
+
+/// Rewrite an OpenAPI 3.1 document into the OpenAPI 3.0 shape
+///
+/// Type arrays such as `[string, "null"]` become `nullable` types or `anyOf`,
+/// schema `examples` arrays become `example`, `const` becomes a single value
+/// `enum` and numeric exclusive bounds become the boolean form. Webhooks move
+/// to the [`WEBHOOKS`] extension.
+pub fn downgrade(mut document: Value) -> Value {
+	debug!("Downgrading OpenAPI 3.1 document to OpenAPI 3.0");
+	if let Value::Object(map) = &mut document {
+		map.insert("openapi".to_string(), json!("3.0.3"));
+		for key in UNSUPPORTED_KEYS {
+			map.remove(*key);
+		}
+		if let Some(webhooks) = map.remove("webhooks") {
+			map.insert(WEBHOOKS.to_string(), webhooks);
+		}
+		// Paths are optional in 3.1
+		map.entry("paths").or_insert_with(|| json!({}));
+		// The document is no schema, but its keys are looked up like any other
+		for (key, child) in map.iter_mut() {
+			walk(child, NAME_MAPS.contains(&key.as_str()));
+		}
+	}
+	document
+}
+
+fn walk(value: &mut Value, is_name_map: bool) {
+	match value {
+		Value::Object(map) => {
+			if !is_name_map {
+				downgrade_schema(map);
+			}
+			// The keys of a name map are names chosen by the spec, such as a
+			// property called `content`, and say nothing about their values
+			for (key, child) in map.iter_mut() {
+				walk(child, !is_name_map && NAME_MAPS.contains(&key.as_str()));
+			}
+		}
+		Value::Array(items) => items.iter_mut().for_each(|item| walk(item, false)),
+		_ => {}
+	}
+}
+
+fn downgrade_schema(map: &mut Map<String, Value>) {
+	if let Some(Value::Array(types)) = map.get("type").cloned() {
+		let nullable = types.iter().any(|t| t == "null");
+		let types: Vec<Value> = types.into_iter().filter(|t| t != "null").collect();
+		match types.as_slice() {
+			[] => {
+				map.remove("type");
+			}
+			[single] => {
+				map.insert("type".to_string(), single.clone());
+			}
+			_ => {
+				map.remove("type");
+				let any_of = types.into_iter().map(|t| json!({ "type": t })).collect();
+				map.insert("anyOf".to_string(), Value::Array(any_of));
+			}
+		}
+		if nullable {
+			map.insert("nullable".to_string(), json!(true));
+		}
+	} else if map.get("type").is_some_and(|t| t == "null") {
+		map.remove("type");
+		map.insert("nullable".to_string(), json!(true));
+	}
+
+	// Schema examples are arrays, examples of parameters and media types are maps
+	if let Some(Value::Array(examples)) = map.get("examples") {
+		let example = examples.first().cloned();
+		map.remove("examples");
+		if let Some(example) = example {
+			map.entry("example").or_insert(example);
+		}
+	}
+
+	if let Some(value) = map.remove("const") {
+		map.insert("enum".to_string(), json!([value]));
+	}
+
+	for (exclusive, bound) in [("exclusiveMinimum", "minimum"), ("exclusiveMaximum", "maximum")] {
+		if let Some(limit) = map.get(exclusive).filter(|limit| limit.is_number()).cloned() {
+			map.insert(bound.to_string(), limit);
+			map.insert(exclusive.to_string(), json!(true));
+		}
+	}
+
+	if map.get("contentEncoding").is_some_and(|e| e == "base64") {
+		map.remove("contentEncoding");
+		map.entry("format").or_insert_with(|| json!("byte"));
+	} else if map.remove("contentMediaType").is_some() {
+		map.entry("format").or_insert_with(|| json!("binary"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_downgrade() {
+		let document = json!({
+			"openapi": "3.1.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"webhooks": { "newPet": {} },
+			"components": {
+				"schemas": {
+					"Pet": {
+						"type": "object",
+						"properties": {
+							"name": { "type": ["string", "null"], "examples": ["Rex"] },
+							"age": { "type": "integer", "exclusiveMinimum": 0 },
+							"kind": { "const": "dog" },
+							"id": { "type": ["string", "integer"] },
+							"const": { "type": "string" }
+						}
+					}
+				}
+			}
+		});
+		assert!(is_openapi31(&document));
+		let document = downgrade(document);
+
+		assert_eq!(document["openapi"], "3.0.3");
+		assert!(document.get("webhooks").is_none());
+		assert_eq!(document[WEBHOOKS], json!({ "newPet": {} }));
+		assert_eq!(document["paths"], json!({}));
+		let properties = &document["components"]["schemas"]["Pet"]["properties"];
+		assert_eq!(properties["name"], json!({ "type": "string", "nullable": true, "example": "Rex" }));
+		assert_eq!(properties["age"]["minimum"], 0);
+		assert_eq!(properties["age"]["exclusiveMinimum"], true);
+		assert_eq!(properties["kind"], json!({ "enum": ["dog"] }));
+		assert_eq!(properties["id"]["anyOf"], json!([{ "type": "string" }, { "type": "integer" }]));
+		assert_eq!(properties["const"], json!({ "type": "string" }));
+	}
+
+	#[test]
+	fn test_downgrade_property_names() {
+		let schema = json!({
+			"type": "object",
+			"properties": {
+				"content": { "type": ["string", "null"] },
+				"headers": { "type": ["integer", "null"] },
+				"examples": { "type": "array", "items": { "type": "string" }, "examples": [["a"]] },
+				"properties": { "type": "object", "properties": { "links": { "const": 1 } } }
+			}
+		});
+		let document = json!({
+			"openapi": "3.1.0",
+			"info": { "title": "Test API", "version": "1.0.0" },
+			"paths": {
+				"/posts": {
+					"post": {
+						"requestBody": { "content": { "application/json": { "schema": schema } } },
+						"responses": { "201": { "description": "created" } }
+					}
+				}
+			}
+		});
+		let document = downgrade(document);
+
+		let schema =
+			&document["paths"]["/posts"]["post"]["requestBody"]["content"]["application/json"]["schema"];
+		let properties = &schema["properties"];
+		assert_eq!(properties["content"], json!({ "type": "string", "nullable": true }));
+		assert_eq!(properties["headers"], json!({ "type": "integer", "nullable": true }));
+		assert_eq!(properties["examples"]["example"], json!(["a"]));
+		assert_eq!(properties["properties"]["properties"]["links"], json!({ "enum": [1] }));
+		assert!(serde_json::from_value::<openapiv3::OpenAPI>(document).is_ok());
+	}
+}
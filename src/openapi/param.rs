@@ -1,21 +1,43 @@
-use openapiv3::{Parameter, Schema, SchemaKind, Type};
+use openapiv3::{
+	OpenAPI, Parameter, ParameterData, ParameterSchemaOrContent, QueryStyle, ReferenceOr, Schema,
+	SchemaKind, Type, VariantOrUnknownOrEmpty,
+};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::content_type::DEFAULT_MAX_DEPTH;
+use super::extensions;
+use super::reference::{resolve_example_reference, resolve_schema_reference};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ParamSource {
 	Query,
 	Body,
 	Path,
 	Header,
 	Form,
+	/// A file part of a multipart form
+	File,
 }
 
 impl ParamSource {
+	/// Lower case name of the source, e.g. `query`
+	pub fn name(&self) -> &'static str {
+		match self {
+			ParamSource::Query => "query",
+			ParamSource::Body => "body",
+			ParamSource::Path => "path",
+			ParamSource::Header => "header",
+			ParamSource::Form => "form",
+			ParamSource::File => "file",
+		}
+	}
+
 	pub fn httpie_operator(&self) -> &'static str {
 		match self {
 			ParamSource::Body | ParamSource::Form | ParamSource::Path => "=",
 			ParamSource::Query => "==",
 			ParamSource::Header => ":",
+			ParamSource::File => "@",
 		}
 	}
 	pub fn httpie_param_prefix(&self) -> &'static str {
@@ -24,6 +46,14 @@ impl ParamSource {
 			_ => "",
 		}
 	}
+
+	/// Whether the param is part of the request body
+	pub fn is_body(&self) -> bool {
+		matches!(self, ParamSource::Body | ParamSource::Form | ParamSource::File)
+	}
+
+	/// Whether the param is a field of a form body
+	pub fn is_form(&self) -> bool { matches!(self, ParamSource::Form | ParamSource::File) }
 }
 
 impl From<&str> for ParamSource {
@@ -34,30 +64,178 @@ impl From<&str> for ParamSource {
 			"path" => Self::Path,
 			"header" => Self::Header,
 			"form" => Self::Form,
+			"file" => Self::File,
 			_ => panic!("Unsupported Param Source: {}", s),
 		}
 	}
 }
 
+/// How the values of an array param are sent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArrayStyle {
+	/// Every value repeats the param, e.g. `tag==a tag==b`
+	Repeated,
+	/// The values are joined into one, e.g. `ids==1,2,3`
+	Joined { separator: char },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Param {
 	pub name: String,
 	pub required: bool,
 	pub source: ParamSource,
 	pub description: Option<String>,
+	/// Default value declared by the schema, rendered for the command line
+	#[serde(default)]
+	pub default: Option<String>,
+	/// Example values declared by the parameter or its schema
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub examples: Vec<String>,
+	/// Values allowed by the `enum` of the schema or of its array items
+	#[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
+	pub enum_values: Vec<String>,
+	/// Type declared by the schema, e.g. `integer` or `string (date-time)`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub schema_type: Option<String>,
+	/// Regular expression that string values have to match
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub pattern: Option<String>,
+	/// How the values are sent, for array params
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub array: Option<ArrayStyle>,
+	/// Whether the spec marks the parameter or property as deprecated
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub deprecated: bool,
 }
 
 impl Param {
+	/// Format the param as a `param<TAB>description` completion line, the
+	/// description naming the type of the value
 	pub fn fish_complete_format(&self) -> String {
 		let desc = self.description.as_deref().unwrap_or(&self.name);
-		let desc = if self.required { desc.to_string() } else { format!("[{}]", desc) };
+		let array = match &self.array {
+			Some(ArrayStyle::Repeated) => Some("repeatable".to_string()),
+			Some(ArrayStyle::Joined { separator: ' ' }) => Some("space separated".to_string()),
+			Some(ArrayStyle::Joined { separator }) => Some(format!("separated by {separator}")),
+			None => None,
+		};
+		let details: Vec<_> = self.schema_type.iter().cloned().chain(array).collect();
+		let desc = if details.is_empty() {
+			desc.to_string()
+		} else {
+			format!("{} ({})", desc, details.join(", "))
+		};
+		let desc = if self.required { desc } else { format!("[{}]", desc) };
+
+		match &self.default {
+			Some(default) => format!("{}{}\t{}", self.httpie_value_format(), default, desc),
+			None => format!("{}\t{}", self.httpie_param_format(), desc),
+		}
+	}
 
-		format!("{}\t{}", self.httpie_param_format(), desc)
+	/// Format value completions for an assignment to this param, e.g.
+	/// `page==1\tdefault value`
+	pub fn fish_value_complete_formats(&self) -> Vec<String> {
+		let prefix = self.httpie_value_format();
+		let default = self.default.iter().map(|value| (value, "default value"));
+		let examples = self
+			.examples
+			.iter()
+			.filter(|&example| self.default.as_ref() != Some(example))
+			.map(|example| (example, "example from spec"));
+		default.chain(examples).map(|(value, desc)| format!("{prefix}{value}\t{desc}")).collect()
 	}
 
+	/// Whether the param may be given more than once, as array params whose
+	/// values repeat the param are
+	pub fn is_repeatable(&self) -> bool { self.array == Some(ArrayStyle::Repeated) }
+
 	pub fn httpie_param_format(&self) -> String {
 		format!("{}{}{}", self.source.httpie_param_prefix(), self.name, self.source.httpie_operator())
 	}
+
+	/// The request item a value of the param follows: `name:=` for typed body
+	/// params, whose values HTTPie would send as strings after `=`
+	pub fn httpie_value_format(&self) -> String {
+		match self.is_raw_json() {
+			true => format!("{}:=", self.name),
+			false => self.httpie_param_format(),
+		}
+	}
+
+	/// A value to fill the param in with: its first example, its default, its
+	/// first allowed value or a placeholder of its type
+	pub fn example_value(&self) -> String {
+		let declared = self.examples.first().or(self.default.as_ref()).or(self.enum_values.first());
+		if let Some(value) = declared {
+			return value.clone();
+		}
+		let schema_type = self.schema_type.as_deref().unwrap_or("string");
+		let placeholder = match schema_type.split_once(' ').map_or(schema_type, |(name, _)| name) {
+			"integer" | "number" => "1",
+			"boolean" => "true",
+			"object" => "{}",
+			"array" => "[]",
+			_ => match schema_type {
+				"string (date)" => "2024-01-01",
+				"string (date-time)" => "2024-01-01T00:00:00Z",
+				"string (uuid)" => "00000000-0000-0000-0000-000000000000",
+				"string (email)" => "user@example.com",
+				"string (uri)" | "string (url)" => "https://example.com",
+				_ => "string",
+			},
+		};
+		placeholder.to_string()
+	}
+
+	/// Check a value given on the command line against the type, format,
+	/// allowed values and pattern of the param, telling what is wrong with it
+	pub fn check_value(&self, value: &str) -> Result<(), String> {
+		let schema_type = self.schema_type.as_deref().unwrap_or_default();
+		let valid = match schema_type.split(' ').next().unwrap_or_default() {
+			"integer" => value.parse::<i64>().is_ok() || value.parse::<u64>().is_ok(),
+			"number" => value.parse::<f64>().is_ok_and(f64::is_finite),
+			"boolean" => value == "true" || value == "false",
+			_ if schema_type == "string (uuid)" => is_uuid(value),
+			_ => true,
+		};
+		if !valid {
+			return Err(format!("expected {}", schema_type));
+		}
+		if !self.enum_values.is_empty() && !self.enum_values.iter().any(|allowed| allowed == value) {
+			return Err(format!("expected one of {}", self.enum_values.join(", ")));
+		}
+		if let Some(pattern) = &self.pattern {
+			// Patterns the regex engine doesn't support are not checked
+			match regex_automata::meta::Regex::new(pattern) {
+				Ok(regex) if !regex.is_match(value) => {
+					return Err(format!("expected a value matching {}", pattern));
+				}
+				Ok(_) => {}
+				Err(e) => tracing::debug!("Skipping pattern {} of {}: {}", pattern, self.name, e),
+			}
+		}
+		Ok(())
+	}
+
+	/// The example value of the param as JSON, typed JSON body params as the
+	/// JSON they hold
+	pub fn example_json(&self) -> serde_json::Value {
+		let value = self.example_value();
+		let json = self.is_raw_json().then(|| serde_json::from_str(&value).ok()).flatten();
+		json.unwrap_or(serde_json::Value::String(value))
+	}
+
+	/// Whether the values of a body param are JSON other than strings, which
+	/// HTTPie sends with `:=`
+	pub fn is_raw_json(&self) -> bool {
+		let schema_type = self.schema_type.as_deref().unwrap_or_default();
+		self.source == ParamSource::Body
+			&& ["integer", "number", "boolean", "object", "array"]
+				.iter()
+				.any(|name| schema_type.starts_with(name))
+	}
 }
 
 impl std::fmt::Display for Param {
@@ -66,46 +244,578 @@ impl std::fmt::Display for Param {
 	}
 }
 
-impl TryFrom<&Parameter> for Param {
-	type Error = String;
-
-	fn try_from(parameter: &Parameter) -> Result<Self, Self::Error> {
-		let (parameter_data, source) = match parameter {
-			Parameter::Query { parameter_data, .. } => (parameter_data, ParamSource::Query),
-			Parameter::Header { parameter_data, .. } => (parameter_data, ParamSource::Header),
-			Parameter::Path { parameter_data, .. } => (parameter_data, ParamSource::Path),
+impl Param {
+	/// Convert a query, header or path parameter
+	///
+	/// Schemas and examples that are references are resolved, so the type,
+	/// default, examples and allowed values are kept for them as well.
+	pub fn try_from_parameter(parameter: &Parameter, spec: &OpenAPI) -> Result<Self, String> {
+		// Only form style query params repeat their values unless told otherwise
+		let (parameter_data, source, separator, explode) = match parameter {
+			Parameter::Query { parameter_data, style, .. } => {
+				let separator = match style {
+					QueryStyle::SpaceDelimited => ' ',
+					QueryStyle::PipeDelimited => '|',
+					_ => ',',
+				};
+				(parameter_data, ParamSource::Query, separator, *style == QueryStyle::Form)
+			}
+			Parameter::Header { parameter_data, .. } => (parameter_data, ParamSource::Header, ',', false),
+			Parameter::Path { parameter_data, .. } => (parameter_data, ParamSource::Path, ',', false),
 			Parameter::Cookie { .. } => return Err("unsupported Cookie param".to_owned()),
 		};
+		if extensions::is_hidden(|name| parameter_data.extensions.get(name)) {
+			return Err(format!("param {} is hidden", parameter_data.name));
+		}
+		let schema = parameter_schema(parameter_data, spec);
+		let array = schema.filter(|schema| is_array(schema)).map(|_| {
+			if parameter_data.explode.unwrap_or(explode) {
+				ArrayStyle::Repeated
+			} else {
+				ArrayStyle::Joined { separator }
+			}
+		});
+		let render = |value: &serde_json::Value| render_values(value, array.as_ref());
 		Ok(Self {
 			name: parameter_data.name.clone(),
 			required: parameter_data.required,
 			source,
 			description: parameter_data.description.clone(),
+			default: schema
+				.and_then(|schema| schema.schema_data.default.as_ref())
+				.and_then(|value| render(value).into_iter().next()),
+			examples: parameter_examples(parameter_data, spec, render),
+			enum_values: schema.map(schema_enum).unwrap_or_default(),
+			schema_type: schema.and_then(schema_type),
+			pattern: schema.and_then(schema_pattern),
+			array,
+			deprecated: parameter_data.deprecated.unwrap_or_default(),
 		})
 	}
 }
 
+/// Get the schema of a parameter, if any
+fn parameter_schema<'a>(
+	parameter_data: &'a ParameterData,
+	spec: &'a OpenAPI,
+) -> Option<&'a Schema> {
+	match &parameter_data.format {
+		ParameterSchemaOrContent::Schema(schema) => resolve(schema, spec),
+		ParameterSchemaOrContent::Content(_) => None,
+	}
+}
+
+/// Collect the examples of a parameter, falling back to its schema example
+/// and to the example of its array items
+///
+/// The `x-example-values` of the parameter and its schema come first.
+fn parameter_examples(
+	parameter_data: &ParameterData,
+	spec: &OpenAPI,
+	render: impl Fn(&serde_json::Value) -> Vec<String>,
+) -> Vec<String> {
+	let mut examples: Vec<String> = parameter_data.example.iter().flat_map(&render).collect();
+	examples.extend(parameter_data.examples.values().flat_map(|example| {
+		let example = match example {
+			ReferenceOr::Item(example) => Some(example),
+			ReferenceOr::Reference { reference } => resolve_example_reference(reference, spec).ok(),
+		};
+		example.and_then(|example| example.value.as_ref()).map(&render).unwrap_or_default()
+	}));
+	let schema = parameter_schema(parameter_data, spec);
+	if examples.is_empty()
+		&& let Some(example) = schema.and_then(|schema| schema.schema_data.example.as_ref())
+	{
+		examples.extend(render(example));
+	}
+	if examples.is_empty() {
+		examples.extend(schema.and_then(array_items).and_then(schema_example));
+	}
+	let values = extensions::example_values(|name| parameter_data.extensions.get(name))
+		.into_iter()
+		.chain(schema.into_iter().flat_map(|schema| {
+			extensions::example_values(|name| schema.schema_data.extensions.get(name))
+		}))
+		.flat_map(render);
+	with_values_first(values, examples)
+}
+
+/// Put extension values before the examples of the spec, dropping repeats
+fn with_values_first(
+	values: impl IntoIterator<Item = String>,
+	examples: Vec<String>,
+) -> Vec<String> {
+	let mut all: Vec<String> = Vec::new();
+	for value in values.into_iter().chain(examples) {
+		if !all.contains(&value) {
+			all.push(value);
+		}
+	}
+	all
+}
+
+fn is_array(schema: &Schema) -> bool {
+	matches!(schema.schema_kind, SchemaKind::Type(Type::Array(_)))
+}
+
+/// The inline item schema of an array schema
+fn array_items(schema: &Schema) -> Option<&Schema> {
+	match &schema.schema_kind {
+		SchemaKind::Type(Type::Array(array)) => match array.items.as_ref()? {
+			ReferenceOr::Item(items) => Some(items),
+			ReferenceOr::Reference { .. } => None,
+		},
+		_ => None,
+	}
+}
+
+fn schema_example(schema: &Schema) -> Option<String> {
+	schema.schema_data.example.as_ref().map(render_value)
+}
+
+/// The values allowed by a schema, those of the items for an array schema
+fn schema_enum(schema: &Schema) -> Vec<String> {
+	fn render<T: ToString>(values: &[Option<T>]) -> Vec<String> {
+		values.iter().flatten().map(T::to_string).collect()
+	}
+	match &schema.schema_kind {
+		SchemaKind::Type(Type::String(string)) => render(&string.enumeration),
+		SchemaKind::Type(Type::Number(number)) => render(&number.enumeration),
+		SchemaKind::Type(Type::Integer(integer)) => render(&integer.enumeration),
+		SchemaKind::Type(Type::Boolean(boolean)) => render(&boolean.enumeration),
+		SchemaKind::Type(Type::Array(_)) => array_items(schema).map(schema_enum).unwrap_or_default(),
+		SchemaKind::Any(any) => any.enumeration.iter().map(render_value).collect(),
+		_ => Vec::new(),
+	}
+}
+
+/// Set a field of a JSON body named in HTTPie's nested JSON syntax, such as
+/// `address[city]` or `tags[]`
+///
+/// Fields nested in `[]` go into the last element of the array unless it
+/// has them already, so `items[][id]` and `items[][name]` make one object.
+pub fn insert_nested(body: &mut serde_json::Value, name: &str, value: serde_json::Value) {
+	use serde_json::Value;
+
+	let mut keys = name.split('[').map(|key| key.trim_end_matches(']')).peekable();
+	let mut target = body;
+	while let Some(key) = keys.next() {
+		target = match key.is_empty() {
+			true => {
+				if !target.is_array() {
+					*target = Value::Array(Vec::new());
+				}
+				let array = target.as_array_mut().expect("target was made an array");
+				let next = keys.peek();
+				let fits = array.last().is_some_and(|last| {
+					next.is_some_and(|next| last.as_object().is_some_and(|o| !o.contains_key(*next)))
+				});
+				if !fits {
+					array.push(Value::Null);
+				}
+				array.last_mut().expect("the array has an element")
+			}
+			false => {
+				if !target.is_object() {
+					*target = Value::Object(Default::default());
+				}
+				let object = target.as_object_mut().expect("target was made an object");
+				object.entry(key).or_insert(Value::Null)
+			}
+		};
+	}
+	*target = value;
+}
+
+/// The values of a field named in HTTPie's nested JSON syntax in a JSON body,
+/// along with the number of objects that lack it
+///
+/// Fields nested in `[]` are looked up in every element of the array.
+pub fn nested_values<'a>(
+	body: &'a serde_json::Value,
+	name: &str,
+) -> (Vec<&'a serde_json::Value>, usize) {
+	let keys: Vec<_> = name.split('[').map(|key| key.trim_end_matches(']')).collect();
+	let mut targets = vec![body];
+	let mut missing = 0;
+	for (i, key) in keys.iter().enumerate() {
+		let mut next = Vec::new();
+		for target in targets {
+			match (key.is_empty(), target) {
+				(true, serde_json::Value::Array(items)) => next.extend(items),
+				(false, serde_json::Value::Object(object)) => match object.get(*key) {
+					Some(value) => next.push(value),
+					None if i == keys.len() - 1 => missing += 1,
+					None => {}
+				},
+				_ => {}
+			}
+		}
+		targets = next;
+	}
+	(targets, missing)
+}
+
+/// Whether a value is a UUID in its hyphenated form
+fn is_uuid(value: &str) -> bool {
+	let groups: Vec<_> = value.split('-').collect();
+	let lengths = groups.iter().map(|group| group.len());
+	lengths.eq([8, 4, 4, 4, 12])
+		&& groups.iter().all(|group| group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// The `pattern` of a string schema
+fn schema_pattern(schema: &Schema) -> Option<String> {
+	match &schema.schema_kind {
+		SchemaKind::Type(Type::String(string)) => string.pattern.clone(),
+		_ => None,
+	}
+}
+
+/// Render the schema default as a command line value
+fn schema_default(schema: &Schema) -> Option<String> {
+	schema.schema_data.default.as_ref().map(render_value)
+}
+
+/// Describe the type of a schema with its format, e.g. `integer (int64)`
+pub(super) fn schema_type(schema: &Schema) -> Option<String> {
+	let (name, format) = match &schema.schema_kind {
+		SchemaKind::Type(Type::String(string)) => ("string", format_name(&string.format)),
+		SchemaKind::Type(Type::Number(number)) => ("number", format_name(&number.format)),
+		SchemaKind::Type(Type::Integer(integer)) => ("integer", format_name(&integer.format)),
+		SchemaKind::Type(Type::Boolean(_)) => ("boolean", None),
+		SchemaKind::Type(Type::Object(_)) => ("object", None),
+		SchemaKind::Type(Type::Array(_)) => match array_items(schema).and_then(schema_type) {
+			Some(items) => return Some(format!("array of {items}")),
+			None => ("array", None),
+		},
+		_ => return None,
+	};
+	Some(match format {
+		Some(format) => format!("{name} ({format})"),
+		None => name.to_string(),
+	})
+}
+
+fn format_name<T: Serialize>(format: &VariantOrUnknownOrEmpty<T>) -> Option<String> {
+	match format {
+		VariantOrUnknownOrEmpty::Item(format) => {
+			serde_json::to_value(format).ok()?.as_str().map(str::to_string)
+		}
+		VariantOrUnknownOrEmpty::Unknown(format) => Some(format.clone()),
+		VariantOrUnknownOrEmpty::Empty => None,
+	}
+}
+
+fn render_value(value: &serde_json::Value) -> String {
+	match value {
+		serde_json::Value::String(s) => s.clone(),
+		other => other.to_string(),
+	}
+}
+
+/// Render a value of a param for the command line, array values of an
+/// array param as they are sent
+fn render_values(value: &serde_json::Value, array: Option<&ArrayStyle>) -> Vec<String> {
+	match (value, array) {
+		(serde_json::Value::Array(items), Some(ArrayStyle::Repeated)) => {
+			items.iter().map(render_value).collect()
+		}
+		(serde_json::Value::Array(items), Some(ArrayStyle::Joined { separator })) => {
+			let items: Vec<_> = items.iter().map(render_value).collect();
+			vec![items.join(&separator.to_string())]
+		}
+		_ => vec![render_value(value)],
+	}
+}
+
 impl Param {
-	pub fn try_from_schema(schema: &Schema) -> Result<Vec<Self>, String> {
+	/// Flatten the properties of an object schema into body params
+	///
+	/// Members of `allOf` are merged into one object. The variants of `oneOf`
+	/// and `anyOf` are united, params that only some variants have are
+	/// optional and name those variants in their description. Properties
+	/// that are objects or arrays are followed by params in HTTPie's nested
+	/// JSON syntax, such as `address[city]` and `tags[]`.
+	pub fn try_from_schema(schema: &Schema, spec: &OpenAPI) -> Result<Vec<Self>, String> {
+		Self::try_from_schema_with(schema, spec, DEFAULT_MAX_DEPTH)
+	}
+
+	/// Flatten a schema like [`Param::try_from_schema`], following at most
+	/// `max_depth` levels of objects, arrays and composed schemas
+	pub fn try_from_schema_with(
+		schema: &Schema,
+		spec: &OpenAPI,
+		max_depth: usize,
+	) -> Result<Vec<Self>, String> {
+		Self::from_schema(schema, spec, max_depth)
+			.ok_or_else(|| "Schema must be an object type".to_string())
+	}
+
+	fn from_schema(schema: &Schema, spec: &OpenAPI, depth: usize) -> Option<Vec<Self>> {
+		if depth == 0 {
+			return None;
+		}
 		match &schema.schema_kind {
 			SchemaKind::Type(Type::Object(object_type)) => {
-				let mut params = Vec::new();
-				for (name, property) in &object_type.properties {
-					let required = object_type.required.contains(name);
-					let description = match property {
-						openapiv3::ReferenceOr::Item(schema) => schema.schema_data.description.clone(),
-						openapiv3::ReferenceOr::Reference { .. } => None,
+				Some(Self::from_properties(&object_type.properties, &object_type.required, spec, depth))
+			}
+			SchemaKind::AllOf { all_of } => Self::merge_members(all_of, spec, depth),
+			SchemaKind::OneOf { one_of: variants } | SchemaKind::AnyOf { any_of: variants } => {
+				Self::unite_variants(variants, spec, depth)
+			}
+			SchemaKind::Any(any) => {
+				let mut params = Self::from_properties(&any.properties, &any.required, spec, depth);
+				let composed = [
+					Self::merge_members(&any.all_of, spec, depth),
+					Self::unite_variants(&any.one_of, spec, depth),
+					Self::unite_variants(&any.any_of, spec, depth),
+				];
+				let composed: Vec<_> = composed.into_iter().flatten().collect();
+				if params.is_empty() && composed.is_empty() {
+					return None;
+				}
+				for more in composed {
+					merge(&mut params, more);
+				}
+				Some(params)
+			}
+			_ => None,
+		}
+	}
+
+	fn from_properties<'a>(
+		properties: impl IntoIterator<Item = (&'a String, &'a ReferenceOr<Box<Schema>>)>,
+		required: &[String],
+		spec: &'a OpenAPI,
+		depth: usize,
+	) -> Vec<Self> {
+		let mut params = Vec::new();
+		for (name, property) in properties {
+			let required = required.contains(name);
+			let property = resolve_boxed(property, spec);
+			if property.is_some_and(|property| {
+				extensions::is_hidden(|key| property.schema_data.extensions.get(key))
+			}) {
+				continue;
+			}
+			params.push(Self::body_param(name, required, property));
+			if let Some(property) = property {
+				params.extend(Self::nested(name, required, property, spec, depth));
+			}
+		}
+		params
+	}
+
+	fn body_param(name: &str, required: bool, schema: Option<&Schema>) -> Self {
+		Self {
+			name: name.to_string(),
+			required,
+			source: ParamSource::Body,
+			description: schema.and_then(|schema| schema.schema_data.description.clone()),
+			default: schema.and_then(schema_default),
+			examples: with_values_first(
+				schema.into_iter().flat_map(|schema| {
+					extensions::example_values(|name| schema.schema_data.extensions.get(name))
+						.into_iter()
+						.map(render_value)
+				}),
+				schema.and_then(schema_example).into_iter().collect(),
+			),
+			enum_values: schema.map(schema_enum).unwrap_or_default(),
+			schema_type: schema.and_then(schema_type),
+			pattern: schema.and_then(schema_pattern),
+			array: None,
+			deprecated: schema.is_some_and(|schema| schema.schema_data.deprecated),
+		}
+	}
+
+	/// Params of the properties or items of an object or array property
+	/// `name` in HTTPie's nested JSON syntax, none for other properties
+	fn nested(
+		name: &str,
+		required: bool,
+		schema: &Schema,
+		spec: &OpenAPI,
+		depth: usize,
+	) -> Vec<Self> {
+		let deprecated = schema.schema_data.deprecated;
+		let (name, schema) = match &schema.schema_kind {
+			SchemaKind::Type(Type::Array(array)) => {
+				let Some(items) = array.items.as_ref().and_then(|items| resolve_boxed(items, spec)) else {
+					return Vec::new();
+				};
+				(format!("{name}[]"), items)
+			}
+			_ => (name.to_string(), schema),
+		};
+		match Self::from_schema(schema, spec, depth - 1) {
+			Some(children) => children
+				.into_iter()
+				.map(|mut child| {
+					child.name = match child.name.split_once('[') {
+						Some((head, rest)) => format!("{name}[{head}][{rest}"),
+						None => format!("{name}[{}]", child.name),
 					};
-					params.push(Self {
-						name: name.clone(),
-						required,
-						source: ParamSource::Body,
-						description,
-					});
+					child.required &= required;
+					// Properties of a deprecated property are deprecated with it
+					child.deprecated |= deprecated;
+					child
+				})
+				.collect(),
+			// Arrays of plain values are appended to item by item
+			None if name.ends_with("[]") => vec![Self::body_param(&name, required, Some(schema))],
+			None => Vec::new(),
+		}
+	}
+
+	/// Turn a body param into a field of a form body, binary strings into
+	/// file parts
+	pub fn into_form(mut self) -> Self {
+		let binary = self.schema_type.as_deref().is_some_and(|schema_type| {
+			schema_type.ends_with("string (binary)") || schema_type.ends_with("string (base64)")
+		});
+		self.source = if binary { ParamSource::File } else { ParamSource::Form };
+		self
+	}
+
+	/// How deep a body param is nested into objects and arrays
+	pub fn nesting(&self) -> usize { self.name.matches('[').count() }
+
+	/// Merge the params of the `allOf` members that are objects
+	///
+	/// A member may only list `required` properties that other members declare,
+	/// so the required names of all members apply to the merged params.
+	fn merge_members(
+		members: &[ReferenceOr<Schema>],
+		spec: &OpenAPI,
+		depth: usize,
+	) -> Option<Vec<Self>> {
+		let members: Vec<_> = members.iter().filter_map(|member| resolve(member, spec)).collect();
+		let required: Vec<&String> = members.iter().flat_map(|member| required_names(member)).collect();
+		let mut params = members.iter().filter_map(|member| Self::from_schema(member, spec, depth - 1));
+		let mut merged = params.next()?;
+		for more in params {
+			merge(&mut merged, more);
+		}
+		for param in &mut merged {
+			param.required |= required.contains(&&param.name);
+		}
+		Some(merged)
+	}
+
+	/// Unite the params of the `oneOf` or `anyOf` variants that are objects
+	fn unite_variants(
+		variants: &[ReferenceOr<Schema>],
+		spec: &OpenAPI,
+		depth: usize,
+	) -> Option<Vec<Self>> {
+		let variants: Vec<_> = variants
+			.iter()
+			.enumerate()
+			.filter_map(|(i, variant)| {
+				let label = match variant {
+					ReferenceOr::Reference { reference } => {
+						reference.rsplit('/').next().unwrap_or(reference).to_string()
+					}
+					ReferenceOr::Item(schema) => {
+						schema.schema_data.title.clone().unwrap_or_else(|| format!("variant {}", i + 1))
+					}
+				};
+				let params = Self::from_schema(resolve(variant, spec)?, spec, depth - 1)?;
+				Some((label, params))
+			})
+			.collect();
+		if variants.is_empty() {
+			return None;
+		}
+
+		// Params with the variants that have them
+		let mut united: Vec<(Self, Vec<&str>)> = Vec::new();
+		for (label, params) in &variants {
+			for param in params {
+				match united.iter_mut().find(|(united, _)| united.name == param.name) {
+					Some((united, labels)) => {
+						united.required &= param.required;
+						fill(united, param);
+						labels.push(label);
+					}
+					None => united.push((param.clone(), vec![label])),
 				}
-				Ok(params)
 			}
-			_ => Err("Schema must be an object type".to_string()),
 		}
+		let params = united
+			.into_iter()
+			.map(|(mut param, labels)| {
+				if labels.len() < variants.len() {
+					param.required = false;
+					let description = param.description.as_deref().unwrap_or(&param.name);
+					param.description = Some(format!("{} ({} only)", description, labels.join(", ")));
+				}
+				param
+			})
+			.collect();
+		Some(params)
+	}
+}
+
+/// Add params to those of an object schema, params that are already there
+/// are required when either is
+/// The names a schema lists as `required`
+fn required_names(schema: &Schema) -> &[String] {
+	match &schema.schema_kind {
+		SchemaKind::Type(Type::Object(object_type)) => &object_type.required,
+		SchemaKind::Any(any) => &any.required,
+		_ => &[],
+	}
+}
+
+fn merge(params: &mut Vec<Param>, more: Vec<Param>) {
+	for param in more {
+		match params.iter_mut().find(|existing| existing.name == param.name) {
+			Some(existing) => {
+				existing.required |= param.required;
+				fill(existing, &param);
+			}
+			None => params.push(param),
+		}
+	}
+}
+
+/// Take the details that a param lacks from another declaration of it
+fn fill(param: &mut Param, other: &Param) {
+	if param.description.is_none() {
+		param.description = other.description.clone();
+	}
+	if param.default.is_none() {
+		param.default = other.default.clone();
+	}
+	if param.schema_type.is_none() {
+		param.schema_type = other.schema_type.clone();
+	}
+	if param.enum_values.is_empty() {
+		param.enum_values = other.enum_values.clone();
+	}
+	for example in &other.examples {
+		if !param.examples.contains(example) {
+			param.examples.push(example.clone());
+		}
+	}
+}
+
+fn resolve<'a>(schema: &'a ReferenceOr<Schema>, spec: &'a OpenAPI) -> Option<&'a Schema> {
+	match schema {
+		ReferenceOr::Item(schema) => Some(schema),
+		ReferenceOr::Reference { reference } => resolve_schema_reference(reference, spec).ok(),
+	}
+}
+
+fn resolve_boxed<'a>(
+	schema: &'a ReferenceOr<Box<Schema>>,
+	spec: &'a OpenAPI,
+) -> Option<&'a Schema> {
+	match schema {
+		ReferenceOr::Item(schema) => Some(schema),
+		ReferenceOr::Reference { reference } => resolve_schema_reference(reference, spec).ok(),
 	}
 }
@@ -0,0 +1,93 @@
+use anyhow::{Context, Result, anyhow};
+use regex_automata::meta::Regex;
+
+/// How a filter matches the paths of endpoints, in our `:param` format
+#[derive(Debug, Clone)]
+pub enum PathFilter {
+	/// Paths that contain the text
+	Substring(String),
+	/// Paths a regular expression matches anywhere in, anchor it with `^` and
+	/// `$` to match whole paths
+	Regex(Regex),
+}
+
+impl PathFilter {
+	pub fn regex(pattern: &str) -> Result<Self> {
+		let regex = Regex::new(pattern).map_err(|e| {
+			// The syntax error says what is wrong, the build error only that
+			// the pattern failed
+			let cause = std::error::Error::source(&e).map_or(e.to_string(), ToString::to_string);
+			anyhow!("Invalid regex '{}': {}", pattern, cause)
+		})?;
+		Ok(Self::Regex(regex))
+	}
+
+	/// A glob matching whole paths, where `*` matches within a path segment,
+	/// `**` across segments and `?` a single character
+	pub fn glob(pattern: &str) -> Result<Self> {
+		let mut regex = String::from("^");
+		let mut chars = pattern.chars().peekable();
+		while let Some(c) = chars.next() {
+			match c {
+				'*' if chars.next_if_eq(&'*').is_some() => regex.push_str(".*"),
+				'*' => regex.push_str("[^/]*"),
+				'?' => regex.push_str("[^/]"),
+				c => {
+					if "\\.+*?()|[]{}^$#&-~".contains(c) {
+						regex.push('\\');
+					}
+					regex.push(c);
+				}
+			}
+		}
+		regex.push('$');
+		Self::regex(&regex).with_context(|| format!("Invalid glob '{}'", pattern))
+	}
+
+	pub fn matches(&self, path: &str) -> bool {
+		match self {
+			PathFilter::Substring(text) => path.contains(text.as_str()),
+			PathFilter::Regex(regex) => regex.is_match(path),
+		}
+	}
+}
+
+impl From<&str> for PathFilter {
+	fn from(text: &str) -> Self { PathFilter::Substring(text.to_string()) }
+}
+
+impl From<String> for PathFilter {
+	fn from(text: String) -> Self { PathFilter::Substring(text) }
+}
+
+impl From<&PathFilter> for PathFilter {
+	fn from(filter: &PathFilter) -> Self { filter.clone() }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_matches() {
+		let substring = PathFilter::from("orders");
+		assert!(substring.matches("/v2/shops/:id/orders"));
+		assert!(!substring.matches("/v2/refunds"));
+
+		let regex = PathFilter::regex("^/v2/.*/(orders|refunds)").unwrap();
+		assert!(regex.matches("/v2/shops/:id/orders"));
+		assert!(regex.matches("/v2/shops/:id/refunds/:refundId"));
+		assert!(!regex.matches("/v1/shops/:id/orders"));
+		assert!(PathFilter::regex("(orders").is_err());
+
+		let glob = PathFilter::glob("/v2/*/orders").unwrap();
+		assert!(glob.matches("/v2/shops/orders"));
+		assert!(!glob.matches("/v2/shops/:id/orders"));
+		let glob = PathFilter::glob("/v2/**/orders").unwrap();
+		assert!(glob.matches("/v2/shops/:id/orders"));
+		let glob = PathFilter::glob("/users/:id?").unwrap();
+		assert!(glob.matches("/users/:ids"));
+		assert!(!glob.matches("/users/:id"));
+		assert!(PathFilter::glob("/files/(1).txt").unwrap().matches("/files/(1).txt"));
+	}
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Trie over the path segments of endpoints, finding the endpoints a typed
+/// path leads to without scanning all of them
+///
+/// Endpoints are referred to by their position in [`super::EndPoints`]. A
+/// `:param` segment of an endpoint path matches any typed value, so
+/// `/users/42` leads to `/users/:id`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PathIndex {
+	/// Number of indexed endpoints, a persisted index of another number of
+	/// endpoints is stale
+	len: usize,
+	root: Node,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct Node {
+	/// Endpoints whose path ends at this node
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	endpoints: Vec<usize>,
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	children: BTreeMap<String, Node>,
+}
+
+impl PathIndex {
+	pub fn new<'a>(paths: impl IntoIterator<Item = &'a str>) -> Self {
+		let mut index = Self::default();
+		for (i, path) in paths.into_iter().enumerate() {
+			let node = segments(path).fold(&mut index.root, |node, segment| {
+				node.children.entry(segment.to_string()).or_default()
+			});
+			node.endpoints.push(i);
+			index.len += 1;
+		}
+		index
+	}
+
+	pub fn len(&self) -> usize { self.len }
+
+	/// Positions of the endpoints whose path starts with `prefix`, in order
+	///
+	/// The last segment of `prefix` may be partially typed.
+	pub fn prefix(&self, prefix: &str) -> Vec<usize> {
+		let segments: Vec<_> = segments(prefix).collect();
+		let mut found = Vec::new();
+		self.root.collect_prefix(&segments, &mut found);
+		found.sort_unstable();
+		found
+	}
+
+	/// Positions of the endpoints whose path `path` matches as a whole, the
+	/// paths with the most literal segments first
+	///
+	/// A typed value such as `42` matches a `:param` segment.
+	pub fn matches(&self, path: &str) -> Vec<usize> {
+		let segments: Vec<_> = segments(path).collect();
+		let mut found = Vec::new();
+		self.root.collect_matches(&segments, 0, &mut found);
+		found.sort_unstable_by_key(|&(literals, i)| (std::cmp::Reverse(literals), i));
+		found.into_iter().map(|(_, i)| i).collect()
+	}
+}
+
+impl Node {
+	fn collect_prefix(&self, segments: &[&str], found: &mut Vec<usize>) {
+		match segments {
+			[] => self.collect_all(found),
+			[last] => {
+				for (segment, child) in &self.children {
+					if segment.starts_with(last) || is_template(segment) {
+						child.collect_all(found);
+					}
+				}
+			}
+			[first, rest @ ..] => {
+				for (segment, child) in &self.children {
+					if segment == first || is_template(segment) {
+						child.collect_prefix(rest, found);
+					}
+				}
+			}
+		}
+	}
+
+	/// Collect the endpoints `segments` lead to along with the number of
+	/// literal segments of their path
+	fn collect_matches(&self, segments: &[&str], literals: usize, found: &mut Vec<(usize, usize)>) {
+		let Some((first, rest)) = segments.split_first() else {
+			found.extend(self.endpoints.iter().map(|&i| (literals, i)));
+			return;
+		};
+		for (segment, child) in &self.children {
+			if segment == first {
+				child.collect_matches(rest, literals + 1, found);
+			} else if is_template(segment) {
+				child.collect_matches(rest, literals, found);
+			}
+		}
+	}
+
+	fn collect_all(&self, found: &mut Vec<usize>) {
+		found.extend(&self.endpoints);
+		for child in self.children.values() {
+			child.collect_all(found);
+		}
+	}
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+	path.strip_prefix('/').unwrap_or(path).split('/')
+}
+
+fn is_template(segment: &str) -> bool { segment.starts_with(':') }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_prefix() {
+		let paths = ["/users", "/users/:id", "/users/:id/orders", "/users/me", "/orders", "/"];
+		let index = PathIndex::new(paths);
+		assert_eq!(index.len(), 6);
+		assert_eq!(index.prefix("/users"), [0, 1, 2, 3]);
+		assert_eq!(index.prefix("/us"), [0, 1, 2, 3]);
+		assert_eq!(index.prefix("/users/"), [1, 2, 3]);
+		// Typed values lead to templated segments as well as to literal ones
+		assert_eq!(index.prefix("/users/42/orders"), [2]);
+		assert_eq!(index.prefix("/users/me"), [1, 2, 3]);
+		assert_eq!(index.prefix("/users/:id"), [1, 2]);
+		assert_eq!(index.prefix(""), [0, 1, 2, 3, 4, 5]);
+		assert!(index.prefix("/products").is_empty());
+	}
+
+	#[test]
+	fn test_matches() {
+		let paths = ["/users", "/users/:id", "/users/:id/posts", "/users/me", "/users/:id"];
+		let index = PathIndex::new(paths);
+		assert_eq!(index.matches("/users/123/posts"), [2]);
+		assert_eq!(index.matches("/users/123"), [1, 4]);
+		// Literal segments win over templated ones
+		assert_eq!(index.matches("/users/me"), [3, 1, 4]);
+		assert_eq!(index.matches("/users/:id"), [1, 4]);
+		assert!(index.matches("/users/123/comments").is_empty());
+		assert!(index.matches("/use").is_empty());
+	}
+}
@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value, json};
+use tracing::debug;
+
+use super::endpoint::EndPoint;
+use super::param::ParamSource;
+use super::{EndPoints, Param};
+
+/// Collection variables holding the base URL of the requests
+const BASE_URL_VARIABLES: &[&str] = &["baseUrl", "base_url", "baseURL", "url", "host"];
+
+/// Headers HTTPie sets by itself
+const SKIPPED_HEADERS: &[&str] = &["content-type", "accept", "content-length", "host"];
+
+/// Names with an optional example value
+type NamedValues = Vec<(String, Option<String>)>;
+
+/// Whether a document is a Postman v2 collection
+pub fn is_postman(document: &Value) -> bool {
+	document
+		.pointer("/info/schema")
+		.and_then(Value::as_str)
+		.is_some_and(|schema| schema.contains("schema.getpostman.com"))
+}
+
+/// Convert a Postman v2.1 collection to an OpenAPI 3.0 document
+///
+/// Every request becomes an operation named after the request and tagged with
+/// its folder. URLs are made relative to the `{{baseUrl}}` variable, which also
+/// becomes the server, and `:name` or `{{name}}` segments become path params.
+/// Query params, headers and JSON bodies are kept with their values as
+/// examples. When several requests share a method and path the first one wins.
+pub fn convert(document: Value) -> Value {
+	debug!("Converting Postman collection to OpenAPI 3");
+	let variables = variables(document.get("variable"));
+	let base_url = BASE_URL_VARIABLES.iter().find_map(|name| variables.get(*name)).cloned();
+
+	let mut paths = Map::new();
+	let mut tags = Vec::new();
+	collect_items(document.get("item"), None, &mut paths, &mut tags);
+
+	let name = document.pointer("/info/name").and_then(Value::as_str).unwrap_or("Postman collection");
+	let mut result = Map::new();
+	result.insert("openapi".to_string(), json!("3.0.3"));
+	result.insert("info".to_string(), json!({ "title": name, "version": "1.0.0" }));
+	if let Some(description) = document.pointer("/info/description").filter(|d| d.is_string()) {
+		result["info"]["description"] = description.clone();
+	}
+	if let Some(base_url) = base_url.filter(|url| url.starts_with("http")) {
+		result.insert("servers".to_string(), json!([{ "url": base_url }]));
+	}
+	let tags = tags.into_iter().map(|name| json!({ "name": name })).collect();
+	result.insert("tags".to_string(), Value::Array(tags));
+	result.insert("paths".to_string(), Value::Object(paths));
+	Value::Object(result)
+}
+
+/// Get the enabled `key`/`value` pairs of a Postman variable list
+fn variables(list: Option<&Value>) -> HashMap<String, String> {
+	enabled(list)
+		.filter_map(|variable| {
+			let key = variable.get("key")?.as_str()?;
+			let value = variable.get("value")?.as_str()?;
+			Some((key.to_string(), value.to_string()))
+		})
+		.collect()
+}
+
+/// Iterate the entries of a list that are not disabled
+fn enabled(list: Option<&Value>) -> impl Iterator<Item = &Value> {
+	list
+		.and_then(Value::as_array)
+		.into_iter()
+		.flatten()
+		.filter(|entry| entry.get("disabled") != Some(&json!(true)))
+}
+
+fn collect_items(
+	items: Option<&Value>,
+	folder: Option<&str>,
+	paths: &mut Map<String, Value>,
+	tags: &mut Vec<String>,
+) {
+	for item in items.and_then(Value::as_array).into_iter().flatten() {
+		let name = item.get("name").and_then(Value::as_str);
+		if item.get("item").is_some() {
+			// Folders group requests like tags
+			if let Some(name) = name
+				&& !tags.iter().any(|tag| tag == name)
+			{
+				tags.push(name.to_string());
+			}
+			collect_items(item.get("item"), name.or(folder), paths, tags);
+		} else if let Some(request) = item.get("request") {
+			add_request(request, name, folder, paths);
+		}
+	}
+}
+
+fn add_request(
+	request: &Value,
+	name: Option<&str>,
+	folder: Option<&str>,
+	paths: &mut Map<String, Value>,
+) {
+	// A request may be given as a bare URL string
+	let (method, url, headers, body) = match request {
+		Value::String(url) => ("get".to_string(), json!(url), None, None),
+		request => (
+			request.get("method").and_then(Value::as_str).unwrap_or("GET").to_lowercase(),
+			request.get("url").cloned().unwrap_or(Value::Null),
+			request.get("header"),
+			request.get("body"),
+		),
+	};
+	let Some((path, path_params, query)) = parse_url(&url) else {
+		debug!("Skipping Postman request without a URL: {:?}", name);
+		return;
+	};
+
+	let mut parameters: Vec<Value> = path_params
+		.into_iter()
+		.map(|(name, example)| parameter(&name, "path", true, example))
+		.collect();
+	parameters
+		.extend(query.into_iter().map(|(name, example)| parameter(&name, "query", false, example)));
+	for header in enabled(headers) {
+		let Some(key) = header.get("key").and_then(Value::as_str) else {
+			continue;
+		};
+		if !SKIPPED_HEADERS.contains(&key.to_lowercase().as_str()) {
+			let example = header.get("value").and_then(Value::as_str).map(str::to_string);
+			parameters.push(parameter(key, "header", false, example));
+		}
+	}
+
+	let mut operation = Map::new();
+	if let Some(name) = name {
+		operation.insert("summary".to_string(), json!(name));
+	}
+	if let Some(folder) = folder {
+		operation.insert("tags".to_string(), json!([folder]));
+	}
+	operation.insert("parameters".to_string(), Value::Array(parameters));
+	if let Some(request_body) = body.and_then(request_body) {
+		operation.insert("requestBody".to_string(), request_body);
+	}
+	operation.insert("responses".to_string(), json!({}));
+
+	let item = paths.entry(path).or_insert_with(|| json!({}));
+	if let Value::Object(item) = item {
+		item.entry(method).or_insert(Value::Object(operation));
+	}
+}
+
+/// A string parameter of an imported request, with its example unless it is
+/// empty or a Postman variable
+pub(super) fn parameter(
+	name: &str,
+	location: &str,
+	required: bool,
+	example: Option<String>,
+) -> Value {
+	let mut parameter = json!({
+		"name": name,
+		"in": location,
+		"required": required,
+		"schema": { "type": "string" },
+	});
+	if let Some(example) = example.filter(|example| !example.is_empty() && !is_variable(example)) {
+		parameter["example"] = json!(example);
+	}
+	parameter
+}
+
+/// Parse a Postman URL, given as a string or as an object with `raw`, `path`,
+/// `query` and `variable`, into an OpenAPI path, its path params and the
+/// query params with their example values
+fn parse_url(url: &Value) -> Option<(String, NamedValues, NamedValues)> {
+	let raw = match url {
+		Value::String(raw) => raw.as_str(),
+		url => url.get("raw").and_then(Value::as_str).unwrap_or_default(),
+	};
+	let segments: Vec<String> = match url.get("path") {
+		Some(Value::Array(segments)) => {
+			segments.iter().filter_map(|segment| segment.as_str().map(str::to_string)).collect()
+		}
+		Some(Value::String(path)) => path.split('/').map(str::to_string).collect(),
+		_ => raw_path(raw)?.split('/').map(str::to_string).collect(),
+	};
+
+	let examples = variables(url.get("variable"));
+	let mut path_params = Vec::new();
+	let mut path = String::new();
+	for segment in segments.iter().filter(|segment| !segment.is_empty()) {
+		let name = segment
+			.strip_prefix(':')
+			.or_else(|| segment.strip_prefix("{{").and_then(|s| s.strip_suffix("}}")));
+		path.push('/');
+		match name {
+			Some(name) => {
+				path.push_str(&format!("{{{name}}}"));
+				let example = examples.get(name).cloned();
+				path_params.push((name.to_string(), example));
+			}
+			None => path.push_str(segment),
+		}
+	}
+	if path.is_empty() {
+		path.push('/');
+	}
+
+	let query = match url.get("query") {
+		Some(query) => enabled(Some(query))
+			.filter_map(|param| {
+				let key = param.get("key")?.as_str()?.to_string();
+				Some((key, param.get("value").and_then(Value::as_str).map(str::to_string)))
+			})
+			.collect(),
+		None => raw
+			.split_once('?')
+			.map(|(_, query)| query)
+			.unwrap_or_default()
+			.split('&')
+			.filter(|pair| !pair.is_empty())
+			.map(|pair| match pair.split_once('=') {
+				Some((key, value)) => (key.to_string(), Some(value.to_string())),
+				None => (pair.to_string(), None),
+			})
+			.collect(),
+	};
+	Some((path, path_params, query))
+}
+
+/// Get the path of a raw URL, dropping a leading `{{baseUrl}}` variable or
+/// scheme and host
+fn raw_path(raw: &str) -> Option<&str> {
+	let raw = raw.split(['?', '#']).next()?;
+	if raw.is_empty() {
+		return None;
+	}
+	let rest = match raw.split_once("://") {
+		Some((_, rest)) => rest.split_once('/').map(|(_, path)| path).unwrap_or_default(),
+		None if raw.starts_with("{{") => {
+			raw.split_once("}}").map(|(_, path)| path.trim_start_matches('/')).unwrap_or_default()
+		}
+		None => raw.trim_start_matches('/'),
+	};
+	Some(rest)
+}
+
+fn is_variable(value: &str) -> bool { value.starts_with("{{") && value.ends_with("}}") }
+
+/// Build a request body from a Postman body, keeping JSON bodies as a schema
+/// with the values as examples and form bodies as form media types
+fn request_body(body: &Value) -> Option<Value> {
+	let (media_type, schema) = match body.get("mode").and_then(Value::as_str)? {
+		"raw" => {
+			let raw = body.get("raw").and_then(Value::as_str)?;
+			let value: Value = serde_json::from_str(raw).ok()?;
+			("application/json", schema_of(&value))
+		}
+		mode @ ("urlencoded" | "formdata") => {
+			let properties: Map<String, Value> = enabled(body.get(mode))
+				.filter_map(|field| {
+					let key = field.get("key")?.as_str()?;
+					let mut schema = json!({ "type": "string" });
+					if field.get("type").and_then(Value::as_str) == Some("file") {
+						schema["format"] = json!("binary");
+					} else if let Some(value) = field.get("value").and_then(Value::as_str) {
+						schema["example"] = json!(value);
+					}
+					Some((key.to_string(), schema))
+				})
+				.collect();
+			let media_type = match mode {
+				"urlencoded" => "application/x-www-form-urlencoded",
+				_ => "multipart/form-data",
+			};
+			(media_type, json!({ "type": "object", "properties": properties }))
+		}
+		_ => return None,
+	};
+	Some(json!({ "content": { media_type: { "schema": schema } } }))
+}
+
+/// Infer a schema from an example value
+pub(super) fn schema_of(value: &Value) -> Value {
+	match value {
+		Value::Object(map) => {
+			let properties: Map<String, Value> =
+				map.iter().map(|(key, value)| (key.clone(), schema_of(value))).collect();
+			json!({ "type": "object", "properties": properties })
+		}
+		Value::Array(items) => match items.first() {
+			Some(item) => json!({ "type": "array", "items": schema_of(item) }),
+			None => json!({ "type": "array", "items": {} }),
+		},
+		Value::String(_) => json!({ "type": "string", "example": value }),
+		Value::Number(n) if n.is_i64() || n.is_u64() => json!({ "type": "integer", "example": value }),
+		Value::Number(_) => json!({ "type": "number", "example": value }),
+		Value::Bool(_) => json!({ "type": "boolean", "example": value }),
+		Value::Null => json!({ "nullable": true }),
+	}
+}
+
+/// Postman schema of exported collections
+const COLLECTION_SCHEMA: &str =
+	"https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Build a Postman v2.1 collection from the endpoints of an API
+///
+/// Requests are grouped into folders by their first tag and use the
+/// `{{baseUrl}}` collection variable. Params are filled with their default or
+/// first example value, and body params make up a JSON or form body.
+pub fn export(name: &str, base_url: &str, endpoints: &EndPoints) -> Value {
+	let mut folders: Vec<(Option<&str>, Vec<Value>)> = Vec::new();
+	for endpoint in endpoints.all() {
+		let folder = endpoint.tags.first().map(String::as_str);
+		let request = export_request(endpoint);
+		match folders.iter_mut().find(|(name, _)| *name == folder) {
+			Some((_, requests)) => requests.push(request),
+			None => folders.push((folder, vec![request])),
+		}
+	}
+	let items: Vec<Value> = folders
+		.into_iter()
+		.flat_map(|(folder, requests)| match folder {
+			Some(folder) => vec![json!({ "name": folder, "item": requests })],
+			None => requests,
+		})
+		.collect();
+	json!({
+		"info": { "name": name, "schema": COLLECTION_SCHEMA },
+		"variable": [{ "key": "baseUrl", "value": base_url }],
+		"item": items,
+	})
+}
+
+fn export_request(endpoint: &EndPoint) -> Value {
+	let value = |param: &Param| {
+		param.default.clone().or_else(|| param.examples.first().cloned()).unwrap_or_default()
+	};
+	let of_source = |source: ParamSource| endpoint.params.iter().filter(move |p| p.source == source);
+
+	let segments: Vec<&str> = endpoint.path.split('/').filter(|s| !s.is_empty()).collect();
+	let query: Vec<Value> =
+		of_source(ParamSource::Query).map(|p| json!({ "key": p.name, "value": value(p) })).collect();
+	let mut raw = format!("{{{{baseUrl}}}}{}", endpoint.path);
+	if !query.is_empty() {
+		let pairs = url::form_urlencoded::Serializer::new(String::new())
+			.extend_pairs(of_source(ParamSource::Query).map(|p| (p.name.clone(), value(p))))
+			.finish();
+		raw = format!("{raw}?{pairs}");
+	}
+	let mut url = json!({ "raw": raw, "host": ["{{baseUrl}}"], "path": segments });
+	if !query.is_empty() {
+		url["query"] = Value::Array(query);
+	}
+	let variables: Vec<Value> =
+		of_source(ParamSource::Path).map(|p| json!({ "key": p.name, "value": value(p) })).collect();
+	if !variables.is_empty() {
+		url["variable"] = Value::Array(variables);
+	}
+
+	let mut request = json!({
+		"method": endpoint.method.to_string(),
+		"header": of_source(ParamSource::Header)
+			.map(|p| json!({ "key": p.name, "value": value(p) }))
+			.collect::<Vec<_>>(),
+		"url": url,
+	});
+	let form: Vec<Value> =
+		of_source(ParamSource::Form).map(|p| json!({ "key": p.name, "value": value(p) })).collect();
+	let files: Vec<Value> =
+		of_source(ParamSource::File).map(|p| json!({ "key": p.name, "type": "file" })).collect();
+	if !files.is_empty() {
+		let fields = form.into_iter().map(|mut field| {
+			field["type"] = json!("text");
+			field
+		});
+		request["body"] =
+			json!({ "mode": "formdata", "formdata": fields.chain(files).collect::<Vec<_>>() });
+	} else if !form.is_empty() {
+		request["body"] = json!({ "mode": "urlencoded", "urlencoded": form });
+	} else if endpoint.has_body_params() {
+		let body: Map<String, Value> = of_source(ParamSource::Body)
+			.map(|p| {
+				let value = p.default.as_ref().or_else(|| p.examples.first()).map(|v| {
+					p.is_raw_json()
+						.then(|| serde_json::from_str(v).ok())
+						.flatten()
+						.unwrap_or_else(|| json!(v))
+				});
+				(p.name.clone(), value.unwrap_or(Value::Null))
+			})
+			.collect();
+		let raw = serde_json::to_string_pretty(&body).unwrap_or_default();
+		request["body"] = json!({
+			"mode": "raw",
+			"raw": raw,
+			"options": { "raw": { "language": "json" } },
+		});
+	}
+
+	let name =
+		endpoint.summary.clone().unwrap_or_else(|| format!("{} {}", endpoint.method, endpoint.path));
+	json!({ "name": name, "request": request })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::openapi::EndPoints;
+
+	#[test]
+	fn test_convert() {
+		let collection = json!({
+			"info": {
+				"name": "Shop",
+				"schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+			},
+			"variable": [{ "key": "baseUrl", "value": "https://shop.example.com/api" }],
+			"item": [{
+				"name": "Pets",
+				"item": [
+					{
+						"name": "Get pet",
+						"request": {
+							"method": "GET",
+							"header": [
+								{ "key": "X-Request-Id", "value": "1" },
+								{ "key": "Accept", "value": "application/json" }
+							],
+							"url": {
+								"raw": "{{baseUrl}}/pets/:petId?expand=owner",
+								"host": ["{{baseUrl}}"],
+								"path": ["pets", ":petId"],
+								"query": [
+									{ "key": "expand", "value": "owner" },
+									{ "key": "debug", "value": "1", "disabled": true }
+								],
+								"variable": [{ "key": "petId", "value": "42" }]
+							}
+						}
+					},
+					{
+						"name": "Create pet",
+						"request": {
+							"method": "POST",
+							"url": "{{baseUrl}}/pets",
+							"body": { "mode": "raw", "raw": "{\"name\": \"Rex\", \"age\": 3}" }
+						}
+					}
+				]
+			}]
+		});
+		assert!(is_postman(&collection));
+		let openapi = convert(collection);
+		assert_eq!(openapi["info"]["title"], "Shop");
+		assert_eq!(openapi["servers"], json!([{ "url": "https://shop.example.com/api" }]));
+
+		let endpoints = EndPoints::try_from_openapi(openapi.to_string()).unwrap();
+		let get = endpoints.all().into_iter().find(|ep| ep.path == "/pets/:petId").unwrap();
+		assert_eq!(get.summary.as_deref(), Some("Get pet"));
+		assert_eq!(get.tags, ["Pets"]);
+		let params: Vec<_> = get.params.iter().map(|p| p.httpie_param_format()).collect();
+		assert_eq!(params, [":petId=", "expand==", "X-Request-Id:"]);
+		assert_eq!(get.params[0].examples, ["42"]);
+
+		let create = endpoints.find("/pets").unwrap();
+		let params: Vec<_> = create.params.iter().map(|p| p.name.as_str()).collect();
+		assert_eq!(params, ["age", "name"]);
+	}
+
+	#[test]
+	fn test_export_round_trip() {
+		let collection = json!({
+			"info": { "name": "Shop", "schema": COLLECTION_SCHEMA },
+			"variable": [{ "key": "baseUrl", "value": "https://shop.example.com/api" }],
+			"item": [{
+				"name": "Pets",
+				"item": [{
+					"name": "Update pet",
+					"request": {
+						"method": "PUT",
+						"header": [{ "key": "X-Request-Id", "value": "1" }],
+						"url": "{{baseUrl}}/pets/:petId?dry_run=true&q=big dog",
+						"body": { "mode": "raw", "raw": "{\"name\": \"Rex\", \"age\": 3}" }
+					}
+				}]
+			}]
+		});
+		let endpoints = EndPoints::try_from_openapi(convert(collection).to_string()).unwrap();
+		let exported = export("Shop", "https://shop.example.com/api", &endpoints);
+		assert!(is_postman(&exported));
+		assert_eq!(exported["variable"][0]["value"], "https://shop.example.com/api");
+		let request = &exported["item"][0]["item"][0]["request"];
+		assert_eq!(exported["item"][0]["name"], "Pets");
+		assert_eq!(request["method"], "PUT");
+		assert_eq!(request["url"]["raw"], "{{baseUrl}}/pets/:petId?dry_run=true&q=big+dog");
+		assert_eq!(request["url"]["variable"], json!([{ "key": "petId", "value": "" }]));
+		assert_eq!(request["header"], json!([{ "key": "X-Request-Id", "value": "1" }]));
+		assert_eq!(request["body"]["raw"], "{\n  \"age\": 3,\n  \"name\": \"Rex\"\n}");
+
+		let reimported = EndPoints::try_from_openapi(convert(exported).to_string()).unwrap();
+		assert_eq!(reimported.all().len(), 1);
+		let params = &reimported.find("/pets/:petId").unwrap().params;
+		assert_eq!(params.len(), 6);
+		let q = params.iter().find(|p| p.name == "q").unwrap();
+		assert_eq!(q.examples, ["big dog"]);
+	}
+}
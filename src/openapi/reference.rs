@@ -1,39 +1,96 @@
 use anyhow::{Result, anyhow};
-use openapiv3::{OpenAPI, ReferenceOr, Schema};
+use openapiv3::{
+	Components, Example, OpenAPI, Parameter, ReferenceOr, RequestBody, Response, Schema,
+};
 use tracing::{debug, warn};
 
 /// Resolve schema reference
+///
+/// Schemas that are references themselves, such as aliases of other schemas,
+/// are followed until a schema is found. Cyclic chains fail.
 pub fn resolve_schema_reference<'a>(reference: &str, spec: &'a OpenAPI) -> Result<&'a Schema> {
-	debug!("Attempting to resolve schema reference: {}", reference);
+	resolve(reference, "schemas", "schema", spec, |components, name| components.schemas.get(name))
+}
 
-	if !reference.starts_with("#/components/schemas/") {
-		warn!("Invalid schema reference path: {}", reference);
-		return Err(anyhow!("Not a schema reference: {}", reference));
-	}
+/// Resolve a reference to a parameter shared under `components.parameters`,
+/// following chained references like [`resolve_schema_reference`]
+pub fn resolve_parameter_reference<'a>(
+	reference: &str,
+	spec: &'a OpenAPI,
+) -> Result<&'a Parameter> {
+	resolve(reference, "parameters", "parameter", spec, |components, name| {
+		components.parameters.get(name)
+	})
+}
+
+/// Resolve a reference to a request body shared under
+/// `components.requestBodies`, following chained references like
+/// [`resolve_schema_reference`]
+pub fn resolve_request_body_reference<'a>(
+	reference: &str,
+	spec: &'a OpenAPI,
+) -> Result<&'a RequestBody> {
+	resolve(reference, "requestBodies", "request body", spec, |components, name| {
+		components.request_bodies.get(name)
+	})
+}
+
+/// Resolve a reference to a response shared under `components.responses`,
+/// following chained references like [`resolve_schema_reference`]
+pub fn resolve_response_reference<'a>(reference: &str, spec: &'a OpenAPI) -> Result<&'a Response> {
+	resolve(reference, "responses", "response", spec, |components, name| {
+		components.responses.get(name)
+	})
+}
 
-	let schema_name = reference.trim_start_matches("#/components/schemas/");
-	debug!("Looking for schema: {}", schema_name);
-
-	let schema = spec
-		.components
-		.as_ref()
-		.and_then(|components| components.schemas.get(schema_name))
-		.and_then(|schema_ref| match schema_ref {
-			ReferenceOr::Item(schema) => {
-				debug!("Found schema: {}", schema_name);
-				Some(schema)
+/// Resolve a reference to an example shared under `components.examples`
+pub fn resolve_example_reference<'a>(reference: &str, spec: &'a OpenAPI) -> Result<&'a Example> {
+	resolve(reference, "examples", "example", spec, |components, name| components.examples.get(name))
+}
+
+/// Follow a reference into the `section` of the components until an item of
+/// `kind` is found
+fn resolve<'a, T>(
+	reference: &str,
+	section: &str,
+	kind: &str,
+	spec: &'a OpenAPI,
+	lookup: impl Fn(&'a Components, &str) -> Option<&'a ReferenceOr<T>>,
+) -> Result<&'a T> {
+	debug!("Attempting to resolve {} reference: {}", kind, reference);
+	let prefix = format!("#/components/{}/", section);
+
+	let mut chain: Vec<&str> = Vec::new();
+	let mut reference = reference;
+	loop {
+		let Some(name) = reference.strip_prefix(&prefix) else {
+			warn!("Invalid {} reference path: {}", kind, reference);
+			return Err(anyhow!("Not a {} reference: {}", kind, reference));
+		};
+		if chain.contains(&name) {
+			chain.push(name);
+			warn!("Cyclic {} reference: {}", kind, chain.join(" -> "));
+			return Err(anyhow!("Cyclic {} reference: {}", kind, chain.join(" -> ")));
+		}
+		chain.push(name);
+		debug!("Looking for {}: {}", kind, name);
+
+		let item = spec.components.as_ref().and_then(|components| lookup(components, name));
+		match item {
+			Some(ReferenceOr::Item(item)) => {
+				debug!("Found {}: {}", kind, name);
+				return Ok(item);
 			}
-			ReferenceOr::Reference { .. } => {
-				warn!("Schema {} is a reference to another reference, which is not supported", schema_name);
-				None
+			Some(ReferenceOr::Reference { reference: next }) => {
+				debug!("The {} {} refers to {}", kind, name, next);
+				reference = next;
 			}
-		})
-		.ok_or_else(|| {
-			warn!("Schema not found: {}", schema_name);
-			anyhow!("Schema not found: {}", schema_name)
-		})?;
-
-	Ok(schema)
+			None => {
+				warn!("Could not find {} {}", kind, name);
+				return Err(anyhow!("No {} named '{}' in the components", kind, name));
+			}
+		}
+	}
 }
 
 #[cfg(test)]
@@ -98,6 +155,69 @@ mod tests {
 		assert!(result.is_err());
 	}
 
+	#[test]
+	fn test_resolve_chained_schema_reference() {
+		let spec = json!({
+				"openapi": "3.0.0",
+				"info": {
+						"title": "Test API",
+						"version": "1.0.0"
+				},
+				"paths": {},
+				"components": {
+						"schemas": {
+								"UserRef": { "$ref": "#/components/schemas/UserAlias" },
+								"UserAlias": { "$ref": "#/components/schemas/User" },
+								"User": {
+										"type": "object",
+										"properties": {
+												"name": { "type": "string" }
+										}
+								},
+								"Ping": { "$ref": "#/components/schemas/Pong" },
+								"Pong": { "$ref": "#/components/schemas/Ping" }
+						}
+				}
+		});
+
+		let spec = serde_json::from_value::<OpenAPI>(spec).unwrap();
+		let resolved = resolve_schema_reference("#/components/schemas/UserRef", &spec).unwrap();
+		match resolved.schema_kind {
+			openapiv3::SchemaKind::Type(openapiv3::Type::Object(_)) => {}
+			_ => unreachable!("Expected object type"),
+		}
+		let error = resolve_schema_reference("#/components/schemas/Ping", &spec).unwrap_err();
+		assert_eq!(error.to_string(), "Cyclic schema reference: Ping -> Pong -> Ping");
+	}
+
+	#[test]
+	fn test_resolve_parameter_reference() {
+		let spec = json!({
+				"openapi": "3.0.0",
+				"info": {
+						"title": "Test API",
+						"version": "1.0.0"
+				},
+				"paths": {},
+				"components": {
+						"parameters": {
+								"Page": {
+										"name": "page",
+										"in": "query",
+										"schema": { "type": "integer" }
+								},
+								"Offset": { "$ref": "#/components/parameters/Page" }
+						}
+				}
+		});
+
+		let spec = serde_json::from_value::<OpenAPI>(spec).unwrap();
+		let resolved = resolve_parameter_reference("#/components/parameters/Offset", &spec).unwrap();
+		assert_eq!(resolved.parameter_data_ref().name, "page");
+		assert!(resolve_parameter_reference("#/components/parameters/Limit", &spec).is_err());
+		assert!(resolve_parameter_reference("#/components/schemas/Page", &spec).is_err());
+	}
+
 	#[test]
 	fn test_resolve_schema_reference_invalid_path() {
 		let spec = json!({
@@ -0,0 +1,207 @@
+use openapiv3::{OpenAPI, ReferenceOr, Responses, Schema, SchemaKind, StatusCode, Type};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::param::schema_type;
+use super::reference::{resolve_response_reference, resolve_schema_reference};
+
+/// Levels of nested objects and arrays that the shape of a body goes into
+const SHAPE_DEPTH: usize = 4;
+
+/// A response that an operation documents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+	/// Status code such as `200`, a range such as `4XX`, or `default`
+	pub status: String,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub description: String,
+	/// Media type of the body, JSON ones are preferred
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub content_type: Option<String>,
+	/// Shape of the body, e.g. `{"id": "integer", "tags": ["string"]}`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub shape: Option<Value>,
+}
+
+impl Response {
+	/// Extract the responses of an operation, references to shared responses
+	/// resolved
+	pub fn extract(responses: &Responses, spec: &OpenAPI) -> Vec<Self> {
+		let statuses = responses.responses.iter().map(|(code, response)| {
+			let status = match code {
+				StatusCode::Code(code) => code.to_string(),
+				StatusCode::Range(range) => format!("{range}XX"),
+			};
+			(status, response)
+		});
+		let default = responses.default.iter().map(|response| ("default".to_string(), response));
+		statuses
+			.chain(default)
+			.filter_map(|(status, response)| {
+				let response = match response {
+					ReferenceOr::Item(response) => response,
+					ReferenceOr::Reference { reference } => {
+						resolve_response_reference(reference, spec).ok()?
+					}
+				};
+				let media_type = response
+					.content
+					.iter()
+					.find(|(content_type, _)| content_type.contains("json"))
+					.or_else(|| response.content.first());
+				let schema = media_type
+					.and_then(|(_, media_type)| media_type.schema.as_ref())
+					.and_then(|schema| resolve(schema, spec));
+				Some(Self {
+					status,
+					description: response.description.clone(),
+					content_type: media_type.map(|(content_type, _)| content_type.clone()),
+					shape: schema.map(|schema| shape(schema, spec, SHAPE_DEPTH)),
+				})
+			})
+			.collect()
+	}
+}
+
+/// Map the properties of an object schema to their own shapes and the items
+/// of an array schema into a list, other schemas become their type
+fn shape(schema: &Schema, spec: &OpenAPI, depth: usize) -> Value {
+	let leaf = || Value::String(schema_type(schema).unwrap_or_else(|| "any".to_string()));
+	if depth == 0 {
+		return leaf();
+	}
+	match &schema.schema_kind {
+		SchemaKind::Type(Type::Object(object_type)) if !object_type.properties.is_empty() => {
+			properties_shape(&object_type.properties, spec, depth)
+		}
+		SchemaKind::Any(any) if !any.properties.is_empty() => {
+			properties_shape(&any.properties, spec, depth)
+		}
+		SchemaKind::Type(Type::Array(array)) => {
+			let items = array.items.as_ref().and_then(|items| resolve_boxed(items, spec));
+			let items = items.map(|items| shape(items, spec, depth - 1));
+			Value::Array(vec![items.unwrap_or_else(|| Value::String("any".to_string()))])
+		}
+		SchemaKind::AllOf { all_of: members }
+		| SchemaKind::OneOf { one_of: members }
+		| SchemaKind::AnyOf { any_of: members } => {
+			// Members are united into one object, anything else takes the
+			// shape of the first member
+			let shapes: Vec<_> = members
+				.iter()
+				.filter_map(|member| resolve(member, spec))
+				.map(|member| shape(member, spec, depth))
+				.collect();
+			if !shapes.is_empty() && shapes.iter().all(Value::is_object) {
+				let mut united = Map::new();
+				for member in shapes.into_iter().filter_map(|shape| shape.as_object().cloned()) {
+					for (name, shape) in member {
+						united.entry(name).or_insert(shape);
+					}
+				}
+				return Value::Object(united);
+			}
+			shapes.into_iter().next().unwrap_or_else(leaf)
+		}
+		_ => leaf(),
+	}
+}
+
+fn properties_shape<'a>(
+	properties: impl IntoIterator<Item = (&'a String, &'a ReferenceOr<Box<Schema>>)>,
+	spec: &OpenAPI,
+	depth: usize,
+) -> Value {
+	let properties = properties.into_iter().map(|(name, property)| {
+		let shape = match resolve_boxed(property, spec) {
+			Some(property) => shape(property, spec, depth - 1),
+			None => Value::String("any".to_string()),
+		};
+		(name.clone(), shape)
+	});
+	Value::Object(properties.collect())
+}
+
+fn resolve<'a>(schema: &'a ReferenceOr<Schema>, spec: &'a OpenAPI) -> Option<&'a Schema> {
+	match schema {
+		ReferenceOr::Item(schema) => Some(schema),
+		ReferenceOr::Reference { reference } => resolve_schema_reference(reference, spec).ok(),
+	}
+}
+
+fn resolve_boxed<'a>(
+	schema: &'a ReferenceOr<Box<Schema>>,
+	spec: &'a OpenAPI,
+) -> Option<&'a Schema> {
+	match schema {
+		ReferenceOr::Item(schema) => Some(schema),
+		ReferenceOr::Reference { reference } => resolve_schema_reference(reference, spec).ok(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use crate::openapi::EndPoints;
+
+	#[test]
+	fn test_extract() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /users/{id}:
+    get:
+      responses:
+        "200":
+          description: The user
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/User"
+        "404":
+          $ref: "#/components/responses/NotFound"
+        default:
+          description: Unexpected error
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        id:
+          type: integer
+          format: int64
+        tags:
+          type: array
+          items:
+            type: string
+        address:
+          allOf:
+            - type: object
+              properties:
+                city:
+                  type: string
+  responses:
+    NotFound:
+      description: No such user
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let responses = &endpoints.find("/users/:id").unwrap().responses;
+		let statuses: Vec<_> = responses.iter().map(|r| r.status.as_str()).collect();
+		assert_eq!(statuses, ["200", "404", "default"]);
+		assert_eq!(responses[0].content_type.as_deref(), Some("application/json"));
+		assert_eq!(
+			responses[0].shape,
+			Some(json!({
+				"id": "integer (int64)",
+				"tags": ["string"],
+				"address": { "city": "string" },
+			}))
+		);
+		assert_eq!(responses[1].description, "No such user");
+		assert_eq!(responses[1].shape, None);
+	}
+}
@@ -0,0 +1,97 @@
+use std::thread;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use reqwest::StatusCode;
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::RETRY_AFTER;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::time::parse_duration;
+
+/// Longest `Retry-After` that is waited for, longer ones fail the download
+const MAX_RETRY_AFTER: u64 = 60;
+
+/// How spec downloads are retried after connection errors, timeouts and
+/// responses of rate limited or failing servers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+	/// Number of retries after the first attempt, 0 disables retrying
+	pub retries: u32,
+	/// Delay before the first retry, such as `1s`, doubled for every retry
+	/// unless the server sends `Retry-After`
+	pub backoff: String,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self { Self { retries: 2, backoff: "1s".to_string() } }
+}
+
+impl RetryPolicy {
+	/// Send a request, retrying it while it fails with a transient error
+	pub fn send(&self, request: &RequestBuilder) -> anyhow::Result<Response> {
+		let mut attempt = 0;
+		loop {
+			let result = request
+				.try_clone()
+				.ok_or_else(|| anyhow!("Request with a streamed body cannot be retried"))?
+				.send();
+			if attempt >= self.retries {
+				return Ok(result?);
+			}
+			let delay = match &result {
+				Ok(response) if is_transient(response.status()) => match retry_after(response) {
+					Some(delay) if delay > MAX_RETRY_AFTER => return Ok(result?),
+					Some(delay) => Duration::from_secs(delay),
+					None => self.delay(attempt),
+				},
+				Err(e) if e.is_connect() || e.is_timeout() => self.delay(attempt),
+				_ => return Ok(result?),
+			};
+			match &result {
+				Ok(response) => warn!("HTTP {}, retrying in {:?}", response.status(), delay),
+				Err(e) => warn!("{}, retrying in {:?}", e, delay),
+			}
+			thread::sleep(delay);
+			attempt += 1;
+		}
+	}
+
+	/// Exponential backoff before retry number `attempt`, counted from 0
+	fn delay(&self, attempt: u32) -> Duration {
+		let backoff = parse_duration(&self.backoff).unwrap_or_else(|e| {
+			warn!("Invalid download backoff: {:#}", e);
+			1
+		});
+		Duration::from_secs(backoff.saturating_mul(1 << attempt.min(16)))
+	}
+}
+
+/// Statuses of rate limits and of server errors
+fn is_transient(status: StatusCode) -> bool {
+	status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Seconds to wait according to a `Retry-After` header in seconds, dates are
+/// not supported and fall back to the backoff
+fn retry_after(response: &Response) -> Option<u64> {
+	response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_delay() {
+		let policy = RetryPolicy { retries: 3, backoff: "2s".to_string() };
+		let delays: Vec<_> = (0..3).map(|attempt| policy.delay(attempt).as_secs()).collect();
+		assert_eq!(delays, [2, 4, 8]);
+		assert!(is_transient(StatusCode::SERVICE_UNAVAILABLE));
+		assert!(is_transient(StatusCode::INTERNAL_SERVER_ERROR));
+		assert!(is_transient(StatusCode::TOO_MANY_REQUESTS));
+		assert!(!is_transient(StatusCode::NOT_FOUND));
+	}
+}
@@ -0,0 +1,204 @@
+use openapiv3::{APIKeyLocation, OpenAPI, Operation, ReferenceOr, SecurityScheme};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// A security scheme that an operation authenticates with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Security {
+	/// Name of the scheme under `components.securitySchemes`
+	pub scheme: String,
+	#[serde(flatten)]
+	pub kind: SecurityKind,
+	/// Scopes the operation needs, for OAuth2 and OpenID Connect
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SecurityKind {
+	/// A key sent as the header, query param or cookie `name`
+	ApiKey {
+		#[serde(rename = "in")]
+		location: KeyLocation,
+		name: String,
+	},
+	/// An `Authorization` header of an HTTP scheme such as `bearer` or `basic`
+	Http {
+		scheme: String,
+	},
+	#[serde(rename = "oauth2")]
+	OAuth2,
+	OpenIdConnect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyLocation {
+	Header,
+	Query,
+	Cookie,
+}
+
+/// The alternative ways an operation authenticates, each naming the schemes
+/// that are used together
+///
+/// The requirements of the operation override those of the spec. An empty
+/// alternative makes authentication optional, no alternatives at all mean
+/// that none is needed. Schemes missing from the components are skipped.
+pub fn requirements(operation: &Operation, spec: &OpenAPI) -> Vec<Vec<Security>> {
+	let requirements = operation.security.as_ref().or(spec.security.as_ref());
+	let schemes = spec.components.as_ref().map(|components| &components.security_schemes);
+	requirements
+		.into_iter()
+		.flatten()
+		.map(|requirement| {
+			requirement
+				.iter()
+				.filter_map(|(name, scopes)| match schemes.and_then(|schemes| schemes.get(name)) {
+					Some(ReferenceOr::Item(scheme)) => Some(Security {
+						scheme: name.clone(),
+						kind: SecurityKind::from(scheme),
+						scopes: scopes.clone(),
+					}),
+					_ => {
+						warn!("Unknown security scheme: {}", name);
+						None
+					}
+				})
+				.collect()
+		})
+		.collect()
+}
+
+impl std::fmt::Display for Security {
+	/// Describe the scheme, e.g. `apiKey: API key in header X-Api-Key`
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: ", self.scheme)?;
+		match &self.kind {
+			SecurityKind::ApiKey { location, name } => {
+				let location = match location {
+					KeyLocation::Header => "header",
+					KeyLocation::Query => "query param",
+					KeyLocation::Cookie => "cookie",
+				};
+				write!(f, "API key in {} {}", location, name)?;
+			}
+			SecurityKind::Http { scheme } => write!(f, "HTTP {} auth", scheme)?,
+			SecurityKind::OAuth2 => write!(f, "OAuth2")?,
+			SecurityKind::OpenIdConnect => write!(f, "OpenID Connect")?,
+		}
+		if !self.scopes.is_empty() {
+			write!(f, " ({})", self.scopes.join(", "))?;
+		}
+		Ok(())
+	}
+}
+
+impl From<&SecurityScheme> for SecurityKind {
+	fn from(scheme: &SecurityScheme) -> Self {
+		match scheme {
+			SecurityScheme::APIKey { location, name, .. } => Self::ApiKey {
+				location: match location {
+					APIKeyLocation::Header => KeyLocation::Header,
+					APIKeyLocation::Query => KeyLocation::Query,
+					APIKeyLocation::Cookie => KeyLocation::Cookie,
+				},
+				name: name.clone(),
+			},
+			SecurityScheme::HTTP { scheme, .. } => Self::Http { scheme: scheme.to_lowercase() },
+			SecurityScheme::OAuth2 { .. } => Self::OAuth2,
+			SecurityScheme::OpenIDConnect { .. } => Self::OpenIdConnect,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::openapi::EndPoints;
+
+	#[test]
+	fn test_requirements() {
+		let spec = r##"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+security:
+  - apiKey: []
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: ok
+    post:
+      security:
+        - token: []
+        - oauth: [write:pets]
+          apiKey: []
+      responses:
+        "200":
+          description: ok
+  /health:
+    get:
+      security: []
+      responses:
+        "200":
+          description: ok
+components:
+  securitySchemes:
+    apiKey:
+      type: apiKey
+      in: header
+      name: X-Api-Key
+    token:
+      type: http
+      scheme: Bearer
+    oauth:
+      type: oauth2
+      flows:
+        clientCredentials:
+          tokenUrl: https://auth.example.com/token
+          scopes:
+            write:pets: Change pets
+"##;
+		let endpoints = EndPoints::try_from_openapi(spec).unwrap();
+		let api_key = Security {
+			scheme: "apiKey".to_string(),
+			kind:   SecurityKind::ApiKey {
+				location: KeyLocation::Header,
+				name:     "X-Api-Key".to_string(),
+			},
+			scopes: Vec::new(),
+		};
+		let pets = endpoints.filter("/pets");
+		assert_eq!(pets[0].security, [vec![api_key.clone()]]);
+		assert_eq!(pets[1].security, [
+			vec![Security {
+				scheme: "token".to_string(),
+				kind:   SecurityKind::Http { scheme: "bearer".to_string() },
+				scopes: Vec::new(),
+			}],
+			vec![api_key, Security {
+				scheme: "oauth".to_string(),
+				kind:   SecurityKind::OAuth2,
+				scopes: vec!["write:pets".to_string()],
+			}],
+		]);
+		assert!(endpoints.find("/health").unwrap().security.is_empty());
+		let described: Vec<_> = pets[1].security.iter().flatten().map(Security::to_string).collect();
+		assert_eq!(described, [
+			"token: HTTP bearer auth",
+			"apiKey: API key in header X-Api-Key",
+			"oauth: OAuth2 (write:pets)"
+		]);
+
+		let cached = serde_json::to_value(&pets[0].security).unwrap();
+		assert_eq!(
+			cached,
+			serde_json::json!([[{ "scheme": "apiKey", "type": "apiKey", "in": "header", "name": "X-Api-Key" }]])
+		);
+	}
+}
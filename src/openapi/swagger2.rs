@@ -0,0 +1,447 @@
+use serde_json::{Map, Value, json};
+use tracing::debug;
+
+/// Operation keys of a Swagger 2 path item
+const OPERATIONS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// Parameter keys that move into the `schema` of an OpenAPI 3 parameter
+const SCHEMA_KEYS: &[&str] = &[
+	"type",
+	"format",
+	"items",
+	"enum",
+	"default",
+	"minimum",
+	"maximum",
+	"minLength",
+	"maxLength",
+	"pattern",
+];
+
+/// Whether a document is a Swagger 2.0 (OpenAPI 2) spec
+pub fn is_swagger2(document: &Value) -> bool {
+	document.get("swagger").and_then(Value::as_str).is_some_and(|v| v.starts_with("2."))
+}
+
+/// Convert a Swagger 2.0 document to an equivalent OpenAPI 3.0 document
+///
+/// `host`, `basePath` and `schemes` become the `servers` block, body and form
+/// parameters become request bodies, and `definitions`, `parameters`,
+/// `responses` and `securityDefinitions` move under `components`.
+pub fn convert(mut document: Value) -> Value {
+	debug!("Converting Swagger 2.0 document to OpenAPI 3");
+	rewrite_refs(&mut document);
+	let consumes = media_types(&document, "consumes");
+	let produces = media_types(&document, "produces");
+
+	let mut result = Map::new();
+	result.insert("openapi".to_string(), json!("3.0.3"));
+	for key in ["info", "tags", "externalDocs", "security"] {
+		if let Some(value) = document.get(key) {
+			result.insert(key.to_string(), value.clone());
+		}
+	}
+	result.insert("servers".to_string(), servers(&document));
+
+	let mut paths = Map::new();
+	for (path, item) in document.get("paths").and_then(Value::as_object).into_iter().flatten() {
+		paths.insert(path.clone(), convert_path_item(item, &consumes, &produces));
+	}
+	result.insert("paths".to_string(), Value::Object(paths));
+
+	let mut components = Map::new();
+	if let Some(definitions) = document.get("definitions") {
+		let mut schemas = definitions.clone();
+		fix_schema(&mut schemas);
+		components.insert("schemas".to_string(), schemas);
+	}
+	if let Some(parameters) = document.get("parameters").and_then(Value::as_object) {
+		let parameters = parameters
+			.iter()
+			.filter(|(_, param)| !is_body_param(param))
+			.map(|(name, param)| (name.clone(), convert_parameter(param)))
+			.collect();
+		components.insert("parameters".to_string(), Value::Object(parameters));
+	}
+	if let Some(responses) = document.get("responses").and_then(Value::as_object) {
+		let responses = responses
+			.iter()
+			.map(|(name, response)| (name.clone(), convert_response(response, &produces)))
+			.collect();
+		components.insert("responses".to_string(), Value::Object(responses));
+	}
+	if let Some(definitions) = document.get("securityDefinitions").and_then(Value::as_object) {
+		let schemes = definitions
+			.iter()
+			.map(|(name, definition)| (name.clone(), convert_security_scheme(definition)))
+			.collect();
+		components.insert("securitySchemes".to_string(), Value::Object(schemes));
+	}
+	if !components.is_empty() {
+		result.insert("components".to_string(), Value::Object(components));
+	}
+	Value::Object(result)
+}
+
+/// Build the servers from `schemes`, `host` and `basePath`
+fn servers(document: &Value) -> Value {
+	let base_path = document.get("basePath").and_then(Value::as_str).unwrap_or("");
+	let Some(host) = document.get("host").and_then(Value::as_str) else {
+		return json!([{ "url": if base_path.is_empty() { "/" } else { base_path } }]);
+	};
+	let schemes: Vec<&str> = document
+		.get("schemes")
+		.and_then(Value::as_array)
+		.map(|schemes| schemes.iter().filter_map(Value::as_str).collect())
+		.unwrap_or_default();
+	let schemes = if schemes.is_empty() { vec!["https"] } else { schemes };
+	schemes
+		.into_iter()
+		.map(|scheme| json!({ "url": format!("{scheme}://{host}{base_path}") }))
+		.collect()
+}
+
+fn convert_path_item(item: &Value, consumes: &[String], produces: &[String]) -> Value {
+	let mut result = Map::new();
+	copy_extensions(item, &mut result);
+	if let Some(parameters) = item.get("parameters").and_then(Value::as_array) {
+		// Path level body parameters cannot be expressed in OpenAPI 3
+		let parameters =
+			parameters.iter().filter(|param| !is_body_param(param)).map(convert_parameter).collect();
+		result.insert("parameters".to_string(), Value::Array(parameters));
+	}
+	for &method in OPERATIONS {
+		if let Some(operation) = item.get(method) {
+			result.insert(method.to_string(), convert_operation(operation, consumes, produces));
+		}
+	}
+	Value::Object(result)
+}
+
+fn convert_operation(operation: &Value, consumes: &[String], produces: &[String]) -> Value {
+	let mut result = Map::new();
+	for key in
+		["tags", "summary", "description", "operationId", "deprecated", "externalDocs", "security"]
+	{
+		if let Some(value) = operation.get(key) {
+			result.insert(key.to_string(), value.clone());
+		}
+	}
+	copy_extensions(operation, &mut result);
+	let consumes = override_media_types(operation, "consumes", consumes);
+	let produces = override_media_types(operation, "produces", produces);
+
+	let mut parameters = Vec::new();
+	let mut body = None;
+	let mut form_properties = Map::new();
+	let mut form_required = Vec::new();
+	let mut has_file = false;
+	for param in operation.get("parameters").and_then(Value::as_array).into_iter().flatten() {
+		match param.get("in").and_then(Value::as_str) {
+			Some("body") => body = Some(param),
+			Some("formData") => {
+				let Some(name) = param.get("name").and_then(Value::as_str) else {
+					continue;
+				};
+				let mut schema = parameter_schema(param);
+				copy_extensions(param, &mut schema);
+				has_file |= schema.get("format").and_then(Value::as_str) == Some("binary");
+				if let Some(description) = param.get("description") {
+					schema.insert("description".to_string(), description.clone());
+				}
+				if param.get("required").and_then(Value::as_bool).unwrap_or(false) {
+					form_required.push(json!(name));
+				}
+				form_properties.insert(name.to_string(), Value::Object(schema));
+			}
+			_ => parameters.push(convert_parameter(param)),
+		}
+	}
+	if !parameters.is_empty() {
+		result.insert("parameters".to_string(), Value::Array(parameters));
+	}
+
+	if let Some(body) = body {
+		let mut schema = body.get("schema").cloned().unwrap_or_else(|| json!({}));
+		fix_schema(&mut schema);
+		let content: Map<_, _> =
+			consumes.iter().map(|media_type| (media_type.clone(), json!({ "schema": schema }))).collect();
+		let mut request_body = json!({ "content": content });
+		for key in ["description", "required"] {
+			if let Some(value) = body.get(key) {
+				request_body[key] = value.clone();
+			}
+		}
+		result.insert("requestBody".to_string(), request_body);
+	} else if !form_properties.is_empty() {
+		let media_type = if has_file || consumes.iter().any(|m| m == "multipart/form-data") {
+			"multipart/form-data"
+		} else {
+			"application/x-www-form-urlencoded"
+		};
+		let mut schema = json!({ "type": "object", "properties": form_properties });
+		if !form_required.is_empty() {
+			schema["required"] = Value::Array(form_required);
+		}
+		result.insert(
+			"requestBody".to_string(),
+			json!({ "content": { media_type: { "schema": schema } } }),
+		);
+	}
+
+	let responses = operation
+		.get("responses")
+		.and_then(Value::as_object)
+		.into_iter()
+		.flatten()
+		.map(|(code, response)| (code.clone(), convert_response(response, &produces)))
+		.collect();
+	result.insert("responses".to_string(), Value::Object(responses));
+	Value::Object(result)
+}
+
+/// Convert a non-body parameter, moving its type information into a schema
+fn convert_parameter(param: &Value) -> Value {
+	if param.get("$ref").is_some() {
+		return param.clone();
+	}
+	let mut result = Map::new();
+	for key in ["name", "in", "description", "required"] {
+		if let Some(value) = param.get(key) {
+			result.insert(key.to_string(), value.clone());
+		}
+	}
+	if let Some(example) = param.get("x-example") {
+		result.insert("example".to_string(), example.clone());
+	}
+	copy_extensions(param, &mut result);
+	result.insert("schema".to_string(), Value::Object(parameter_schema(param)));
+	Value::Object(result)
+}
+
+/// Copy the vendor extensions of an object, which mean the same in OpenAPI 3
+fn copy_extensions(from: &Value, to: &mut Map<String, Value>) {
+	let extensions = from.as_object().into_iter().flatten().filter(|(key, _)| key.starts_with("x-"));
+	for (key, value) in extensions {
+		to.insert(key.clone(), value.clone());
+	}
+}
+
+fn parameter_schema(param: &Value) -> Map<String, Value> {
+	let mut schema = Value::Object(
+		SCHEMA_KEYS
+			.iter()
+			.filter_map(|&key| param.get(key).map(|value| (key.to_string(), value.clone())))
+			.collect(),
+	);
+	fix_schema(&mut schema);
+	match schema {
+		Value::Object(schema) => schema,
+		_ => unreachable!("schema is an object"),
+	}
+}
+
+fn convert_response(response: &Value, produces: &[String]) -> Value {
+	if response.get("$ref").is_some() {
+		return response.clone();
+	}
+	let description = response.get("description").cloned().unwrap_or_else(|| json!(""));
+	let mut result = json!({ "description": description });
+	if let Some(schema) = response.get("schema") {
+		let mut schema = schema.clone();
+		fix_schema(&mut schema);
+		let content: Map<_, _> =
+			produces.iter().map(|media_type| (media_type.clone(), json!({ "schema": schema }))).collect();
+		result["content"] = Value::Object(content);
+	}
+	result
+}
+
+/// Convert a security definition, `basic` becoming an HTTP scheme and the
+/// OAuth2 flow moving under `flows`
+fn convert_security_scheme(definition: &Value) -> Value {
+	let mut scheme = definition.clone();
+	let Some(object) = scheme.as_object_mut() else {
+		return scheme;
+	};
+	match object.get("type").and_then(Value::as_str) {
+		Some("basic") => {
+			object.insert("type".to_string(), json!("http"));
+			object.insert("scheme".to_string(), json!("basic"));
+		}
+		Some("oauth2") => {
+			let flow = match object.remove("flow").as_ref().and_then(Value::as_str) {
+				Some("password") => "password",
+				Some("application") => "clientCredentials",
+				Some("accessCode") => "authorizationCode",
+				_ => "implicit",
+			};
+			let mut settings = Map::new();
+			for key in ["authorizationUrl", "tokenUrl", "scopes"] {
+				if let Some(value) = object.remove(key) {
+					settings.insert(key.to_string(), value);
+				}
+			}
+			settings.entry("scopes").or_insert_with(|| json!({}));
+			object.insert("flows".to_string(), json!({ flow: settings }));
+		}
+		_ => {}
+	}
+	scheme
+}
+
+fn is_body_param(param: &Value) -> bool { param.get("in").and_then(Value::as_str) == Some("body") }
+
+/// Get the document level media types, defaulting to JSON
+fn media_types(document: &Value, key: &str) -> Vec<String> {
+	override_media_types(document, key, &["application/json".to_string()])
+}
+
+fn override_media_types(value: &Value, key: &str, default: &[String]) -> Vec<String> {
+	let media_types: Vec<String> = value
+		.get(key)
+		.and_then(Value::as_array)
+		.map(|types| types.iter().filter_map(Value::as_str).map(str::to_string).collect())
+		.unwrap_or_default();
+	if media_types.is_empty() { default.to_vec() } else { media_types }
+}
+
+/// Point references at their OpenAPI 3 locations
+fn rewrite_refs(value: &mut Value) {
+	match value {
+		Value::Object(map) => {
+			if let Some(Value::String(reference)) = map.get_mut("$ref") {
+				for (from, to) in [
+					("#/definitions/", "#/components/schemas/"),
+					("#/parameters/", "#/components/parameters/"),
+					("#/responses/", "#/components/responses/"),
+				] {
+					if let Some(name) = reference.strip_prefix(from) {
+						*reference = format!("{to}{name}");
+						break;
+					}
+				}
+			}
+			map.values_mut().for_each(rewrite_refs);
+		}
+		Value::Array(items) => items.iter_mut().for_each(rewrite_refs),
+		_ => {}
+	}
+}
+
+/// Replace schema constructs that only exist in Swagger 2, such as the `file`
+/// type and `x-nullable`
+fn fix_schema(value: &mut Value) {
+	match value {
+		Value::Object(map) => {
+			if map.get("type").and_then(Value::as_str) == Some("file") {
+				map.insert("type".to_string(), json!("string"));
+				map.insert("format".to_string(), json!("binary"));
+			}
+			if let Some(nullable) = map.remove("x-nullable") {
+				map.insert("nullable".to_string(), nullable);
+			}
+			map.values_mut().for_each(fix_schema);
+		}
+		Value::Array(items) => items.iter_mut().for_each(fix_schema),
+		_ => {}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn petstore() -> Value {
+		json!({
+			"swagger": "2.0",
+			"info": { "title": "Petstore", "version": "1.0.0" },
+			"host": "petstore.example.com",
+			"basePath": "/v2",
+			"schemes": ["https"],
+			"paths": {
+				"/pets/{petId}": {
+					"parameters": [
+						{ "name": "petId", "in": "path", "required": true, "type": "integer" }
+					],
+					"get": {
+						"summary": "Get a pet",
+						"parameters": [{ "name": "fields", "in": "query", "type": "string", "x-example": "name" }],
+						"responses": {
+							"200": { "description": "ok", "schema": { "$ref": "#/definitions/Pet" } }
+						}
+					},
+					"post": {
+						"consumes": ["application/x-www-form-urlencoded"],
+						"parameters": [
+							{ "name": "name", "in": "formData", "required": true, "type": "string" },
+							{ "name": "photo", "in": "formData", "type": "file" }
+						],
+						"responses": { "200": { "description": "ok" } }
+					},
+					"put": {
+						"x-httpie-oapi-hidden": true,
+						"parameters": [
+							{ "name": "pet", "in": "body", "required": true, "schema": { "$ref": "#/definitions/Pet" } }
+						],
+						"responses": { "200": { "description": "ok" } }
+					}
+				}
+			},
+			"securityDefinitions": {
+				"basicAuth": { "type": "basic" },
+				"oauth": {
+					"type": "oauth2",
+					"flow": "application",
+					"tokenUrl": "https://auth.example.com/token",
+					"scopes": { "read:pets": "Read pets" }
+				}
+			},
+			"security": [{ "basicAuth": [] }],
+			"definitions": {
+				"Pet": {
+					"type": "object",
+					"required": ["name"],
+					"properties": { "name": { "type": "string" }, "tag": { "type": "string", "x-nullable": true } }
+				}
+			}
+		})
+	}
+
+	#[test]
+	fn test_convert() {
+		let document = petstore();
+		assert!(is_swagger2(&document));
+		let converted = convert(document);
+
+		assert_eq!(converted["servers"], json!([{ "url": "https://petstore.example.com/v2" }]));
+		let item = &converted["paths"]["/pets/{petId}"];
+		assert_eq!(item["parameters"][0]["schema"], json!({ "type": "integer" }));
+		assert_eq!(item["get"]["parameters"][0]["example"], "name");
+		assert_eq!(
+			item["get"]["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+			"#/components/schemas/Pet"
+		);
+		let form = &item["post"]["requestBody"]["content"]["multipart/form-data"]["schema"];
+		assert_eq!(form["required"], json!(["name"]));
+		assert_eq!(form["properties"]["photo"], json!({ "type": "string", "format": "binary" }));
+		assert_eq!(item["put"]["requestBody"]["required"], true);
+		assert_eq!(item["put"]["x-httpie-oapi-hidden"], true);
+		assert_eq!(converted["components"]["schemas"]["Pet"]["properties"]["tag"]["nullable"], true);
+
+		let schemes = &converted["components"]["securitySchemes"];
+		assert_eq!(schemes["basicAuth"], json!({ "type": "http", "scheme": "basic" }));
+		assert_eq!(
+			schemes["oauth"]["flows"]["clientCredentials"]["tokenUrl"],
+			"https://auth.example.com/token"
+		);
+		assert_eq!(converted["security"], json!([{ "basicAuth": [] }]));
+		serde_json::from_value::<openapiv3::OpenAPI>(converted).unwrap();
+	}
+
+	#[test]
+	fn test_servers_without_host() {
+		let document = json!({ "swagger": "2.0", "basePath": "/api" });
+		assert_eq!(servers(&document), json!([{ "url": "/api" }]));
+	}
+}
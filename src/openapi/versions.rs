@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use tracing::warn;
+
+use crate::config::Config;
+
+/// Number of spec versions kept per API unless configured otherwise
+pub const DEFAULT_KEEP: usize = 5;
+
+/// A copy of a spec document kept from an earlier download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Version {
+	/// Unix timestamp of the download, which identifies the version, a later
+	/// second for the second of two downloads within the same second
+	pub timestamp: u64,
+	/// Size of the spec document in bytes
+	pub size: u64,
+}
+
+/// Keep a copy of a downloaded spec document, dropping the oldest copies
+/// beyond `keep`
+///
+/// A document that is identical to the newest copy is not kept again.
+pub fn archive(name: &str, spec_json: &str, keep: usize) -> Result<()> {
+	archive_in(&Config::get_versions_dir(name), spec_json, keep, crate::time::now())
+}
+
+fn archive_in(dir: &Path, spec_json: &str, keep: usize, now: u64) -> Result<()> {
+	if keep == 0 {
+		return Ok(());
+	}
+	let versions = list_in(dir);
+	if let Some(newest) = versions.first()
+		&& fs::read_to_string(version_path(dir, newest.timestamp)).is_ok_and(|doc| doc == spec_json)
+	{
+		return Ok(());
+	}
+
+	fs::create_dir_all(dir)
+		.with_context(|| format!("Failed to create versions directory: {}", dir.display()))?;
+	// The new version is the newest even when one was kept within this second
+	let timestamp = versions.first().map_or(now, |newest| now.max(newest.timestamp + 1));
+	let path = version_path(dir, timestamp);
+	fs::write(&path, spec_json)
+		.with_context(|| format!("Failed to write spec version: {}", path.display()))?;
+
+	for version in list_in(dir).into_iter().skip(keep) {
+		let path = version_path(dir, version.timestamp);
+		if let Err(e) = fs::remove_file(&path) {
+			warn!("Failed to remove spec version {}: {}", path.display(), e);
+		}
+	}
+	Ok(())
+}
+
+/// The kept versions of the spec of an API, newest first
+pub fn list(name: &str) -> Vec<Version> { list_in(&Config::get_versions_dir(name)) }
+
+fn list_in(dir: &Path) -> Vec<Version> {
+	let Ok(entries) = fs::read_dir(dir) else {
+		return Vec::new();
+	};
+	let mut versions: Vec<_> = entries
+		.filter_map(|entry| {
+			let entry = entry.ok()?;
+			let timestamp = entry.file_name().to_str()?.strip_suffix(".json")?.parse().ok()?;
+			Some(Version { timestamp, size: entry.metadata().ok()?.len() })
+		})
+		.collect();
+	versions.sort_by_key(|version| std::cmp::Reverse(version.timestamp));
+	versions
+}
+
+/// The version to roll back to from the `current` one, or from the newest
+/// when none is current: the newest of the older versions
+pub fn previous(versions: &[Version], current: Option<u64>) -> Option<u64> {
+	let current = current.or(versions.first().map(|version| version.timestamp))?;
+	versions.iter().map(|version| version.timestamp).find(|&timestamp| timestamp < current)
+}
+
+/// Read a kept version of the spec of an API
+pub fn read(name: &str, timestamp: u64) -> Result<String> {
+	let path = version_path(&Config::get_versions_dir(name), timestamp);
+	if !path.exists() {
+		return Err(anyhow!(
+			"API '{}' has no spec version {}, see `httpie-oapi spec history {}`",
+			name,
+			timestamp,
+			name
+		));
+	}
+	fs::read_to_string(&path)
+		.with_context(|| format!("Failed to read spec version: {}", path.display()))
+}
+
+/// Move the kept versions of an API to another name
+pub fn rename(from: &str, to: &str) -> Result<()> {
+	let dir = Config::get_versions_dir(from);
+	if !dir.exists() {
+		return Ok(());
+	}
+	fs::rename(&dir, Config::get_versions_dir(to))
+		.with_context(|| format!("Failed to move spec versions of '{}'", from))
+}
+
+/// Remove the kept versions of an API
+pub fn remove(name: &str) {
+	let dir = Config::get_versions_dir(name);
+	if dir.exists()
+		&& let Err(e) = fs::remove_dir_all(&dir)
+	{
+		warn!("Failed to remove spec versions {}: {}", dir.display(), e);
+	}
+}
+
+fn version_path(dir: &Path, timestamp: u64) -> PathBuf { dir.join(format!("{}.json", timestamp)) }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// An empty versions directory of a test
+	fn versions_dir(test: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("httpie-oapi-{}-{}", test, std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		dir
+	}
+
+	fn timestamps(dir: &Path) -> Vec<u64> {
+		list_in(dir).into_iter().map(|version| version.timestamp).collect()
+	}
+
+	#[test]
+	fn test_archive() {
+		let dir = versions_dir("archive");
+		archive_in(&dir, "v1", 3, 100).unwrap();
+		// An unchanged spec is not kept again
+		archive_in(&dir, "v1", 3, 200).unwrap();
+		assert_eq!(timestamps(&dir), [100]);
+
+		archive_in(&dir, "v2", 3, 300).unwrap();
+		archive_in(&dir, "v3", 3, 400).unwrap();
+		archive_in(&dir, "v4", 3, 500).unwrap();
+		assert_eq!(timestamps(&dir), [500, 400, 300]);
+		assert_eq!(list_in(&dir)[0].size, 2);
+		assert_eq!(fs::read_to_string(version_path(&dir, 300)).unwrap(), "v2");
+
+		archive_in(&dir, "v5", 0, 600).unwrap();
+		assert_eq!(timestamps(&dir), [500, 400, 300]);
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_archive_same_second() {
+		let dir = versions_dir("archive-same-second");
+		archive_in(&dir, "v1", 5, 100).unwrap();
+		archive_in(&dir, "v2", 5, 100).unwrap();
+		archive_in(&dir, "v3", 5, 100).unwrap();
+		assert_eq!(timestamps(&dir), [102, 101, 100]);
+		assert_eq!(fs::read_to_string(version_path(&dir, 102)).unwrap(), "v3");
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_previous() {
+		let versions = [300, 200, 100].map(|timestamp| Version { timestamp, size: 0 });
+		assert_eq!(previous(&versions, None), Some(200));
+		assert_eq!(previous(&versions, Some(200)), Some(100));
+		assert_eq!(previous(&versions, Some(100)), None);
+		assert_eq!(previous(&[], None), None);
+	}
+}
@@ -0,0 +1,101 @@
+use std::io::{self, IsTerminal};
+
+use serde::{Deserialize, Serialize};
+
+/// Output theme selectable with `theme = "..."` in the config file
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeName {
+	/// Colored methods and unicode symbols
+	#[default]
+	Default,
+	/// Unicode symbols without colors
+	Plain,
+	/// ASCII symbols without colors, for dumb terminals
+	Ascii,
+}
+
+/// Styling applied to human readable output
+///
+/// Output that is piped keeps its plain machine readable form, so themes only
+/// apply when stdout is a terminal.
+pub struct Theme {
+	colors: bool,
+	symbols: Option<Symbols>,
+}
+
+struct Symbols {
+	required: &'static str,
+	optional: &'static str,
+	deprecated: &'static str,
+}
+
+impl Theme {
+	/// Get the theme for stdout, honoring `NO_COLOR` and `TERM=dumb`
+	pub fn for_stdout(name: ThemeName) -> Self {
+		if !io::stdout().is_terminal() {
+			return Self { colors: false, symbols: None };
+		}
+		let dumb = std::env::var("TERM").is_ok_and(|term| term == "dumb");
+		let name = if dumb { ThemeName::Ascii } else { name };
+		let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+		let symbols = match name {
+			ThemeName::Default | ThemeName::Plain => {
+				Symbols { required: "●", optional: "○", deprecated: "⚠ deprecated" }
+			}
+			ThemeName::Ascii => Symbols { required: "*", optional: " ", deprecated: "(deprecated)" },
+		};
+		Self { colors: name == ThemeName::Default && !no_color, symbols: Some(symbols) }
+	}
+
+	/// Style an HTTP method name
+	pub fn method(&self, method: &str) -> String {
+		if !self.colors {
+			return method.to_string();
+		}
+		let color = match method {
+			"GET" => "32",
+			"POST" => "33",
+			"PUT" => "34",
+			"PATCH" => "36",
+			"DELETE" => "31",
+			_ => "35",
+		};
+		format!("\x1b[1;{color}m{method}\x1b[0m")
+	}
+
+	/// Prefix marking a parameter as required or optional, if the theme has one
+	pub fn required_marker(&self, required: bool) -> Option<&'static str> {
+		self.symbols.as_ref().map(|symbols| if required { symbols.required } else { symbols.optional })
+	}
+
+	/// Marker of deprecated endpoints, if the theme has one
+	pub fn deprecated_marker(&self) -> Option<String> {
+		self.symbols.as_ref().map(|symbols| self.dim(symbols.deprecated))
+	}
+
+	/// Style secondary text such as descriptions
+	pub fn dim(&self, text: &str) -> String {
+		if self.colors { format!("\x1b[2m{text}\x1b[0m") } else { text.to_string() }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_plain_output_is_unstyled() {
+		let theme = Theme { colors: false, symbols: None };
+		assert_eq!(theme.method("GET"), "GET");
+		assert_eq!(theme.required_marker(true), None);
+		assert_eq!(theme.deprecated_marker(), None);
+		assert_eq!(theme.dim("summary"), "summary");
+	}
+
+	#[test]
+	fn test_colored_method() {
+		let theme = Theme { colors: true, symbols: None };
+		assert_eq!(theme.method("DELETE"), "\x1b[1;31mDELETE\x1b[0m");
+	}
+}
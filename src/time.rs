@@ -0,0 +1,62 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, anyhow};
+
+/// Current time as seconds since the Unix epoch
+pub fn now() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0) }
+
+/// Format the time elapsed since `timestamp` for humans, e.g. `3 days ago`
+pub fn format_age(timestamp: u64) -> String {
+	let secs = now().saturating_sub(timestamp);
+	let (value, unit) = match secs {
+		0..60 => return "just now".to_string(),
+		60..3600 => (secs / 60, "minute"),
+		3600..86400 => (secs / 3600, "hour"),
+		_ => (secs / 86400, "day"),
+	};
+	let plural = if value == 1 { "" } else { "s" };
+	format!("{value} {unit}{plural} ago")
+}
+
+/// Parse a short duration like `30m`, `2h`, `1d` or `1w` into seconds
+pub fn parse_duration(s: &str) -> Result<u64> {
+	let s = s.trim();
+	let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+	let (value, unit) = s.split_at(split);
+	let value: u64 = value.parse().map_err(|_| anyhow!("Invalid duration '{}'", s))?;
+	let factor = match unit {
+		"s" | "" => 1,
+		"m" => 60,
+		"h" => 3600,
+		"d" => 86400,
+		"w" => 7 * 86400,
+		_ => return Err(anyhow!("Invalid duration unit in '{}', expected s, m, h, d or w", s)),
+	};
+	value.checked_mul(factor).ok_or_else(|| anyhow!("Duration '{}' is too long", s))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_duration() {
+		assert_eq!(parse_duration("45").unwrap(), 45);
+		assert_eq!(parse_duration("30m").unwrap(), 1800);
+		assert_eq!(parse_duration("2h").unwrap(), 7200);
+		assert_eq!(parse_duration("1d").unwrap(), 86400);
+		assert_eq!(parse_duration("1w").unwrap(), 604800);
+		assert!(parse_duration("d").is_err());
+		assert!(parse_duration("3y").is_err());
+		assert!(parse_duration("99999999999999999999").is_err());
+		assert!(parse_duration("99999999999999w").is_err());
+	}
+
+	#[test]
+	fn test_format_age() {
+		assert_eq!(format_age(now()), "just now");
+		assert_eq!(format_age(now() - 120), "2 minutes ago");
+		assert_eq!(format_age(now() - 3600), "1 hour ago");
+		assert_eq!(format_age(now() - 3 * 86400), "3 days ago");
+	}
+}
@@ -2,6 +2,8 @@
 pub struct Tokens {
 	tokens: Vec<Token>,
 	cursor_pos: usize,
+	/// Empty token at the cursor when it is not inside or next to a token
+	new_token: Option<Token>,
 }
 
 #[derive(Debug, Clone)]
@@ -23,17 +25,30 @@ impl Tokens {
 			tokens.push(Token { text: token, start, end });
 			current_pos = end;
 		}
-		Self { tokens, cursor_pos }
+
+		// Shells may report a cursor past the end of the line
+		let cursor_pos = cursor_pos.min(line.len());
+		let touches_token =
+			tokens.iter().any(|token| cursor_pos >= token.start && cursor_pos <= token.end);
+		let new_token = (!touches_token).then(|| Token {
+			text:  String::new(),
+			start: cursor_pos,
+			end:   cursor_pos,
+		});
+		Self { tokens, cursor_pos, new_token }
 	}
 
 	/// Get the token at the current cursor position
 	///
-	/// # Returns
-	///
-	/// Returns a reference to the token that contains the cursor position,
-	/// or None if no token contains the cursor position
+	/// A cursor inside or at either end of a token is on that token. A cursor
+	/// anywhere else, e.g. after trailing whitespace, starts a new empty token
+	/// at the cursor position.
 	pub fn current_token(&self) -> Option<&Token> {
-		self.tokens.iter().find(|token| self.cursor_pos >= token.start && self.cursor_pos <= token.end)
+		self
+			.tokens
+			.iter()
+			.find(|token| self.cursor_pos >= token.start && self.cursor_pos <= token.end)
+			.or(self.new_token.as_ref())
 	}
 
 	/// Find a token that starts with the given base_url
@@ -53,6 +68,35 @@ impl Tokens {
 	pub fn has_token_starting_with(&self, text: &str) -> bool {
 		self.tokens.iter().any(|t| t.text.starts_with(text))
 	}
+
+	/// Get the positional arguments after the program, i.e. the tokens not
+	/// starting with `-`
+	pub fn positionals(&self) -> Vec<&Token> {
+		self.tokens.iter().skip(1).filter(|token| !token.text.starts_with('-')).collect()
+	}
+
+	/// Get the value of a long option given as `--name=value` or `--name value`
+	pub fn option_value(&self, name: &str) -> Option<&str> {
+		self.tokens.iter().enumerate().find_map(|(i, token)| {
+			let rest = token.text.strip_prefix(name)?;
+			match rest.strip_prefix('=') {
+				Some(value) => Some(value),
+				None if rest.is_empty() => self.tokens.get(i + 1).map(|next| next.text.as_str()),
+				None => None,
+			}
+		})
+	}
+
+	/// Get the typed value of a long option when the cursor is on it, given as
+	/// `--name=value` or `--name value`
+	pub fn current_option_value(&self, name: &str) -> Option<&str> {
+		let current = self.current_token()?;
+		if let Some(value) = current.text.strip_prefix(name).and_then(|rest| rest.strip_prefix('=')) {
+			return Some(value);
+		}
+		let previous = self.tokens.iter().rev().find(|token| token.end < current.start)?;
+		(previous.text == name).then_some(current.text.as_str())
+	}
 }
 
 #[cfg(test)]
@@ -122,4 +166,61 @@ mod tests {
 		let tokens = tokens!("http example.com|");
 		assert!(tokens.find_token_starting_with("http://api1.com").is_none());
 	}
+
+	#[test]
+	fn test_option_value() {
+		let tokens = tokens!("http --tag=users example.com|");
+		assert_eq!(tokens.option_value("--tag"), Some("users"));
+
+		let tokens = tokens!("http --tag users example.com|");
+		assert_eq!(tokens.option_value("--tag"), Some("users"));
+
+		let tokens = tokens!("http --tags=users example.com|");
+		assert!(tokens.option_value("--tag").is_none());
+	}
+
+	#[test]
+	fn test_current_option_value() {
+		let tokens = tokens!("http --tag us|");
+		assert_eq!(tokens.current_option_value("--tag"), Some("us"));
+
+		let tokens = tokens!("http --tag |");
+		assert_eq!(tokens.current_option_value("--tag"), Some(""));
+
+		let tokens = tokens!("http --tag=us|");
+		assert_eq!(tokens.current_option_value("--tag"), Some("us"));
+
+		let tokens = tokens!("http --tag users |");
+		assert!(tokens.current_option_value("--tag").is_none());
+	}
+
+	#[test]
+	fn test_positionals() {
+		let tokens = tokens!("http --verbose GET example.com|");
+		let positionals: Vec<_> = tokens.positionals().iter().map(|t| t.text.as_str()).collect();
+		assert_eq!(positionals, vec!["GET", "example.com"]);
+	}
+
+	#[test]
+	fn test_current_token_after_trailing_space() {
+		let tokens = tokens!("http example.com |");
+		let token = tokens.current_token().unwrap();
+		assert_eq!(token.text, "");
+		assert_eq!(token.start, 17);
+	}
+
+	#[test]
+	fn test_current_token_cursor_past_end() {
+		let tokens = super::Tokens::new("http example.com", 40);
+		assert_eq!(tokens.current_token().unwrap().text, "example.com");
+
+		let tokens = super::Tokens::new("http example.com ", 40);
+		assert_eq!(tokens.current_token().unwrap().text, "");
+	}
+
+	#[test]
+	fn test_current_token_empty_line() {
+		let tokens = tokens!("|");
+		assert_eq!(tokens.current_token().unwrap().text, "");
+	}
 }
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Config;
+
+/// Where the values of a linked param come from: a GET endpoint of the API
+/// and a selector picking the values out of its JSON response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueSource {
+	/// Path of the endpoint relative to the base URL, e.g. `/projects`
+	pub path: String,
+	/// Selector of the values in the response, e.g. `$[*].id`
+	pub select: String,
+}
+
+/// Values fetched for the linked params of one API, keyed by param name
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ValueCache {
+	#[serde(default)]
+	params: BTreeMap<String, CachedValues>,
+}
+
+/// Values of one linked param and when they were fetched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedValues {
+	pub values: Vec<String>,
+	/// Unix timestamp of the fetch
+	pub fetched: u64,
+}
+
+impl ValueCache {
+	pub fn load(api: &str) -> Result<Self> {
+		let path = Config::get_values_cache_path(api);
+		if !path.exists() {
+			return Ok(Self::default());
+		}
+		let content = fs::read_to_string(&path)
+			.with_context(|| format!("Failed to read values cache: {}", path.display()))?;
+		serde_json::from_str(&content)
+			.with_context(|| format!("Failed to parse values cache: {}", path.display()))
+	}
+
+	pub fn save(&self, api: &str) -> Result<()> {
+		let path = Config::get_values_cache_path(api);
+		fs::write(&path, serde_json::to_string(self)?)
+			.with_context(|| format!("Failed to write values cache: {}", path.display()))
+	}
+
+	pub fn get(&self, param: &str) -> Option<&CachedValues> { self.params.get(param) }
+
+	pub fn insert(&mut self, param: &str, values: Vec<String>) {
+		let cached = CachedValues { values, fetched: crate::time::now() };
+		self.params.insert(param.to_string(), cached);
+	}
+}
+
+/// Pick values out of a JSON document with a selector made of `.field`,
+/// `[index]` and `[*]` steps after the `$` root
+///
+/// Strings are taken as they are, numbers and booleans are formatted, other
+/// values are skipped.
+pub fn select(document: &Value, selector: &str) -> Result<Vec<String>> {
+	let invalid = || anyhow!("Invalid selector '{}', expected e.g. '$[*].id'", selector);
+	let mut rest = selector.strip_prefix('$').unwrap_or(selector);
+	let mut nodes = vec![document];
+	while !rest.is_empty() {
+		if let Some(step) = rest.strip_prefix('[') {
+			let (index, after) = step.split_once(']').ok_or_else(invalid)?;
+			nodes = match index {
+				"*" => nodes
+					.into_iter()
+					.flat_map(|node| match node {
+						Value::Array(items) => items.iter().collect(),
+						Value::Object(fields) => fields.values().collect(),
+						_ => Vec::new(),
+					})
+					.collect(),
+				index => {
+					let index: usize = index.parse().map_err(|_| invalid())?;
+					nodes.into_iter().filter_map(|node| node.get(index)).collect()
+				}
+			};
+			rest = after;
+		} else if let Some(step) = rest.strip_prefix('.') {
+			let end = step.find(['.', '[']).unwrap_or(step.len());
+			let (field, after) = step.split_at(end);
+			if field.is_empty() {
+				return Err(invalid());
+			}
+			nodes = nodes.into_iter().filter_map(|node| node.get(field)).collect();
+			rest = after;
+		} else {
+			return Err(invalid());
+		}
+	}
+	Ok(
+		nodes
+			.into_iter()
+			.filter_map(|node| match node {
+				Value::String(value) => Some(value.clone()),
+				Value::Number(value) => Some(value.to_string()),
+				Value::Bool(value) => Some(value.to_string()),
+				_ => None,
+			})
+			.collect(),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::*;
+
+	#[test]
+	fn test_select() {
+		let projects = json!([{ "id": 1, "name": "api" }, { "id": "p-2" }, { "name": "no id" }]);
+		assert_eq!(select(&projects, "$[*].id").unwrap(), ["1", "p-2"]);
+		assert_eq!(select(&projects, "$[0].name").unwrap(), ["api"]);
+
+		let page = json!({ "data": { "items": [{ "slug": "a" }, { "slug": "b" }] } });
+		assert_eq!(select(&page, "$.data.items[*].slug").unwrap(), ["a", "b"]);
+		assert!(select(&page, "$.data.missing[*]").unwrap().is_empty());
+		assert!(select(&page, "$data").is_err());
+		assert!(select(&page, "$[x]").is_err());
+	}
+}
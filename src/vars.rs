@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// Persisted path variable values, shared by all APIs or scoped to one
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VarStore {
+	/// Variables available to every API
+	#[serde(default)]
+	global: BTreeMap<String, String>,
+	/// Variables scoped to a single API, keyed by API name
+	#[serde(default)]
+	apis: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl VarStore {
+	pub fn load() -> Result<Self> {
+		let path = Config::get_vars_path();
+		if !path.exists() {
+			return Ok(Self::default());
+		}
+		let content = fs::read_to_string(&path)
+			.with_context(|| format!("Failed to read variables file: {}", path.display()))?;
+		toml::from_str(&content)
+			.with_context(|| format!("Failed to parse variables file: {}", path.display()))
+	}
+
+	pub fn save(&self) -> Result<()> {
+		let path = Config::get_vars_path();
+		let content = toml::to_string_pretty(self).context("Failed to serialize variables")?;
+		fs::write(&path, content)
+			.with_context(|| format!("Failed to write variables file: {}", path.display()))
+	}
+
+	/// Set a variable of an API, or of every API when none is given
+	pub fn set(&mut self, api: Option<&str>, name: &str, value: &str) {
+		let vars = match api {
+			Some(api) => self.apis.entry(api.to_string()).or_default(),
+			None => &mut self.global,
+		};
+		vars.insert(name.to_string(), value.to_string());
+	}
+
+	/// Remove a variable of an API, or one shared by every API when none is
+	/// given, returning whether it was set
+	pub fn unset(&mut self, api: Option<&str>, name: &str) -> bool {
+		let Some(api) = api else {
+			return self.global.remove(name).is_some();
+		};
+		let Some(vars) = self.apis.get_mut(api) else {
+			return false;
+		};
+		let removed = vars.remove(name).is_some();
+		if vars.is_empty() {
+			self.apis.remove(api);
+		}
+		removed
+	}
+
+	/// Forget the variables scoped to an API, returning whether it had any
+	pub fn remove_api(&mut self, api: &str) -> bool { self.apis.remove(api).is_some() }
+
+	/// Scope the variables of an API to its new name
+	pub fn rename_api(&mut self, old: &str, new: &str) -> bool {
+		let Some(vars) = self.apis.remove(old) else {
+			return false;
+		};
+		self.apis.insert(new.to_string(), vars);
+		true
+	}
+
+	/// All variables with the API they are scoped to, the shared ones first
+	pub fn list(&self) -> Vec<(Option<&str>, &str, &str)> {
+		let global = self.global.iter().map(|(name, value)| (None, name.as_str(), value.as_str()));
+		let scoped = self.apis.iter().flat_map(|(api, vars)| {
+			vars.iter().map(move |(name, value)| (Some(api.as_str()), name.as_str(), value.as_str()))
+		});
+		global.chain(scoped).collect()
+	}
+
+	/// Get the value of a variable, preferring the API scoped one
+	pub fn get(&self, api: &str, name: &str) -> Option<&str> {
+		self
+			.apis
+			.get(api)
+			.and_then(|vars| vars.get(name))
+			.or_else(|| self.global.get(name))
+			.map(String::as_str)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_scoped_vars() {
+		let mut vars = VarStore::default();
+		vars.set(None, "tenantId", "acme");
+		vars.set(None, "id", "1");
+		vars.set(Some("shop"), "id", "42");
+		assert_eq!(vars.get("shop", "id"), Some("42"));
+		assert_eq!(vars.get("shop", "tenantId"), Some("acme"));
+		assert_eq!(vars.get("tracker", "id"), Some("1"));
+		assert_eq!(vars.list(), [
+			(None, "id", "1"),
+			(None, "tenantId", "acme"),
+			(Some("shop"), "id", "42")
+		]);
+
+		assert!(vars.unset(Some("shop"), "id"));
+		assert!(!vars.unset(Some("shop"), "id"));
+		assert_eq!(vars.get("shop", "id"), Some("1"));
+		assert!(vars.apis.is_empty());
+
+		vars.set(Some("shop"), "id", "42");
+		assert!(vars.rename_api("shop", "store"));
+		assert_eq!(vars.get("store", "id"), Some("42"));
+		assert_eq!(vars.get("shop", "id"), Some("1"));
+		assert!(vars.remove_api("store"));
+		assert!(!vars.remove_api("store"));
+		assert_eq!(vars.get("store", "id"), Some("1"));
+	}
+}